@@ -0,0 +1,139 @@
+//! the records/sec of the logger from `log::info!` until the appender wrote the record,
+//! compare two releases with `cargo bench --bench throughput -- --save-baseline v1`
+//! and `cargo bench --bench throughput -- --baseline v1`.
+//! the packers are measured if their feature is enabled,for example
+//! `cargo bench --bench throughput --features zip,lz4,gzip`
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use fast_log::appender::{FastLogRecord, LogAppender};
+use fast_log::consts::LogSize;
+use fast_log::plugin::file_split::RollingType;
+use fast_log::plugin::packer::LogPacker;
+use fast_log::Config;
+use std::time::{Duration, Instant};
+
+fn bench_dir(name: &str) -> String {
+    let dir = format!("target/bench_logs/{}/", name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// log `iters` records and wait until they are written
+fn throughput(c: &mut Criterion, group: &str, name: &str, config: Config) {
+    let mut group = c.benchmark_group(group);
+    group.throughput(Throughput::Elements(1));
+    let _guard = fast_log::init_scoped(config).unwrap();
+    group.bench_function(name, |b| {
+        b.iter_custom(|iters| {
+            let now = Instant::now();
+            for index in 0..iters {
+                log::info!("Commencing yak shaving{}", index);
+            }
+            fast_log::flush().unwrap().wait();
+            now.elapsed()
+        })
+    });
+    group.finish();
+}
+
+fn bench_noop(c: &mut Criterion) {
+    throughput(c, "appender", "noop", fast_log::bench_mode());
+}
+
+/// the output of the console appender is mixed with the report of criterion,
+/// run it alone with `cargo bench --bench throughput -- console > /dev/null` and read target/criterion
+fn bench_console(c: &mut Criterion) {
+    throughput(c, "appender", "console", Config::new().console());
+}
+
+fn bench_file(c: &mut Criterion) {
+    let dir = bench_dir("file");
+    throughput(
+        c,
+        "appender",
+        "file",
+        Config::new().file(&format!("{}bench.log", dir)),
+    );
+}
+
+fn bench_file_split(c: &mut Criterion) {
+    throughput(
+        c,
+        "file_split",
+        "log",
+        Config::new().file_split(
+            &bench_dir("log"),
+            LogSize::MB(1),
+            RollingType::KeepNum(5),
+            LogPacker {},
+        ),
+    );
+    #[cfg(feature = "zip")]
+    throughput(
+        c,
+        "file_split",
+        "zip",
+        Config::new().file_split(
+            &bench_dir("zip"),
+            LogSize::MB(1),
+            RollingType::KeepNum(5),
+            fast_log::plugin::packer::ZipPacker {},
+        ),
+    );
+    #[cfg(feature = "lz4")]
+    throughput(
+        c,
+        "file_split",
+        "lz4",
+        Config::new().file_split(
+            &bench_dir("lz4"),
+            LogSize::MB(1),
+            RollingType::KeepNum(5),
+            fast_log::plugin::packer::LZ4Packer {},
+        ),
+    );
+    #[cfg(feature = "gzip")]
+    throughput(
+        c,
+        "file_split",
+        "gzip",
+        Config::new().file_split(
+            &bench_dir("gzip"),
+            LogSize::MB(1),
+            RollingType::KeepNum(5),
+            fast_log::plugin::packer::GZipPacker {},
+        ),
+    );
+}
+
+/// the appender taking 50us for each batch,so the producers outrun it
+struct SlowAppender {}
+
+impl LogAppender for SlowAppender {
+    fn do_logs(&self, _records: &[FastLogRecord]) {
+        std::thread::sleep(Duration::from_micros(50));
+    }
+}
+
+/// the producers block on the full channel,the smaller the channel the smaller the batches
+fn bench_saturation(c: &mut Criterion) {
+    for chan_len in [64, 1024, 65536] {
+        throughput(
+            c,
+            "saturation",
+            &format!("chan_len_{}", chan_len),
+            Config::new()
+                .chan_len(Some(chan_len))
+                .custom(SlowAppender {}),
+        );
+    }
+}
+
+criterion_group!(
+    benches,
+    bench_noop,
+    bench_console,
+    bench_file,
+    bench_file_split,
+    bench_saturation
+);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+use fast_log::appender::{FastLogRecord, LogAppender};
+use fast_log::bencher::TPS;
+use fast_log::config::Config;
+use fast_log::ProducerMode;
+use std::time::Instant;
+
+/// compare the producer modes under many threads
+/// cargo run --release --package example --bin bench_test_sharded
+fn main() {
+    struct BenchRecvLog {}
+    impl LogAppender for BenchRecvLog {
+        fn do_logs(&self, _records: &[FastLogRecord]) {
+            //nothing
+        }
+    }
+    let threads: u64 = 32;
+    let per_thread: u64 = 100000;
+    for mode in [ProducerMode::Channel, ProducerMode::Sharded] {
+        let guard =
+            fast_log::init_scoped(Config::new().producer_mode(mode).custom(BenchRecvLog {}))
+                .unwrap();
+        let now = Instant::now();
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                std::thread::spawn(move || {
+                    for index in 0..per_thread {
+                        log::info!("Commencing yak shaving{}", index);
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        //wait log finish write all
+        log::logger().flush();
+        println!("{:?} {} threads:", mode, threads);
+        now.time(threads * per_thread);
+        now.tps(threads * per_thread);
+        drop(guard);
+    }
+}
@@ -0,0 +1,494 @@
+//! the record formatting and filtering core of fast_log,without the worker,the channel and the system clock,
+//! the time comes from a [`Clock`] and the bytes go to a [`Sink`],for a custom clock and transport.
+//! `FastLogFormat` and `EnvFilter` of fast_log are built on it,fast_log re-exports it as `fast_log::embedded`.
+//! the crate is `no_std`,it needs `alloc` only.
+//! for example:
+//! ```rust
+//! use core::time::Duration;
+//! use fast_log_core::{Clock, LogCore, Sink};
+//!
+//! struct Uptime;
+//! impl Clock for Uptime {
+//!     fn now(&self) -> Duration {
+//!         Duration::from_secs(1_660_902_827)
+//!     }
+//! }
+//!
+//! struct Uart(Vec<u8>);
+//! impl Sink for Uart {
+//!     fn write(&mut self, bytes: &[u8]) {
+//!         self.0.extend_from_slice(bytes);
+//!     }
+//! }
+//!
+//! let mut core = LogCore::new(Uptime, Uart(vec![]), "info,app::radio=warn").unwrap();
+//! core.log(&log::Record::builder()
+//!     .level(log::Level::Info)
+//!     .target("app")
+//!     .module_path(Some("app"))
+//!     .args(format_args!("booted"))
+//!     .build());
+//! assert_eq!(core.sink().0, b"2022-08-19 09:53:47.000000000 INFO app - booted\n");
+//! ```
+#![no_std]
+#![forbid(unsafe_code)]
+#![allow(unused_must_use)]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Write};
+use core::str::FromStr;
+use core::time::Duration;
+use log::{Level, LevelFilter, Record};
+
+/// the wall clock,the local time since the unix epoch
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+/// the byte transport(uart,rtt,usb...)
+pub trait Sink {
+    fn write(&mut self, bytes: &[u8]);
+
+    fn flush(&mut self) {}
+}
+
+/// the fields of a text line,see [`write_line`]
+pub struct Line<'a> {
+    /// the local time since the unix epoch in seconds and nanoseconds,negative before the epoch
+    pub secs: i64,
+    pub nanos: u32,
+    pub level: Level,
+    /// the text of the level,for example "INFO"
+    pub level_name: &'a str,
+    /// the name or id of the producing thread,empty to omit it
+    pub thread: &'a str,
+    pub module_path: &'a str,
+    pub args: &'a dyn Display,
+    pub file: &'a str,
+    pub line: Option<u32>,
+    /// the sequence number of the record,None to omit it
+    pub seq: Option<u64>,
+    pub trace_id: Option<u128>,
+    pub span_id: Option<u64>,
+    /// the structured key-values,written as `key=value` after the args
+    pub kv: &'a [(String, KvValue)],
+}
+
+/// write the date as `2022-08-19 09:53:47` and `digits` of the fractional seconds
+pub fn write_date(buf: &mut String, secs: i64, nanos: u32, digits: usize) {
+    let (year, mon, day) = civil_from_days(secs.div_euclid(86400));
+    let rem = secs.rem_euclid(86400);
+    write!(
+        buf,
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        mon,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    );
+    if digits > 0 {
+        let digits = digits.min(9);
+        write!(
+            buf,
+            ".{:0w$}",
+            nanos / 10u32.pow(9 - digits as u32),
+            w = digits
+        );
+    }
+}
+
+/// the bytes of [`write_date`] without the formatter,for the hot path
+pub fn write_date_bytes(buf: &mut Vec<u8>, secs: i64, nanos: u32, digits: usize) {
+    let (year, mon, day) = civil_from_days(secs.div_euclid(86400));
+    if !(0..=9999).contains(&year) {
+        let mut s = String::with_capacity(36);
+        write_date(&mut s, secs, nanos, digits);
+        buf.extend_from_slice(s.as_bytes());
+        return;
+    }
+    let rem = secs.rem_euclid(86400) as u32;
+    let year = year as u32;
+    buf.extend_from_slice(&[
+        b'0' + (year / 1000) as u8,
+        b'0' + (year / 100 % 10) as u8,
+        b'0' + (year / 10 % 10) as u8,
+        b'0' + (year % 10) as u8,
+        b'-',
+    ]);
+    for (v, sep) in [
+        (mon, b'-'),
+        (day, b' '),
+        (rem / 3600, b':'),
+        (rem % 3600 / 60, b':'),
+    ] {
+        buf.extend_from_slice(&[b'0' + (v / 10) as u8, b'0' + (v % 10) as u8, sep]);
+    }
+    let sec = rem % 60;
+    buf.extend_from_slice(&[b'0' + (sec / 10) as u8, b'0' + (sec % 10) as u8]);
+    if digits > 0 {
+        let digits = digits.min(9);
+        buf.push(b'.');
+        let mut div = 100_000_000;
+        for _ in 0..digits {
+            buf.push(b'0' + (nanos / div % 10) as u8);
+            div /= 10;
+        }
+    }
+}
+
+/// the (year,month,day) of the days since the unix epoch,see http://howardhinnant.github.io/date_algorithms.html
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let mon = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if mon <= 2 { 1 } else { 0 };
+    (year, mon, day)
+}
+
+/// the days since the unix epoch of the date,the inverse of `civil_from_days`
+pub fn days_from_civil(year: i64, mon: u32, day: u32) -> i64 {
+    let year = if mon <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if mon > 2 { mon - 3 } else { mon + 9 } as i64;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// write `{date} {level} [{thread}] seq={seq} trace_id={trace_id} span_id={span_id} {module_path} - {args} {key}={value}`,
+/// and `  {file}:{line}` if the level is at or above `display_line_level`
+pub fn write_line(buf: &mut String, line: &Line, digits: usize, display_line_level: LevelFilter) {
+    write_date(buf, line.secs, line.nanos, digits);
+    write_fields(buf, line, display_line_level);
+}
+
+/// write the fields of [`write_line`] after the date,` {level} [{thread}] ...`
+pub fn write_fields(buf: &mut String, line: &Line, display_line_level: LevelFilter) {
+    write!(buf, " {} ", line.level_name);
+    if !line.thread.is_empty() {
+        write!(buf, "[{}] ", line.thread);
+    }
+    if let Some(seq) = line.seq {
+        write!(buf, "seq={} ", seq);
+    }
+    if let Some(trace_id) = line.trace_id {
+        write!(buf, "trace_id={:032x} ", trace_id);
+    }
+    if let Some(span_id) = line.span_id {
+        write!(buf, "span_id={:016x} ", span_id);
+    }
+    write!(buf, "{} - {}", line.module_path, line.args);
+    for (k, v) in line.kv {
+        write!(buf, " {}={}", k, v);
+    }
+    if line.level.to_level_filter() <= display_line_level && !line.file.is_empty() {
+        write!(buf, "  {}:{}", line.file, line.line.unwrap_or_default());
+    }
+    buf.push('\n');
+}
+
+/// the `RUST_LOG` style directives,see `EnvFilter`
+pub struct Directives {
+    /// sorted by the module length
+    pub directives: Vec<(Option<String>, LevelFilter)>,
+    /// keep only the messages containing it
+    pub message: Option<String>,
+}
+
+impl Directives {
+    /// the most verbose level of the directives
+    pub fn max_level(&self) -> LevelFilter {
+        max_level(&self.directives)
+    }
+
+    /// the level of the longest module prefix of `target`,see [`level_of`]
+    pub fn level_of(&self, target: &str) -> LevelFilter {
+        level_of(&self.directives, target)
+    }
+
+    /// is the record enabled by the level and the message
+    pub fn enabled(&self, record: &Record) -> bool {
+        enabled(&self.directives, self.message.as_deref(), record)
+    }
+}
+
+impl FromStr for Directives {
+    /// the invalid directive
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (spec, message) = match spec.split_once('/') {
+            Some((spec, message)) => (spec, Some(message.to_string())),
+            None => (spec, None),
+        };
+        let mut directives = Vec::new();
+        for d in spec.split(',').map(|d| d.trim()).filter(|d| !d.is_empty()) {
+            let directive = match d.split_once('=') {
+                Some((module, level)) => (
+                    Some(module.trim().to_string()),
+                    LevelFilter::from_str(level.trim()).map_err(|_| d.to_string())?,
+                ),
+                None => match LevelFilter::from_str(d) {
+                    Ok(level) => (None, level),
+                    Err(_) => (Some(d.to_string()), LevelFilter::Trace),
+                },
+            };
+            directives.push(directive);
+        }
+        //stable sort,so the last of the same module wins
+        directives.sort_by_key(|(m, _)| m.as_ref().map(|m| m.len() + 1).unwrap_or(0));
+        Ok(Self {
+            directives,
+            message,
+        })
+    }
+}
+
+/// the most verbose level of the directives
+pub fn max_level(directives: &[(Option<String>, LevelFilter)]) -> LevelFilter {
+    directives
+        .iter()
+        .map(|(_, level)| *level)
+        .max()
+        .unwrap_or(LevelFilter::Off)
+}
+
+/// the level of the longest module prefix of `target`,the module is `target` or its parent at a `::`
+pub fn level_of(directives: &[(Option<String>, LevelFilter)], target: &str) -> LevelFilter {
+    for (module, level) in directives.iter().rev() {
+        match module {
+            None => return *level,
+            Some(m) if contains(m, target) => return *level,
+            _ => {}
+        }
+    }
+    LevelFilter::Off
+}
+
+/// is `target` the module or in the module,`hyper` contains `hyper::client` but not `hyperx`
+fn contains(module: &str, target: &str) -> bool {
+    match target.strip_prefix(module) {
+        Some(rest) => rest.is_empty() || rest.starts_with("::"),
+        None => false,
+    }
+}
+
+/// is the record enabled by the level of the directives and the message
+pub fn enabled(
+    directives: &[(Option<String>, LevelFilter)],
+    message: Option<&str>,
+    record: &Record,
+) -> bool {
+    if record.level() > level_of(directives, record.target()) {
+        return false;
+    }
+    match message {
+        Some(m) => record.args().to_string().contains(m),
+        None => true,
+    }
+}
+
+/// filter,format and write records into a sink,
+/// wrap it in the lock of the target(for example a critical section mutex) to implement `log::Log`
+pub struct LogCore<C: Clock, S: Sink> {
+    clock: C,
+    sink: S,
+    pub directives: Directives,
+    /// the digits of the fractional seconds,default is 9
+    pub digits: usize,
+    /// show the file and line at or above the level,default is Warn
+    pub display_line_level: LevelFilter,
+    buf: String,
+}
+
+impl<C: Clock, S: Sink> LogCore<C, S> {
+    /// `directives` is the `RUST_LOG` style filter,for example "info,app::radio=warn"
+    pub fn new(clock: C, sink: S, directives: &str) -> Result<Self, String> {
+        Ok(Self {
+            clock,
+            sink,
+            directives: directives.parse()?,
+            digits: 9,
+            display_line_level: LevelFilter::Warn,
+            buf: String::new(),
+        })
+    }
+
+    pub fn enabled(&self, record: &Record) -> bool {
+        self.directives.enabled(record)
+    }
+
+    /// format and write the record if enabled
+    pub fn log(&mut self, record: &Record) {
+        if !self.enabled(record) {
+            return;
+        }
+        let now = self.clock.now();
+        self.buf.clear();
+        write_line(
+            &mut self.buf,
+            &Line {
+                secs: now.as_secs() as i64,
+                nanos: now.subsec_nanos(),
+                level: record.level(),
+                level_name: record.level().as_str(),
+                thread: "",
+                module_path: record.module_path().unwrap_or_default(),
+                args: record.args(),
+                file: record.file().unwrap_or_default(),
+                line: record.line(),
+                seq: None,
+                trace_id: None,
+                span_id: None,
+                kv: &[],
+            },
+            self.digits,
+            self.display_line_level,
+        );
+        self.sink.write(self.buf.as_bytes());
+    }
+
+    pub fn flush(&mut self) {
+        self.sink.flush();
+    }
+
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    pub fn sink_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+}
+
+/// the typed value of a structured key-value,see `FastLogRecord::kv` of fast_log
+#[derive(Clone, Debug, PartialEq)]
+pub enum KvValue {
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Str(String),
+}
+
+impl KvValue {
+    /// the value of the log crate,the booleans and numbers keep their type,the others are strings
+    pub fn from_value(value: &log::kv::Value) -> Self {
+        if let Some(v) = value.to_bool() {
+            Self::Bool(v)
+        } else if let Some(v) = value.to_i64() {
+            Self::I64(v)
+        } else if let Some(v) = value.to_u64() {
+            Self::U64(v)
+        } else if let Some(v) = value.to_f64() {
+            Self::F64(v)
+        } else {
+            Self::Str(value.to_string())
+        }
+    }
+
+    /// append the value as JSON,the strings and the non-finite numbers are quoted
+    pub fn write_json(&self, buf: &mut String) {
+        match self {
+            KvValue::Str(v) => {
+                buf.push('"');
+                escape_json(buf, v);
+                buf.push('"');
+            }
+            KvValue::F64(v) if !v.is_finite() => {
+                write!(buf, "\"{}\"", v);
+            }
+            v => {
+                write!(buf, "{}", v);
+            }
+        }
+    }
+}
+
+impl Display for KvValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            KvValue::Bool(v) => v.fmt(f),
+            KvValue::I64(v) => v.fmt(f),
+            KvValue::U64(v) => v.fmt(f),
+            KvValue::F64(v) => v.fmt(f),
+            KvValue::Str(v) => v.fmt(f),
+        }
+    }
+}
+
+impl log::kv::ToValue for KvValue {
+    fn to_value(&self) -> log::kv::Value<'_> {
+        match self {
+            KvValue::Bool(v) => log::kv::Value::from(*v),
+            KvValue::I64(v) => log::kv::Value::from(*v),
+            KvValue::U64(v) => log::kv::Value::from(*v),
+            KvValue::F64(v) => log::kv::Value::from(*v),
+            KvValue::Str(v) => log::kv::Value::from(v.as_str()),
+        }
+    }
+}
+
+impl From<bool> for KvValue {
+    fn from(v: bool) -> Self {
+        KvValue::Bool(v)
+    }
+}
+
+impl From<i64> for KvValue {
+    fn from(v: i64) -> Self {
+        KvValue::I64(v)
+    }
+}
+
+impl From<u64> for KvValue {
+    fn from(v: u64) -> Self {
+        KvValue::U64(v)
+    }
+}
+
+impl From<f64> for KvValue {
+    fn from(v: f64) -> Self {
+        KvValue::F64(v)
+    }
+}
+
+impl From<String> for KvValue {
+    fn from(v: String) -> Self {
+        KvValue::Str(v)
+    }
+}
+
+impl From<&str> for KvValue {
+    fn from(v: &str) -> Self {
+        KvValue::Str(v.to_string())
+    }
+}
+
+/// append `v` to `buf` as the content of a json string
+pub fn escape_json(buf: &mut String, v: &str) {
+    for c in v.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                write!(buf, "\\u{:04x}", c as u32);
+            }
+            c => buf.push(c),
+        }
+    }
+}
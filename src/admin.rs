@@ -0,0 +1,247 @@
+//! the HTTP admin handler of the running logger,mount it on the server of the application(axum,hyper...)
+//! so ops can change the levels and the filter and read the metrics without app-specific plumbing:
+//! * `GET /level`,`PUT /level` the level of the logger,the body is the level(`debug`)
+//! * `GET /levels` the target levels,`PUT /levels/{target}` and `DELETE /levels/{target}` one of them,
+//!   see [`set_target_level`](crate::set_target_level)
+//! * `GET /filter`,`PUT /filter` the filter on top of `Config::filter`,the body is the `RUST_LOG` style
+//!   directives of [`EnvFilter`],a record is filtered by either of them. `DELETE /filter` removes it,
+//!   and the init of a new config(`reload`,`init_scoped`) too
+//! * `GET /metrics` the [`metrics`](crate::metrics) in JSON
+//!
+//! the handler is synchronous and framework free,the server collects the body and calls
+//! [`AdminRouter::handle`],for example with axum:
+//! `let (parts, body) = req.into_parts(); let body = axum::body::to_bytes(body, 4096).await?;`
+//! `admin.handle(Request::from_parts(parts, body))`.
+//! you need enable fast_log = { ... ,features=["admin"]}
+//! ```rust
+//! use fast_log::admin::http::{Request, StatusCode};
+//! let admin = fast_log::admin::router().prefix("/log");
+//! let res = admin.handle(Request::put("/log/levels/my_app::db").body("trace").unwrap());
+//! assert_eq!(res.status(), StatusCode::OK);
+//! let res = admin.handle(Request::get("/log/levels").body("").unwrap());
+//! assert_eq!(res.body(), r#"{"my_app::db":"TRACE"}"#);
+//! ```
+use crate::appender::FastLogRecord;
+use crate::filter::{EnvFilter, Filter};
+use crate::formats::escape_json;
+use crate::LOGGER;
+use http::{header, Method, Request, Response, StatusCode};
+use log::LevelFilter;
+use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+pub use http;
+
+/// the directives of the filter set by `PUT /filter`
+static FILTER: Mutex<Option<String>> = Mutex::new(None);
+
+/// the filter of the config under the filter set by `PUT /filter`
+static BASE: Mutex<Option<Arc<Box<dyn Filter>>>> = Mutex::new(None);
+
+/// the filter set by `PUT /filter` on top of the filter of the config
+struct Layered {
+    base: Arc<Box<dyn Filter>>,
+    filter: EnvFilter,
+}
+
+impl Filter for Layered {
+    fn filter(&self, record: &log::Record) -> bool {
+        self.base.filter(record) || self.filter.filter(record)
+    }
+
+    fn flush(&self) -> Vec<FastLogRecord> {
+        self.base.flush()
+    }
+}
+
+/// the filter of the config shared with the layered filters
+struct Shared(Arc<Box<dyn Filter>>);
+
+impl Filter for Shared {
+    fn filter(&self, record: &log::Record) -> bool {
+        self.0.filter(record)
+    }
+
+    fn flush(&self) -> Vec<FastLogRecord> {
+        self.0.flush()
+    }
+}
+
+fn unshare(base: Arc<Box<dyn Filter>>) -> Box<dyn Filter> {
+    Arc::try_unwrap(base).unwrap_or_else(|v| Box::new(Shared(v)))
+}
+
+/// the admin routes under a path prefix,see the [module](self) docs
+#[derive(Clone, Debug, Default)]
+pub struct AdminRouter {
+    pub prefix: String,
+}
+
+pub fn router() -> AdminRouter {
+    AdminRouter::default()
+}
+
+impl AdminRouter {
+    /// serve the routes under `prefix`,for example "/admin/log"
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// handle the request,404 if the path is not an admin route
+    pub fn handle<B: AsRef<[u8]>>(&self, req: Request<B>) -> Response<String> {
+        let path = match req.uri().path().strip_prefix(self.prefix.as_str()) {
+            Some(v) => v,
+            None => return text(StatusCode::NOT_FOUND, "not found"),
+        };
+        let body = String::from_utf8_lossy(req.body().as_ref());
+        let body = body.trim();
+        match (req.method(), path) {
+            (&Method::GET, "/level") => text(StatusCode::OK, LOGGER.get_level().as_str()),
+            (&Method::PUT, "/level") => match LevelFilter::from_str(body) {
+                Ok(level) => {
+                    LOGGER.set_level(level);
+                    text(StatusCode::OK, level.as_str())
+                }
+                Err(_) => text(StatusCode::BAD_REQUEST, "invalid level"),
+            },
+            (&Method::GET, "/levels") => {
+                let mut json = String::from("{");
+                for (index, (target, level)) in crate::target_levels().iter().enumerate() {
+                    if index > 0 {
+                        json.push(',');
+                    }
+                    json.push('"');
+                    escape_json(&mut json, target);
+                    write!(json, "\":\"{}\"", level);
+                }
+                json.push('}');
+                self::json(json)
+            }
+            (method, path) if path.starts_with("/levels/") => {
+                let target = &path["/levels/".len()..];
+                match *method {
+                    Method::PUT => match LevelFilter::from_str(body) {
+                        Ok(level) => {
+                            crate::set_target_level(target, level);
+                            text(StatusCode::OK, level.as_str())
+                        }
+                        Err(_) => text(StatusCode::BAD_REQUEST, "invalid level"),
+                    },
+                    Method::DELETE => match crate::clear_target_level(target) {
+                        Some(level) => text(StatusCode::OK, level.as_str()),
+                        None => text(StatusCode::NOT_FOUND, "no level of the target"),
+                    },
+                    _ => text(StatusCode::METHOD_NOT_ALLOWED, "method not allowed"),
+                }
+            }
+            (&Method::GET, "/filter") => {
+                let filter = FILTER.lock().unwrap_or_else(|e| e.into_inner());
+                text(StatusCode::OK, filter.as_deref().unwrap_or_default())
+            }
+            (&Method::PUT, "/filter") => match EnvFilter::from_str(body) {
+                Ok(filter) => {
+                    if !layer_filter(Some(filter)) {
+                        return not_initialized();
+                    }
+                    *FILTER.lock().unwrap_or_else(|e| e.into_inner()) = Some(body.to_string());
+                    text(StatusCode::OK, body)
+                }
+                Err(e) => text(StatusCode::BAD_REQUEST, &e.to_string()),
+            },
+            (&Method::DELETE, "/filter") => {
+                if !layer_filter(None) {
+                    return not_initialized();
+                }
+                FILTER.lock().unwrap_or_else(|e| e.into_inner()).take();
+                text(StatusCode::OK, "")
+            }
+            (&Method::GET, "/metrics") => {
+                let m = crate::metrics();
+                json(format!(
+                    "{{\"records_received\":{},\"records_written\":{},\"records_dropped\":{},\"chan_len\":{},\"bytes_written\":{},\"last_flush_duration_seconds\":{}}}",
+                    m.records_received,
+                    m.records_written,
+                    m.records_dropped,
+                    m.chan_len,
+                    m.bytes_written,
+                    m.last_flush_duration.as_secs_f64()
+                ))
+            }
+            (_, "/level" | "/levels" | "/filter" | "/metrics") => {
+                text(StatusCode::METHOD_NOT_ALLOWED, "method not allowed")
+            }
+            _ => text(StatusCode::NOT_FOUND, "not found"),
+        }
+    }
+}
+
+/// set the filter on top of the filter of the running logger,None restores the filter of the config.
+/// false if the logger is not initialized
+fn layer_filter(filter: Option<EnvFilter>) -> bool {
+    let mut installed = LOGGER
+        .chan
+        .filter
+        .write()
+        .unwrap_or_else(|e| e.into_inner());
+    let current = match installed.take() {
+        Some(v) => v,
+        None => return false,
+    };
+    let mut base = BASE.lock().unwrap_or_else(|e| e.into_inner());
+    let config_filter = match base.take() {
+        Some(v) => {
+            //the layered filter holding the filter of the config
+            drop(current);
+            v
+        }
+        None => Arc::new(current),
+    };
+    *installed = Some(match filter {
+        Some(filter) => {
+            *base = Some(config_filter.clone());
+            Box::new(Layered {
+                base: config_filter,
+                filter,
+            })
+        }
+        None => unshare(config_filter),
+    });
+    true
+}
+
+/// drop the filter of `PUT /filter` replaced by the init of a config,
+/// return the filter of the replaced config under it
+pub(crate) fn reset_filter(replaced: Option<Box<dyn Filter>>) -> Option<Box<dyn Filter>> {
+    FILTER.lock().unwrap_or_else(|e| e.into_inner()).take();
+    let base = BASE.lock().unwrap_or_else(|e| e.into_inner()).take();
+    match (replaced, base) {
+        (Some(layered), Some(base)) => {
+            drop(layered);
+            Some(unshare(base))
+        }
+        (replaced, _) => replaced,
+    }
+}
+
+fn not_initialized() -> Response<String> {
+    text(StatusCode::CONFLICT, "the logger is not initialized")
+}
+
+fn text(status: StatusCode, body: &str) -> Response<String> {
+    response(status, "text/plain; charset=utf-8", body.to_string())
+}
+
+fn json(body: String) -> Response<String> {
+    response(StatusCode::OK, "application/json", body)
+}
+
+fn response(status: StatusCode, content_type: &str, body: String) -> Response<String> {
+    let mut res = Response::new(body);
+    *res.status_mut() = status;
+    if let Ok(v) = content_type.parse() {
+        res.headers_mut().insert(header::CONTENT_TYPE, v);
+    }
+    res
+}
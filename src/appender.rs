@@ -1,14 +1,59 @@
-use std::time::SystemTime;
+use crate::error::LogError;
 use crate::WaitGroup;
+pub use fast_log_core::KvValue;
+use std::sync::Arc;
+use std::thread::ThreadId;
+use std::time::{Duration, SystemTime};
 
 /// LogAppender append logs
 /// Appender will be running on single main thread,please do_log for new thread or new an Future
 pub trait LogAppender: Send {
+    /// called once on the appender thread before any do_logs,
+    /// open connections or resources here, or do nothing
+    fn open(&self) {}
+
     /// Batch write log, or do nothing
     fn do_logs(&self, records: &[FastLogRecord]);
 
+    /// Batch write log and return the write failure,used by FallbackAppender to detect failures.
+    /// default is do_logs() and never fail
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
+        self.do_logs(records);
+        Ok(())
+    }
+
     /// flush or do nothing
     fn flush(&self) {}
+
+    /// fsync the written data to disk, or do nothing
+    fn sync(&self) {}
+
+    /// fsync and return the failure,used by `fast_log::audit`.
+    /// default is sync() and never fail
+    fn try_sync(&self) -> Result<(), LogError> {
+        self.sync();
+        Ok(())
+    }
+
+    /// if Some,on_idle() is called when no records have arrived for the duration
+    fn idle_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// called when no records have arrived for idle_timeout(), or do nothing
+    fn on_idle(&self) {}
+
+    /// called once on the appender thread after exit(),
+    /// flush and close connections or resources here, or do nothing
+    fn close(&self) {}
+
+    /// describe the appender and its settings for the startup banner(`Config::banner`),
+    /// default is the type name
+    fn describe(&self) -> String {
+        let name = std::any::type_name::<Self>();
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name).to_string()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -37,6 +82,7 @@ impl PartialEq for Command {
 
 impl Eq for Command {}
 
+/// the record of the logger,see [`FastLogRecord::new`]
 #[derive(Clone, Debug)]
 pub struct FastLogRecord {
     pub command: Command,
@@ -46,10 +92,64 @@ pub struct FastLogRecord {
     pub module_path: String,
     pub file: String,
     pub line: Option<u32>,
+    /// the name of the producing thread,empty if unnamed or `Config::with_source` is false
+    pub thread_name: String,
+    /// the id of the producing thread,None if `Config::with_source` is false
+    pub thread_id: Option<ThreadId>,
+    /// the host name,None if `Config::with_hostname` is false
+    pub hostname: Option<Arc<str>>,
+    /// the process id,None if `Config::with_pid` is false
+    pub pid: Option<u32>,
+    /// the sequence number of the record in the process,a gap means dropped or lost records.
+    /// None if `Config::with_seq` is false
+    pub seq: Option<u64>,
+    /// the end of the record in the crash journal,None if not journaled,see `Config::journal`
+    pub(crate) journal_end: Option<u64>,
+    /// the structured key-values of the record,for example `log::info!(user_id = 1; "login")`,
+    /// the booleans and numbers keep their type,see [`KvValue`]
+    pub kv: Vec<(String, KvValue)>,
+    /// the trace id of the current OpenTelemetry context,None without the `otel` feature
+    pub trace_id: Option<u128>,
+    /// the span id of the current OpenTelemetry context,None without the `otel` feature,
+    /// the id of the tracing span is the `tracing_span_id` key-value with the `tracing` feature
+    pub span_id: Option<u64>,
     pub now: SystemTime,
     pub formated: String,
 }
 
+impl FastLogRecord {
+    /// a record of `level` logged at `target` now,the other fields are empty.
+    /// for example a record made by an interceptor:
+    /// ```rust
+    /// use fast_log::appender::FastLogRecord;
+    /// let mut record = FastLogRecord::new(log::Level::Warn, "my_app::audit", "denied".to_string());
+    /// record.line = Some(42);
+    /// assert_eq!(record.args, "denied");
+    /// ```
+    pub fn new(level: log::Level, target: &str, args: String) -> Self {
+        Self {
+            command: Command::CommandRecord,
+            level,
+            target: target.to_string(),
+            args,
+            module_path: target.to_string(),
+            file: String::new(),
+            line: None,
+            thread_name: String::new(),
+            thread_id: None,
+            hostname: None,
+            pid: None,
+            seq: None,
+            journal_end: None,
+            kv: vec![],
+            trace_id: None,
+            span_id: None,
+            now: crate::now(),
+            formated: String::new(),
+        }
+    }
+}
+
 /// format record data
 pub trait RecordFormat: Send + Sync {
     fn do_format(&self, arg: &mut FastLogRecord);
@@ -1,3 +1,6 @@
+use crate::appender::{FastLogRecord, LogAppender};
+use crate::Config;
+
 pub trait TPS {
     fn tps(&self, total: u64);
     fn time(&self, total: u64);
@@ -27,3 +30,20 @@ impl TPS for std::time::Instant {
         println!("cost:{:?}", time);
     }
 }
+
+/// the appender dropping the records,measures the cost of the logger without the io
+pub struct NoopAppender {}
+
+impl LogAppender for NoopAppender {
+    fn do_logs(&self, _records: &[FastLogRecord]) {}
+}
+
+/// the config writing the records to a [`NoopAppender`],
+/// so the throughput of the producers,the channel and the format is measured alone
+/// ```rust
+/// let _guard = fast_log::init_scoped(fast_log::bench_mode()).unwrap();
+/// log::info!("dropped");
+/// ```
+pub fn bench_mode() -> Config {
+    Config::new().custom(NoopAppender {})
+}
@@ -0,0 +1,67 @@
+//! redirect the stdout and stderr of the process into the log,
+//! so the stray `print!`/`eprintln!` of the dependencies end up in the rotated files too.
+//! the file descriptors 1 and 2 are replaced by pipes,a reader thread turns every line into a record
+//! of the target `stdout`(Info) or `stderr`(Warn) with the key-value `stream`.
+//! the [`ConsoleAppender`](crate::plugin::console::ConsoleAppender) keeps writing to the original stdout.
+//! you need enable fast_log = { ... ,features=["capture"]}
+//! ```rust
+//! fast_log::init(fast_log::Config::new().console()).unwrap();
+//! fast_log::capture_stdout().unwrap();
+//! println!("a stray print");
+//! log::logger().flush();
+//! ```
+#![allow(unsafe_code)]
+
+use crate::error::LogError;
+use log::Level;
+use once_cell::sync::OnceCell;
+use std::fs::File;
+use std::io::Write;
+use std::os::fd::{FromRawFd, RawFd};
+
+/// the original stdout,taken by `capture_stdout`
+static ORIGINAL_STDOUT: OnceCell<File> = OnceCell::new();
+
+/// redirect the stdout and stderr into the log for the rest of the process,
+/// fail if they are already captured
+pub fn capture_stdout() -> Result<(), LogError> {
+    let original = duplicate(libc::STDOUT_FILENO)?;
+    if ORIGINAL_STDOUT.set(original).is_err() {
+        return Err(LogError::from("[fast_log] stdout is already captured"));
+    }
+    std::io::stdout().flush();
+    std::io::stderr().flush();
+    let stdout = redirect(libc::STDOUT_FILENO)?;
+    crate::pipe::spawn_reader(stdout, Level::Info, "stdout", "stdout");
+    let stderr = redirect(libc::STDERR_FILENO)?;
+    crate::pipe::spawn_reader(stderr, Level::Warn, "stderr", "stderr");
+    Ok(())
+}
+
+/// the original stdout if captured,the console appender writes to it
+pub(crate) fn original_stdout() -> Option<&'static File> {
+    ORIGINAL_STDOUT.get()
+}
+
+fn duplicate(fd: RawFd) -> Result<File, LogError> {
+    let fd = unsafe { libc::dup(fd) };
+    if fd < 0 {
+        return Err(LogError::from(std::io::Error::last_os_error()));
+    }
+    Ok(unsafe { File::from_raw_fd(fd) })
+}
+
+/// replace `fd` by the write end of a pipe,return the read end
+fn redirect(fd: RawFd) -> Result<File, LogError> {
+    let mut fds: [RawFd; 2] = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } < 0 {
+        return Err(LogError::from(std::io::Error::last_os_error()));
+    }
+    let (read, write) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    if unsafe { libc::dup2(fds[1], fd) } < 0 {
+        return Err(LogError::from(std::io::Error::last_os_error()));
+    }
+    //fd is the write end now,close the other one
+    drop(write);
+    Ok(read)
+}
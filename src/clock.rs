@@ -0,0 +1,104 @@
+//! the clock of the record timestamps,see [`Config::clock`](crate::Config::clock).
+//! the timestamps of the records,the formats and the time rolling of the files are taken by [`now`](crate::now),
+//! so a [`TimeSource`] like [`MockClock`](crate::test::MockClock) makes them deterministic in tests
+use crate::runtime::{self, Instant};
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+/// the pluggable time of [`Clock::Custom`]
+pub trait TimeSource: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+#[derive(Clone, Default)]
+pub enum Clock {
+    /// read the wall clock for every record,the timestamps follow the NTP adjustments
+    #[default]
+    System,
+    /// read the wall clock once on init and add the monotonic elapsed time,
+    /// the intervals between the timestamps are accurate and never go backwards,
+    /// but the timestamps drift from the wall clock in a long running process
+    Monotonic,
+    /// read the time source,for example a mock clock in tests
+    Custom(Arc<dyn TimeSource>),
+}
+
+impl Clock {
+    pub fn custom<T: TimeSource + 'static>(source: T) -> Self {
+        Clock::Custom(Arc::new(source))
+    }
+}
+
+impl Debug for Clock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Clock::System => f.write_str("System"),
+            Clock::Monotonic => f.write_str("Monotonic"),
+            Clock::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+/// the custom clocks are equal if they share the time source
+impl PartialEq for Clock {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Clock::System, Clock::System) | (Clock::Monotonic, Clock::Monotonic) => true,
+            (Clock::Custom(a), Clock::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Clock {}
+
+struct MonotonicSource {
+    time: SystemTime,
+    instant: Instant,
+}
+
+impl TimeSource for MonotonicSource {
+    fn now(&self) -> SystemTime {
+        self.time + self.instant.elapsed()
+    }
+}
+
+static HAS_SOURCE: AtomicBool = AtomicBool::new(false);
+/// the clock and its time source,None with `Clock::System`
+static SOURCE: RwLock<Option<(Clock, Arc<dyn TimeSource>)>> = RwLock::new(None);
+
+/// set the clock of the timestamps,`Clock::Monotonic` takes the wall clock again.
+/// `init` sets the clock of the config,it replaces the clock set before
+pub fn set_clock(clock: Clock) {
+    let mut source = SOURCE.write().unwrap_or_else(|e| e.into_inner());
+    *source = match &clock {
+        Clock::System => None,
+        Clock::Monotonic => {
+            let monotonic = MonotonicSource {
+                time: runtime::system_now(),
+                instant: Instant::now(),
+            };
+            Some((clock, Arc::new(monotonic)))
+        }
+        Clock::Custom(v) => Some((clock.clone(), v.clone())),
+    };
+    HAS_SOURCE.store(source.is_some(), Ordering::SeqCst);
+}
+
+pub fn clock() -> Clock {
+    match SOURCE.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some((clock, _)) => clock.clone(),
+        None => Clock::System,
+    }
+}
+
+/// the time of the time source,None with `Clock::System`
+pub(crate) fn source_now() -> Option<SystemTime> {
+    if !HAS_SOURCE.load(Ordering::Relaxed) {
+        return None;
+    }
+    let source = SOURCE.read().unwrap_or_else(|e| e.into_inner());
+    source.as_ref().map(|(_, v)| v.now())
+}
@@ -1,29 +1,218 @@
 use crate::appender::{LogAppender, RecordFormat};
+use crate::clock::Clock;
 use crate::consts::LogSize;
-use crate::filter::{Filter, NoFilter};
+use crate::date::TimePrecision;
+use crate::encoding::LineEnd;
+use crate::error::LogError;
+use crate::filter::{EnvFilter, Filter, NoFilter};
+use crate::interceptor::Interceptor;
 use crate::plugin::console::ConsoleAppender;
+use crate::plugin::fallback::FallbackAppender;
 use crate::plugin::file::FileAppender;
 use crate::plugin::file_loop::FileLoopAppender;
-use crate::plugin::file_split::{FileSplitAppender, Packer, RollingType};
+use crate::plugin::file_split::{
+    per_process_path, FileSplitAppender, Packer, RollingType, RotateEvent, SplitBy,
+};
+use crate::plugin::formatted::FormattedAppender;
+use crate::plugin::packer::LogPacker;
+use crate::plugin::ring_buffer::{RecentRecords, RingBufferAppender};
+use crate::plugin::router::RouterAppender;
+use crate::scrub::Scrubber;
 use crate::FastLogFormat;
-use log::LevelFilter;
+use log::{Level, LevelFilter};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// the batch tuning of the worker,it sizes the batches handed to the appenders.
+/// the network appenders send each batch they get,only the kafka appender reads the linger(`linger.ms`)
+#[derive(Copy, Clone, Debug)]
+pub struct Batch {
+    /// max records of one batch
+    pub max_records: usize,
+    /// max bytes of one batch
+    pub max_bytes: usize,
+    /// max time a record waits for batching,default is zero(not wait)
+    pub linger: Duration,
+}
+
+impl Default for Batch {
+    fn default() -> Self {
+        Self {
+            max_records: usize::MAX,
+            max_bytes: usize::MAX,
+            linger: Duration::ZERO,
+        }
+    }
+}
+
+/// the thread that formats records
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FormatOn {
+    /// format on the thread that calls `log`,
+    /// slower for the caller but the format can read thread-local context(thread name,span...)
+    Producer,
+    /// format on the worker thread,keeps the hot path minimal
+    #[default]
+    Consumer,
+}
+
+/// how the producer threads hand the records to the worker,see [`Config::producer_mode`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ProducerMode {
+    /// all threads send into the shared channel of `chan_len`
+    #[default]
+    Channel,
+    /// each thread pushes into its own queue(1024 records) the worker drains,
+    /// no contention between the producers of many threads(32+).
+    /// the records of different threads may be written slightly out of time order
+    Sharded,
+}
+
+/// when the appenders fsync the written records to disk,see [`Config::fsync`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// leave the records in the OS page cache
+    #[default]
+    Never,
+    /// fsync after every `n` records
+    EveryN(usize),
+    /// fsync the written records at most the duration later
+    Interval(Duration),
+    /// fsync after every write,the slowest
+    Always,
+}
+
+/// a file appender added by [`Config::file`],[`Config::file_loop`] or [`Config::file_split`],
+/// the path is checked by [`Config::validate`] and the appender is created by init
+pub struct PendingFile {
+    /// the position in `appends`
+    index: usize,
+    path: String,
+    /// Some(zero) for the split appenders writing in the directory of the path,zero is an invalid max_temp_size
+    split: Option<bool>,
+    make: Box<dyn FnOnce() -> Box<dyn LogAppender> + Send>,
+}
 
 pub struct Config {
     pub appends: Vec<Box<dyn LogAppender>>,
+    /// the file appenders created by init,see [`PendingFile`]
+    pub files: Vec<PendingFile>,
     pub level: LevelFilter,
     pub filter: Box<dyn Filter>,
     pub format: Box<dyn RecordFormat>,
+    pub format_on: FormatOn,
+    pub producer_mode: ProducerMode,
     pub chan_len: Option<usize>,
+    pub time_precision: TimePrecision,
+    /// reuse the formatted timestamp of the same millisecond,see [`Config::coarse_time`]
+    pub coarse_time: bool,
+    pub clock: Clock,
+    /// the level names of the text formats,see [`Config::level_names`]
+    pub level_names: Option<[String; 5]>,
+    /// None => not log panics,Some(abort) => log panics and abort after flushing if abort is true
+    pub panic_hook: Option<bool>,
+    pub on_error: Option<fn(&LogError)>,
+    /// the callback after a file is rolled and packed
+    pub on_rotate: Option<fn(&RotateEvent)>,
+    /// the threads packing the rolled files,see [`Config::pack_workers`]
+    pub pack_workers: usize,
+    /// flush immediately after writing a record at or above this level
+    pub flush_level: LevelFilter,
+    /// flush appenders when no records have arrived for the duration since the last write,see [`Config::flush_interval`]
+    pub flush_interval: Option<Duration>,
+    /// fsync after the level-dependent flush
+    pub flush_fsync: bool,
+    /// the interval and the level of the heartbeat record,see [`Config::heartbeat`]
+    pub heartbeat: Option<(Duration, Level)>,
+    /// fsync the records by the count or the time
+    pub fsync: FsyncPolicy,
+    pub line_end: LineEnd,
+    pub batch: Batch,
+    /// records at or above this level are logged synchronously
+    pub sync_level: LevelFilter,
+    /// drop the records instead of blocking the log call on the full channel
+    pub non_blocking: bool,
+    /// the size of the recycled string pool,0 is disabled
+    pub pool_size: usize,
+    /// capture the name and id of the producing thread on records
+    pub source: bool,
+    /// attach the host name to records
+    pub hostname: bool,
+    /// attach the process id to records
+    pub pid: bool,
+    /// attach the sequence number to records
+    pub seq: bool,
+    /// truncate the longer messages,see [`Config::max_record_len`]
+    pub max_record_len: Option<usize>,
+    #[cfg(feature = "encoding")]
+    pub encoding: Option<&'static encoding_rs::Encoding>,
+    /// change the records on the worker thread,see [`Config::interceptor`]
+    pub interceptors: Vec<Box<dyn Interceptor>>,
+    /// rewrite the records before the appenders,see [`Config::scrubber`]
+    pub scrubbers: Vec<Box<dyn Scrubber>>,
+    /// the path and the size of the crash journal,see [`Config::journal`]
+    #[cfg(feature = "mmap")]
+    pub journal: Option<(String, LogSize)>,
+    /// the records of the ring buffer added by [`Config::ring_buffer`],see [`Config::recent`]
+    pub recent: Option<RecentRecords>,
+    /// the appender of `fast_log::audit`,see [`Config::audit`]
+    pub audit: Option<Box<dyn LogAppender>>,
+    /// the problems found while building the config,see [`Config::validate`]
+    pub errors: Vec<LogError>,
+    /// write the effective config as the first record,see [`Config::banner`]
+    pub banner: bool,
+    /// route the internal events at or above the level to the appenders,see [`Config::diagnostics`]
+    pub diagnostics: LevelFilter,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             appends: vec![],
+            files: vec![],
             level: LevelFilter::Trace,
             filter: Box::new(NoFilter {}),
             format: Box::new(FastLogFormat::new()),
+            format_on: FormatOn::Consumer,
+            producer_mode: ProducerMode::Channel,
             chan_len: Some(100000),
+            time_precision: TimePrecision::Nanos,
+            coarse_time: false,
+            clock: Clock::System,
+            level_names: None,
+            panic_hook: None,
+            on_error: None,
+            on_rotate: None,
+            pack_workers: 0,
+            flush_level: LevelFilter::Off,
+            flush_interval: None,
+            flush_fsync: false,
+            heartbeat: None,
+            fsync: FsyncPolicy::Never,
+            line_end: LineEnd::LF,
+            batch: Batch::default(),
+            sync_level: LevelFilter::Off,
+            non_blocking: false,
+            pool_size: 0,
+            source: false,
+            hostname: false,
+            pid: false,
+            seq: false,
+            max_record_len: None,
+            #[cfg(feature = "encoding")]
+            encoding: None,
+            interceptors: vec![],
+            scrubbers: vec![],
+            #[cfg(feature = "mmap")]
+            journal: None,
+            recent: None,
+            audit: None,
+            errors: vec![],
+            banner: false,
+            diagnostics: LevelFilter::Off,
         }
     }
 }
@@ -33,6 +222,41 @@ impl Config {
         Self::default()
     }
 
+    /// the config of the env vars,a drop-in replacement of env_logger:
+    /// * `RUST_LOG` the level and module directives,see [`EnvFilter`],default is `error`
+    /// * `FAST_LOG_FILE` log to the file instead of the console
+    /// * `FAST_LOG_MAX_SIZE` split the file at the size(for example `10MB`) and keep all packs
+    ///
+    /// the invalid values are reported by `fast_log::init`
+    pub fn from_env() -> Self {
+        let mut config = Self::new();
+        let spec = std::env::var("RUST_LOG").unwrap_or_default();
+        let spec = if spec.trim().is_empty() {
+            "error"
+        } else {
+            &spec
+        };
+        match EnvFilter::from_str(spec) {
+            Ok(filter) => config = config.level(filter.max_level()).filter(filter),
+            Err(e) => config.errors.push(e),
+        }
+        let max_size = std::env::var("FAST_LOG_MAX_SIZE")
+            .ok()
+            .map(|v| LogSize::from_str(&v));
+        match (std::env::var("FAST_LOG_FILE").ok(), max_size) {
+            (_, Some(Err(e))) => config.errors.push(e),
+            (Some(file), Some(Ok(size))) => {
+                config = config.file_split(&file, size, RollingType::All, LogPacker {})
+            }
+            (Some(file), None) => config = config.file(&file),
+            (None, Some(Ok(_))) => config.errors.push(LogError::Config(
+                "FAST_LOG_MAX_SIZE is set without FAST_LOG_FILE".to_string(),
+            )),
+            (None, None) => config = config.console(),
+        }
+        config
+    }
+
     /// set log LevelFilter
     pub fn level(mut self, level: LevelFilter) -> Self {
         self.level = level;
@@ -48,47 +272,580 @@ impl Config {
         self.format = Box::new(format);
         self
     }
+    /// add a ConsoleAppender with PrettyFormat,the colored multi-line format for local development,
+    /// the other appenders keep `Config::format`
+    pub fn pretty(self) -> Self {
+        self.custom_format(ConsoleAppender::new(), crate::PrettyFormat::new())
+    }
+    /// set the thread that formats records,default is FormatOn::Consumer(the worker thread).
+    /// records written by `print` are already formatted and not affected
+    pub fn format_on(mut self, on: FormatOn) -> Self {
+        self.format_on = on;
+        self
+    }
+    /// set how the producer threads hand the records to the worker,default is ProducerMode::Channel.
+    /// `ProducerMode::Sharded` removes the contention on the channel under many producer threads,
+    /// see `cargo run --release --package example --bin bench_test_sharded`
+    pub fn producer_mode(mut self, mode: ProducerMode) -> Self {
+        self.producer_mode = mode;
+        self
+    }
     /// add a ConsoleAppender
     pub fn console(mut self) -> Self {
-        self.appends.push(Box::new(ConsoleAppender {}));
+        self.appends.push(Box::new(ConsoleAppender::new()));
         self
     }
-    /// add a FileAppender
-    pub fn file(mut self, file: &str) -> Self {
-        self.appends.push(Box::new(FileAppender::new(file)));
+    /// add a WebConsoleAppender writing into the browser console on wasm32-unknown-unknown
+    /// you need enable fast_log = { ... ,features=["web-console"]}
+    #[cfg(all(feature = "web-console", target_arch = "wasm32", target_os = "unknown"))]
+    pub fn web_console(mut self) -> Self {
+        self.appends.push(Box::new(
+            crate::plugin::web_console::WebConsoleAppender::new(),
+        ));
         self
     }
-    /// add a FileLoopAppender
-    pub fn file_loop(mut self, file: &str, max_temp_size: LogSize) -> Self {
+    /// add a LogcatAppender writing into the Android logcat,the tag is the target of the record
+    /// you need enable fast_log = { ... ,features=["logcat"]}
+    #[cfg(all(feature = "logcat", target_os = "android"))]
+    pub fn logcat(mut self) -> Self {
+        self.appends
+            .push(Box::new(crate::plugin::logcat::LogcatAppender::new()));
+        self
+    }
+    /// add an OsLogAppender writing into the Apple unified logging of `subsystem`(for example "com.example.app")
+    /// you need enable fast_log = { ... ,features=["os_log"]}
+    #[cfg(all(feature = "os_log", target_vendor = "apple"))]
+    pub fn os_log(mut self, subsystem: &str) -> Self {
         self.appends
-            .push(Box::new(FileLoopAppender::new(file, max_temp_size)));
+            .push(Box::new(crate::plugin::os_log::OsLogAppender::new(
+                subsystem,
+            )));
+        self
+    }
+    /// add a RingBufferAppender keeping the last `capacity` records,see [`Config::recent`] and `fast_log::dump_recent`
+    pub fn ring_buffer(mut self, capacity: usize) -> Self {
+        let appender = RingBufferAppender::new(capacity);
+        self.recent = Some(appender.recent());
+        self.appends.push(Box::new(appender));
+        self
+    }
+    /// the records of the last ring buffer added by [`Config::ring_buffer`],take it before the init
+    /// ```rust
+    /// let config = fast_log::Config::new().console().ring_buffer(100);
+    /// let recent = config.recent().unwrap();
+    /// fast_log::init(config).unwrap();
+    /// let records = fast_log::dump_recent(&recent);
+    /// ```
+    pub fn recent(&self) -> Option<RecentRecords> {
+        self.recent.clone()
+    }
+    /// add a file appender created by init,after `validate` checked the path
+    fn pending_file<F>(mut self, path: &str, split: Option<bool>, make: F) -> Self
+    where
+        F: FnOnce() -> Box<dyn LogAppender> + Send + 'static,
+    {
+        self.files.push(PendingFile {
+            index: self.appends.len() + self.files.len(),
+            path: path.to_string(),
+            split,
+            make: Box::new(make),
+        });
         self
     }
+    /// create the file appenders of `file`,`file_loop` and `file_split` in their place,called by init
+    pub(crate) fn create_files(&mut self) {
+        for x in std::mem::take(&mut self.files) {
+            let index = x.index.min(self.appends.len());
+            self.appends.insert(index, (x.make)());
+        }
+    }
+    /// add a FileAppender
+    pub fn file(self, file: &str) -> Self {
+        let path = file.to_string();
+        self.pending_file(file, None, move || Box::new(FileAppender::new(&path)))
+    }
+    /// add a FileLoopAppender
+    pub fn file_loop(self, file: &str, max_temp_size: LogSize) -> Self {
+        let path = file.to_string();
+        self.pending_file(file, Some(max_temp_size.get_len() == 0), move || {
+            Box::new(FileLoopAppender::new(&path, max_temp_size))
+        })
+    }
     /// add a FileSplitAppender
-    pub fn file_split<P: Packer + 'static>(
-        mut self,
+    /// `max_temp_size` is the LogSize,or SplitBy::Records to split by the record count
+    pub fn file_split<S: Into<SplitBy>, P: Packer + 'static>(
+        self,
+        file_path: &str,
+        max_temp_size: S,
+        rolling_type: RollingType,
+        packer: P,
+    ) -> Self {
+        self.file_split_boxed(file_path, max_temp_size, rolling_type, Box::new(packer))
+    }
+    /// add a FileSplitAppender with a boxed packer
+    pub fn file_split_boxed<S: Into<SplitBy>>(
+        self,
+        file_path: &str,
+        max_temp_size: S,
+        rolling_type: RollingType,
+        packer: Box<dyn Packer>,
+    ) -> Self {
+        let max_temp_size = max_temp_size.into();
+        let path = file_path.to_string();
+        self.pending_file(file_path, Some(max_temp_size.is_zero()), move || {
+            Box::new(FileSplitAppender::new(
+                &path,
+                max_temp_size,
+                rolling_type,
+                packer,
+            ))
+        })
+    }
+    /// add a FileSplitAppender whose temp file name is suffixed with the process id and the start time,
+    /// so the instances of a service on the same host never clobber the files of each other,
+    /// see [`per_process_path`](crate::plugin::file_split::per_process_path)
+    pub fn file_split_per_process<S: Into<SplitBy>, P: Packer + 'static>(
+        self,
         file_path: &str,
-        max_temp_size: LogSize,
+        max_temp_size: S,
         rolling_type: RollingType,
         packer: P,
     ) -> Self {
-        self.appends.push(Box::new(FileSplitAppender::new(
-            file_path,
+        self.file_split(
+            &per_process_path(file_path),
             max_temp_size,
             rolling_type,
-            Box::new(packer),
-        )));
-        self
+            packer,
+        )
     }
     /// add a custom LogAppender
     pub fn custom<Appender: LogAppender + 'static>(mut self, arg: Appender) -> Self {
         self.appends.push(Box::new(arg));
         self
     }
+    /// add a custom LogAppender with its own format,for example JSON in the file and PrettyFormat on the console,
+    /// the other appenders keep `Config::format`. see [`FormattedAppender`]
+    pub fn custom_format<Appender: LogAppender + 'static, F: RecordFormat + 'static>(
+        mut self,
+        arg: Appender,
+        format: F,
+    ) -> Self {
+        self.appends
+            .push(Box::new(FormattedAppender::new(arg, format)));
+        self
+    }
+    /// add a FallbackAppender,records are routed to `secondary` when `primary` fails repeatedly
+    pub fn fallback<P: LogAppender + 'static, S: LogAppender + 'static>(
+        mut self,
+        primary: P,
+        secondary: S,
+    ) -> Self {
+        self.appends.push(Box::new(FallbackAppender::new(
+            Box::new(primary),
+            Box::new(secondary),
+        )));
+        self
+    }
+    /// set the appender of the audit records,see `fast_log::audit`.
+    /// the audit records skip the level,the filter and the channel,
+    /// each one is written,flushed and fsynced before `audit` returns
+    pub fn audit<A: LogAppender + 'static>(mut self, appender: A) -> Self {
+        self.audit = Some(Box::new(appender));
+        self
+    }
+    /// add a RouterAppender,records are routed by the target prefix to different appenders
+    pub fn router(mut self, router: RouterAppender) -> Self {
+        self.appends.push(Box::new(router));
+        self
+    }
 
-    /// if none=> unbounded() channel,if Some =>  bounded(len) channel
+    /// if none=> unbounded() channel,if Some =>  bounded(len) channel. default is Some(100000)
+    ///
+    /// backpressure of the bounded channel: when the channel is full(for example a stuck disk),
+    /// `log::info!` and the other log macros retry a few times and then block the caller until the worker
+    /// drains the channel, so queued records can't grow until the application OOM.
+    /// `print` blocks immediately. the unbounded channel never blocks but its memory is not limited.
+    /// with [`non_blocking`](Self::non_blocking) the log macros drop the record instead of blocking,
+    /// and `try_print` gives back the message.
+    ///
+    /// the channel is created on the first use of the logger, so `pre_init` or `print` before `init`
+    /// creates an unbounded channel and this option is ignored
     pub fn chan_len(mut self, len: Option<usize>) -> Self {
         self.chan_len = len;
         self
     }
+
+    /// set the fractional seconds precision of the log timestamp,default is TimePrecision::Nanos
+    pub fn time_precision(mut self, precision: TimePrecision) -> Self {
+        self.time_precision = precision;
+        self
+    }
+
+    /// format the timestamp of the default formats at most at millisecond precision,
+    /// and only when the millisecond(or the second of TimePrecision::Secs) changes,
+    /// the records of the same millisecond reuse the cached text,
+    /// and the records read the time ticked every millisecond by a thread instead of the clock of Clock::System.
+    /// cheaper for the high-volume logs,default is false
+    pub fn coarse_time(mut self, coarse: bool) -> Self {
+        self.coarse_time = coarse;
+        self
+    }
+
+    /// set the clock of the timestamps,default is Clock::System.
+    /// Clock::Monotonic is immune to the NTP jumps for the latency analysis from the logs.
+    /// init sets this clock,it replaces a clock set by `fast_log::clock::set_clock` before
+    pub fn clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// set the level names of FastLogFormat and PrettyFormat in the order of ERROR,WARN,INFO,DEBUG,TRACE,
+    /// for example fixed-width `["EROR", "WARN", "INFO", "DEBG", "TRAC"]`,lowercase or localized names.
+    /// the machine-readable formats(json,csv...) keep the standard names
+    pub fn level_names(mut self, names: [&str; 5]) -> Self {
+        self.level_names = Some(names.map(|v| v.to_string()));
+        self
+    }
+
+    /// log panics of all threads(tagged with the thread name) at error level and flush,
+    /// if abort is true,the process is aborted after flushing
+    pub fn panic_hook(mut self, abort: bool) -> Self {
+        self.panic_hook = Some(abort);
+        self
+    }
+
+    /// set the callback of appender failures(disk full, broken pipe, rotation errors...),
+    /// instead of being silently swallowed inside the worker
+    pub fn on_error(mut self, f: fn(&LogError)) -> Self {
+        self.on_error = Some(f);
+        self
+    }
+
+    /// set the callback invoked after a file is rolled and packed(on the pack worker),
+    /// for example to upload the archive or notify the collectors
+    pub fn on_rotate(mut self, f: fn(&RotateEvent)) -> Self {
+        self.on_rotate = Some(f);
+        self
+    }
+
+    /// the threads packing the rolled files of the split appenders,the packs of an appender are packed in order.
+    /// default(0) is the available parallelism up to 4,it takes effect before the first rotation of the process
+    pub fn pack_workers(mut self, workers: usize) -> Self {
+        self.pack_workers = workers;
+        self
+    }
+
+    /// flush appenders immediately after writing a record at or above `level`(for example LevelFilter::Error),
+    /// and fsync if `fsync` is true, so crashes shortly after an error never lose the error line itself.
+    /// default is LevelFilter::Off(not flush by level)
+    pub fn flush_level(mut self, level: LevelFilter, fsync: bool) -> Self {
+        self.flush_level = level;
+        self.flush_fsync = fsync;
+        self
+    }
+
+    /// flush the appenders(the buffer of `FileAppender::with_buffer_size`,the gzip compressor...)
+    /// when no records have arrived for `interval` after a write,
+    /// so the last lines of a low-traffic service don't stay in the buffers.
+    /// default is None(flush by `flush_level`,`fast_log::flush` and exit only)
+    pub fn flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = Some(interval);
+        self
+    }
+
+    /// the worker writes a heartbeat record of target "fast_log" at `level` every `interval`,
+    /// with the records waiting in the channel(`queue_depth`) and the records and bytes written per second
+    /// since the last heartbeat,so the log pipeline can tell a wedged service from a quiet one.
+    /// the heartbeat skips the level and the filter
+    pub fn heartbeat(mut self, interval: Duration, level: Level) -> Self {
+        self.heartbeat = Some((interval, level));
+        self
+    }
+
+    /// set the fsync policy of the appenders,trade throughput for durability.
+    /// default is FsyncPolicy::Never(the records sit in the OS page cache until the OS writes them)
+    pub fn fsync(mut self, policy: FsyncPolicy) -> Self {
+        self.fsync = policy;
+        self
+    }
+
+    /// set the record terminator,default is LineEnd::LF
+    pub fn line_end(mut self, line_end: LineEnd) -> Self {
+        self.line_end = line_end;
+        self
+    }
+
+    /// max records of one batch,default is unlimited
+    pub fn batch_max_records(mut self, max_records: usize) -> Self {
+        self.batch.max_records = max_records;
+        self
+    }
+
+    /// max bytes of one batch,default is unlimited
+    pub fn batch_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.batch.max_bytes = max_bytes;
+        self
+    }
+
+    /// max time a record waits for batching,more records each batch(throughput) vs fresher logs
+    pub fn linger(mut self, linger: Duration) -> Self {
+        self.batch.linger = linger;
+        self
+    }
+
+    /// records at or above `level`(for example LevelFilter::Error) are logged synchronously,
+    /// the log call returns after the record is written and flushed by all appenders.
+    /// the records of the worker and the appender threads are not waited for,
+    /// they would wait for themselves. default is LevelFilter::Off
+    pub fn sync_level(mut self, level: LevelFilter) -> Self {
+        self.sync_level = level;
+        self
+    }
+
+    /// never block the log call on the full bounded channel,the record is dropped and counted
+    /// in `metrics().records_dropped` instead,so latency-critical threads(audio,trading) skip logging
+    /// rather than wait for the worker. default is false,see [`chan_len`](Self::chan_len)
+    /// and [`try_print`](crate::try_print)
+    pub fn non_blocking(mut self, non_blocking: bool) -> Self {
+        self.non_blocking = non_blocking;
+        self
+    }
+
+    /// set the size of the recycled string pool,the strings of the written records are reused
+    /// by the next records instead of allocating fresh strings. default is 0(disabled).
+    /// the pool is one lock-free queue shared by all threads,not thread-local:
+    /// the appender threads give back the strings the logging threads take.
+    /// the first size is kept for the life of the process
+    pub fn pool_size(mut self, size: usize) -> Self {
+        self.pool_size = size;
+        self
+    }
+
+    /// truncate the messages longer than `bytes` before sending them to the channel,
+    /// with the suffix `…[truncated N bytes]`,so a misbehaving caller logging a 100 MB string
+    /// never fills the channel and the disk. the cut is on a char boundary,default is unlimited.
+    /// it applies to the log macros,`print`,`print_with` and the string key-values
+    pub fn max_record_len(mut self, bytes: usize) -> Self {
+        self.max_record_len = Some(bytes);
+        self
+    }
+
+    /// capture the source of records,module_path,file,line and the name and id of the producing thread,
+    /// so the format can show where messages originated. default is false,
+    /// module_path,file and line are always captured,true additionally captures the thread
+    pub fn with_source(mut self, source: bool) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// attach the host name(computed once at init) to each record,
+    /// for log aggregation where the collector doesn't add it
+    pub fn with_hostname(mut self, hostname: bool) -> Self {
+        self.hostname = hostname;
+        self
+    }
+
+    /// attach the process id to each record
+    pub fn with_pid(mut self, pid: bool) -> Self {
+        self.pid = pid;
+        self
+    }
+
+    /// attach a sequence number(starting at 1,increasing by one for each record of the process) to each record
+    /// and show it in the output,so the collector can detect the records dropped on the full channel or lost
+    /// in a crash by the gaps. the records of different threads may be written out of order with
+    /// `ProducerMode::Sharded`,sort them by the sequence number first
+    pub fn with_seq(mut self, seq: bool) -> Self {
+        self.seq = seq;
+        self
+    }
+
+    /// you need enable fast_log = { ... ,features=["encoding"]}
+    /// transcode the output of the console and file appenders,for example encoding_rs::GBK
+    #[cfg(feature = "encoding")]
+    pub fn encoding(mut self, encoding: &'static encoding_rs::Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// add an Interceptor to the chain changing the records on the worker thread before the format
+    /// and the appenders,the interceptors run in the order they are added,see [`Interceptor`]
+    pub fn interceptor<I: Interceptor + 'static>(mut self, interceptor: I) -> Self {
+        self.interceptors.push(Box::new(interceptor));
+        self
+    }
+
+    /// add a Scrubber rewriting the formatted records(and the messages of `print`) before any appender,
+    /// also the message and the key-values sent as fields by the gelf,otel,http,logcat and os_log appenders,
+    /// for example to mask the emails,IPs and JWTs. the scrubbers run by `Scrubber::order`,
+    /// then in the order they are added
+    /// ```rust
+    /// use fast_log::scrub::{EmailScrubber, IpScrubber, JwtScrubber};
+    /// use fast_log::Config;
+    /// let config = Config::new()
+    ///     .console()
+    ///     .scrubber(JwtScrubber::new())
+    ///     .scrubber(EmailScrubber::new())
+    ///     .scrubber(IpScrubber::new());
+    /// ```
+    pub fn scrubber<S: Scrubber + 'static>(mut self, scrubber: S) -> Self {
+        self.scrubbers.push(Box::new(scrubber));
+        self
+    }
+
+    /// append the records of the log macros to a memory-mapped journal of `size` at `path` before sending them
+    /// to the appenders,a record leaves the journal after all appenders wrote and flushed it,
+    /// so the records lost by a crash(in the channel or the buffers of the appenders) are written by the next `init`.
+    /// the records are flushed after each batch and never sharded,see [`journal`](crate::journal).
+    /// the journal keeps its previous size while it holds records of the previous process
+    /// you need enable fast_log = { ... ,features=["mmap"]}
+    /// ```rust,no_run
+    /// use fast_log::consts::LogSize;
+    /// use fast_log::Config;
+    /// fast_log::init(Config::new().file("target/logs/app.log").journal("target/logs/app.journal", LogSize::MB(1))).unwrap();
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn journal(mut self, path: &str, size: LogSize) -> Self {
+        self.journal = Some((path.to_string(), size));
+        self
+    }
+
+    /// write the effective config(version,level,appenders,rotation...) as the first record of target "fast_log",
+    /// it skips the level and the filter,so "why is nothing being logged" can be answered from the output
+    pub fn banner(mut self, banner: bool) -> Self {
+        self.banner = banner;
+        self
+    }
+
+    /// write the internal events of fast_log(rotation,pack and write failures,slow appenders,dropped records)
+    /// at or above `level` by the appenders as records of target "fast_log",default is LevelFilter::Off.
+    /// at most 10 events per second are written,and never block the appender threads
+    pub fn diagnostics(mut self, level: LevelFilter) -> Self {
+        self.diagnostics = level;
+        self
+    }
+
+    /// describe the effective config,see [`Config::banner`]
+    pub fn describe(&self) -> String {
+        let appenders: Vec<String> = self.appends.iter().map(|v| v.describe()).collect();
+        let mut s = format!(
+            "fast_log {} level={} chan_len={} format_on={:?} producer_mode={:?} non_blocking={} time_precision={:?} flush_level={} fsync={:?} appenders=[{}]",
+            env!("CARGO_PKG_VERSION"),
+            self.level,
+            match self.chan_len {
+                Some(v) => v.to_string(),
+                None => "unbounded".to_string(),
+            },
+            self.format_on,
+            self.producer_mode,
+            self.non_blocking,
+            self.time_precision,
+            self.flush_level,
+            self.fsync,
+            appenders.join(", ")
+        );
+        if let Some(audit) = &self.audit {
+            s.push_str(&format!(" audit={}", audit.describe()));
+        }
+        s
+    }
+
+    /// check the config and the paths of the file appenders,`init` fails with the first problem
+    /// instead of failing later on the worker thread
+    pub fn validate(&self) -> Result<(), LogError> {
+        if let Some(e) = self.errors.first() {
+            return Err(e.clone());
+        }
+        if self.appends.is_empty() && self.files.is_empty() {
+            return Err(LogError::Config("appenders can not be empty".to_string()));
+        }
+        for x in &self.files {
+            match x.split {
+                None => check_file(&x.path)?,
+                Some(zero) => check_split(&x.path, zero)?,
+            }
+        }
+        if self.chan_len == Some(0) {
+            return Err(LogError::Config(
+                "chan_len must be greater than 0,or None for the unbounded channel".to_string(),
+            ));
+        }
+        if self.batch.max_records == 0 || self.batch.max_bytes == 0 {
+            return Err(LogError::Config(
+                "batch_max_records and batch_max_bytes must be greater than 0".to_string(),
+            ));
+        }
+        if self.fsync == FsyncPolicy::EveryN(0) {
+            return Err(LogError::Config(
+                "FsyncPolicy::EveryN(0) is invalid,use FsyncPolicy::Always".to_string(),
+            ));
+        }
+        if self.flush_fsync && self.flush_level == LevelFilter::Off {
+            return Err(LogError::Config(
+                "flush_level(LevelFilter::Off, true) never fsync,set a flush level".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// can the log file be created and written,nothing is created
+fn check_file(file: &str) -> Result<(), LogError> {
+    let file = file.replace('\\', "/");
+    if Path::new(&file).exists() {
+        return match OpenOptions::new().append(true).open(&file) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(LogError::Config(format!(
+                "can not write the log file {}:{}",
+                file, e
+            ))),
+        };
+    }
+    match file.rfind('/') {
+        None => check_dir(""),
+        Some(right) => check_dir(&file[0..right]),
+    }
+}
+
+/// can the log directory of the split appenders be created and written
+fn check_split(file_path: &str, zero: bool) -> Result<(), LogError> {
+    if zero {
+        return Err(LogError::Config(format!(
+            "the max_temp_size of {} can not be zero",
+            file_path
+        )));
+    }
+    let file_path = file_path.replace('\\', "/");
+    match file_path.rfind('/') {
+        None => Ok(()),
+        Some(right) => check_dir(&file_path[0..right]),
+    }
+}
+
+/// can the log directory be created and written,
+/// a probe file is created in the nearest existing directory and removed
+fn check_dir(dir: &str) -> Result<(), LogError> {
+    static PROBE: AtomicUsize = AtomicUsize::new(0);
+    let mut existing = Path::new(dir);
+    while !existing.as_os_str().is_empty() && !existing.exists() {
+        existing = existing.parent().unwrap_or(Path::new(""));
+    }
+    if existing.as_os_str().is_empty() {
+        existing = Path::new(".");
+    }
+    let probe = existing.join(format!(
+        ".fast_log_probe_{}_{}",
+        std::process::id(),
+        PROBE.fetch_add(1, Ordering::Relaxed)
+    ));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            Ok(())
+        }
+        Err(e) => Err(LogError::Config(format!(
+            "the log directory {} is not writable:{}",
+            dir, e
+        ))),
+    }
 }
@@ -0,0 +1,196 @@
+use crate::consts::LogSize;
+use crate::date::TimePrecision;
+use crate::error::LogError;
+use crate::filter::ModuleFilter;
+use crate::plugin::file_split::{Packer, RollingType};
+use crate::plugin::packer::LogPacker;
+use crate::{Config, FastLogFormatJson};
+use log::LevelFilter;
+use serde::Deserialize;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// the declarative config,for example log.toml:
+/// ```toml
+/// level = "info"
+/// chan_len = 100000
+/// flush_level = "error"
+/// time_precision = "millis"
+/// format = "json"
+/// level_names = ["EROR", "WARN", "INFO", "DEBG", "TRAC"]
+/// exclude = ["hyper"]
+///
+/// [[appenders]]
+/// type = "console"
+///
+/// [[appenders]]
+/// type = "file_split"
+/// path = "target/logs/"
+/// max_size = "10MB"
+/// keep_num = 10
+/// packer = "zip"
+/// ```
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    /// off,error,warn,info,debug,trace
+    pub level: Option<String>,
+    /// 0 is the unbounded channel
+    pub chan_len: Option<usize>,
+    /// drop the records instead of blocking on the full channel
+    pub non_blocking: Option<bool>,
+    /// flush the appenders after writing a record at or above the level,for example "error"
+    pub flush_level: Option<String>,
+    /// fsync after the flush of flush_level
+    pub flush_fsync: Option<bool>,
+    /// seconds,millis,micros,nanos
+    pub time_precision: Option<String>,
+    /// text(default) or json
+    pub format: Option<String>,
+    /// the level names of the text format,ERROR,WARN,INFO,DEBUG,TRACE in order
+    pub level_names: Option<[String; 5]>,
+    /// only log the modules containing one of them
+    pub include: Option<Vec<String>>,
+    /// not log the modules containing one of them
+    pub exclude: Option<Vec<String>>,
+    pub appenders: Vec<AppenderConfig>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AppenderConfig {
+    Console,
+    File {
+        path: String,
+    },
+    FileLoop {
+        path: String,
+        max_size: String,
+    },
+    /// keep all packs if neither keep_num nor keep_secs is set
+    FileSplit {
+        path: String,
+        max_size: String,
+        keep_num: Option<i64>,
+        keep_secs: Option<u64>,
+        /// log(default),zip,lz4,gzip
+        packer: Option<String>,
+    },
+    RingBuffer {
+        capacity: usize,
+    },
+}
+
+impl FileConfig {
+    /// parse the toml,or the yaml if `yaml` is true
+    pub fn parse(data: &str, yaml: bool) -> Result<Self, LogError> {
+        if yaml {
+            serde_yaml::from_str(data).map_err(|e| LogError::Parse(e.to_string()))
+        } else {
+            toml::from_str(data).map_err(|e| LogError::Parse(e.to_string()))
+        }
+    }
+
+    pub fn to_config(&self) -> Result<Config, LogError> {
+        let mut config = Config::new();
+        if let Some(level) = &self.level {
+            config = config.level(
+                LevelFilter::from_str(level)
+                    .map_err(|_| LogError::Parse(format!("invalid level {}", level)))?,
+            );
+        }
+        if let Some(chan_len) = self.chan_len {
+            config = config.chan_len(if chan_len == 0 { None } else { Some(chan_len) });
+        }
+        if let Some(non_blocking) = self.non_blocking {
+            config = config.non_blocking(non_blocking);
+        }
+        if let Some(level) = &self.flush_level {
+            config = config.flush_level(
+                LevelFilter::from_str(level)
+                    .map_err(|_| LogError::Parse(format!("invalid flush_level {}", level)))?,
+                self.flush_fsync.unwrap_or(false),
+            );
+        }
+        if let Some(precision) = &self.time_precision {
+            config = config.time_precision(match precision.to_lowercase().as_str() {
+                "seconds" => TimePrecision::Seconds,
+                "millis" => TimePrecision::Millis,
+                "micros" => TimePrecision::Micros,
+                "nanos" => TimePrecision::Nanos,
+                _ => {
+                    return Err(LogError::Parse(format!(
+                        "invalid time_precision {}",
+                        precision
+                    )))
+                }
+            });
+        }
+        if let Some(names) = &self.level_names {
+            config = config.level_names(names.each_ref().map(|v| v.as_str()));
+        }
+        match self.format.as_deref() {
+            None | Some("text") => {}
+            Some("json") => config = config.format(FastLogFormatJson::new()),
+            Some(v) => return Err(LogError::Parse(format!("invalid format {}", v))),
+        }
+        if self.include.is_some() || self.exclude.is_some() {
+            config = config.filter(ModuleFilter::new(
+                self.include.clone(),
+                self.exclude.clone(),
+            ));
+        }
+        for x in &self.appenders {
+            config = match x {
+                AppenderConfig::Console => config.console(),
+                AppenderConfig::File { path } => config.file(path),
+                AppenderConfig::FileLoop { path, max_size } => {
+                    config.file_loop(path, LogSize::from_str(max_size)?)
+                }
+                AppenderConfig::FileSplit {
+                    path,
+                    max_size,
+                    keep_num,
+                    keep_secs,
+                    packer,
+                } => {
+                    let rolling = match (keep_num, keep_secs) {
+                        (Some(num), _) => RollingType::KeepNum(*num),
+                        (None, Some(secs)) => RollingType::KeepTime(Duration::from_secs(*secs)),
+                        (None, None) => RollingType::All,
+                    };
+                    let packer = packer_of(packer.as_deref().unwrap_or("log"))?;
+                    config.file_split_boxed(path, LogSize::from_str(max_size)?, rolling, packer)
+                }
+                AppenderConfig::RingBuffer { capacity } => config.ring_buffer(*capacity),
+            };
+        }
+        Ok(config)
+    }
+}
+
+fn packer_of(name: &str) -> Result<Box<dyn Packer>, LogError> {
+    match name {
+        "log" => Ok(Box::new(LogPacker {})),
+        #[cfg(feature = "zip")]
+        "zip" => Ok(Box::new(crate::plugin::packer::ZipPacker {})),
+        #[cfg(feature = "lz4")]
+        "lz4" => Ok(Box::new(crate::plugin::packer::LZ4Packer {})),
+        #[cfg(feature = "gzip")]
+        "gzip" => Ok(Box::new(crate::plugin::packer::GZipPacker {})),
+        _ => Err(LogError::Config(format!(
+            "unknown packer {},or the feature of the packer is not enabled",
+            name
+        ))),
+    }
+}
+
+impl Config {
+    /// load the config from a toml file,or a yaml file(.yaml,.yml)
+    /// you need enable fast_log = { ... ,features=["config_file"]}
+    pub fn from_file(path: &str) -> Result<Config, LogError> {
+        let data = std::fs::read_to_string(path)?;
+        let yaml = path.ends_with(".yaml") || path.ends_with(".yml");
+        FileConfig::parse(&data, yaml)?.to_config()
+    }
+}
@@ -1,4 +1,20 @@
+use crate::error::LogError;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+/// the binary(1024) size of the log files
+/// ```rust
+/// use fast_log::consts::LogSize;
+/// let size: LogSize = "1.5GB".parse().unwrap();
+/// assert_eq!(size, LogSize::MB(1536));
+/// assert_eq!(size.to_string(), "1536MB");
+/// assert_eq!(LogSize::MB(1) + LogSize::KB(512), LogSize::KB(1536));
+/// ```
+#[derive(Copy, Clone, Debug)]
 pub enum LogSize {
+    B(usize),
     KB(usize),
     MB(usize),
     GB(usize),
@@ -7,6 +23,7 @@ pub enum LogSize {
 impl LogSize {
     pub fn get_len(&self) -> usize {
         match self {
+            Self::B(b) => *b,
             Self::KB(kb) => {
                 return kb * 1024;
             }
@@ -18,4 +35,128 @@ impl LogSize {
             }
         }
     }
+
+    /// the size in the largest exact unit,for example 1536 => KB(1536/1024) is not exact,so B(1536)
+    pub fn from_bytes(len: usize) -> Self {
+        if len == 0 {
+            return Self::B(0);
+        }
+        for (unit, f) in [
+            (1024 * 1024 * 1024, Self::GB as fn(usize) -> Self),
+            (1024 * 1024, Self::MB),
+            (1024, Self::KB),
+        ] {
+            if len.is_multiple_of(unit) {
+                return f(len / unit);
+            }
+        }
+        Self::B(len)
+    }
+
+    /// the size in the largest exact unit
+    pub fn normalize(&self) -> Self {
+        Self::from_bytes(self.get_len())
+    }
+}
+
+impl From<LogSize> for usize {
+    fn from(v: LogSize) -> Self {
+        v.get_len()
+    }
+}
+
+impl From<LogSize> for u64 {
+    fn from(v: LogSize) -> Self {
+        v.get_len() as u64
+    }
+}
+
+impl Add for LogSize {
+    type Output = LogSize;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::from_bytes(self.get_len() + rhs.get_len())
+    }
+}
+
+/// saturates at zero
+impl Sub for LogSize {
+    type Output = LogSize;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::from_bytes(self.get_len().saturating_sub(rhs.get_len()))
+    }
+}
+
+/// compare the bytes,so KB(1024) == MB(1)
+impl PartialEq for LogSize {
+    fn eq(&self, other: &Self) -> bool {
+        self.get_len() == other.get_len()
+    }
+}
+
+impl Eq for LogSize {}
+
+impl PartialOrd for LogSize {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LogSize {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.get_len().cmp(&other.get_len())
+    }
+}
+
+/// display in the largest exact unit,for example "10MB","1536KB","100B"
+impl Display for LogSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.normalize() {
+            Self::B(v) => write!(f, "{}B", v),
+            Self::KB(v) => write!(f, "{}KB", v),
+            Self::MB(v) => write!(f, "{}MB", v),
+            Self::GB(v) => write!(f, "{}GB", v),
+        }
+    }
+}
+
+/// parse "100","100B","500kb","10MB","1.5GB","2GiB",the unit is case insensitive and the "B" is optional
+impl FromStr for LogSize {
+    type Err = LogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || LogError::Parse(format!("invalid log size {}", s));
+        let v = s.trim().to_uppercase();
+        let v = v.strip_suffix('B').unwrap_or(&v);
+        let v = v.strip_suffix('I').unwrap_or(v);
+        let (num, unit) = match v.char_indices().last() {
+            Some((i, c)) if c.is_ascii_alphabetic() => (&v[..i], c),
+            _ => (v, 'B'),
+        };
+        let unit: usize = match unit {
+            'B' => 1,
+            'K' => 1024,
+            'M' => 1024 * 1024,
+            'G' => 1024 * 1024 * 1024,
+            _ => {
+                return Err(LogError::Parse(format!(
+                    "invalid log size {},the unit must be B,KB,MB or GB",
+                    s
+                )))
+            }
+        };
+        let num = num.trim();
+        if let Ok(num) = num.parse::<usize>() {
+            return num
+                .checked_mul(unit)
+                .map(Self::from_bytes)
+                .ok_or_else(invalid);
+        }
+        let num: f64 = num.parse().map_err(|_| invalid())?;
+        if !num.is_finite() || num < 0.0 {
+            return Err(invalid());
+        }
+        Ok(Self::from_bytes((num * unit as f64).round() as usize))
+    }
 }
@@ -0,0 +1,319 @@
+use crate::embedded;
+use crate::error::LogError;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// the precision of the fractional seconds in log timestamp
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum TimePrecision {
+    /// 2022-08-19 09:53:47
+    Seconds,
+    /// 2022-08-19 09:53:47.798
+    Millis,
+    /// 2022-08-19 09:53:47.798674
+    Micros,
+    /// 2022-08-19 09:53:47.798674000
+    #[default]
+    Nanos,
+}
+
+impl TimePrecision {
+    /// digits of the fractional seconds
+    pub fn digits(&self) -> usize {
+        match self {
+            TimePrecision::Seconds => 0,
+            TimePrecision::Millis => 3,
+            TimePrecision::Micros => 6,
+            TimePrecision::Nanos => 9,
+        }
+    }
+
+    /// format time to local date string,fractional seconds are zero-padded to the precision
+    pub fn format(&self, time: SystemTime) -> String {
+        let mut s = String::with_capacity(29);
+        write_local_date(&mut s, time, self.digits());
+        s
+    }
+
+    /// append the local date of `format` to the bytes,without the intermediate `String`
+    pub fn format_into(&self, buf: &mut Vec<u8>, time: SystemTime) {
+        format_into(buf, time, self.digits());
+    }
+
+    fn from_u8(v: u8) -> Self {
+        match v {
+            0 => TimePrecision::Seconds,
+            1 => TimePrecision::Millis,
+            2 => TimePrecision::Micros,
+            _ => TimePrecision::Nanos,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            TimePrecision::Seconds => 0,
+            TimePrecision::Millis => 1,
+            TimePrecision::Micros => 2,
+            TimePrecision::Nanos => 3,
+        }
+    }
+}
+
+static TIME_PRECISION: AtomicU8 = AtomicU8::new(3);
+
+/// set the global time precision used by the default formats
+pub fn set_time_precision(precision: TimePrecision) {
+    TIME_PRECISION.store(precision.to_u8(), Ordering::Relaxed);
+}
+
+/// get the global time precision used by the default formats
+pub fn time_precision() -> TimePrecision {
+    TimePrecision::from_u8(TIME_PRECISION.load(Ordering::Relaxed))
+}
+
+static COARSE_TIME: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// the millisecond(or the second) and the digits of the last formatted date,and the date
+    static DATE_CACHE: RefCell<(i64, usize, String)> = const { RefCell::new((i64::MIN, 0, String::new())) };
+}
+
+/// the nanos since UNIX_EPOCH ticked by the coarse clock thread,0 if it is not ticking
+static COARSE_NOW: AtomicU64 = AtomicU64::new(0);
+static COARSE_TICKING: AtomicBool = AtomicBool::new(false);
+
+/// set the coarse time of the default formats,see [`Config::coarse_time`](crate::Config::coarse_time)
+pub fn set_coarse_time(coarse: bool) {
+    COARSE_TIME.store(coarse, Ordering::Relaxed);
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    if coarse {
+        start_coarse_clock();
+    }
+}
+
+/// tick the wall clock every millisecond while the coarse time is on,
+/// the records read the ticked time instead of the clock
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn start_coarse_clock() {
+    if COARSE_TICKING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    fn tick() {
+        let nanos = crate::runtime::system_now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        COARSE_NOW.store(nanos, Ordering::Relaxed);
+    }
+    tick();
+    let started = std::thread::Builder::new()
+        .name("fast_log_clock".to_string())
+        .spawn(|| {
+            while coarse_time() {
+                std::thread::sleep(Duration::from_millis(1));
+                tick();
+            }
+            COARSE_NOW.store(0, Ordering::Relaxed);
+            COARSE_TICKING.store(false, Ordering::SeqCst);
+        });
+    if started.is_err() {
+        COARSE_NOW.store(0, Ordering::Relaxed);
+        COARSE_TICKING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// the time ticked by the coarse clock,None if the coarse time is off
+pub(crate) fn coarse_now() -> Option<SystemTime> {
+    match COARSE_NOW.load(Ordering::Relaxed) {
+        0 => None,
+        nanos => Some(UNIX_EPOCH + Duration::from_nanos(nanos)),
+    }
+}
+
+pub fn coarse_time() -> bool {
+    COARSE_TIME.load(Ordering::Relaxed)
+}
+
+/// write the local date of `time` with `digits` of the fractional seconds.
+/// with the coarse time the digits are at most 3,
+/// and the date is formatted again only when the millisecond(or the second) changes
+pub fn write_local_date(buf: &mut String, time: SystemTime, digits: usize) {
+    let (secs, nanos) = local_secs(time);
+    if !coarse_time() {
+        embedded::write_date(buf, secs, nanos, digits);
+        return;
+    }
+    let digits = digits.min(3);
+    let key = match digits {
+        0 => secs,
+        _ => secs
+            .saturating_mul(1000)
+            .saturating_add((nanos / 1_000_000) as i64),
+    };
+    DATE_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.0 != key || cache.1 != digits {
+            cache.2.clear();
+            embedded::write_date(&mut cache.2, secs, nanos, digits);
+            cache.0 = key;
+            cache.1 = digits;
+        }
+        buf.push_str(&cache.2);
+    });
+}
+
+/// append the local date of `time` with `digits`(0..=9) of the zero-padded fractional seconds to the bytes,
+/// for example `2022-08-19 09:53:47.000798` with 6 digits
+pub fn format_into(buf: &mut Vec<u8>, time: SystemTime, digits: usize) {
+    let (secs, nanos) = local_secs(time);
+    embedded::write_date_bytes(buf, secs, nanos, digits);
+}
+
+/// the local time since the unix epoch in seconds and nanoseconds,negative before the epoch
+pub fn local_secs(time: SystemTime) -> (i64, u32) {
+    let (secs, nanos) = unix_timestamp(time);
+    (secs + crate::offset_sec() as i64, nanos)
+}
+
+/// the time since the unix epoch in seconds and nanoseconds,negative before the epoch.
+/// the time math is done by `SystemTime::checked_add` and `SystemTime::checked_sub`
+/// ```rust
+/// use fast_log::date::{from_unix_timestamp, unix_timestamp};
+/// let time = from_unix_timestamp(-1, 500_000_000).unwrap();
+/// assert_eq!(unix_timestamp(time), (-1, 500_000_000));
+/// ```
+pub fn unix_timestamp(time: SystemTime) -> (i64, u32) {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() as i64, d.subsec_nanos()),
+        Err(e) => {
+            let d = e.duration();
+            match d.subsec_nanos() {
+                0 => (-(d.as_secs() as i64), 0),
+                n => (-(d.as_secs() as i64) - 1, 1_000_000_000 - n),
+            }
+        }
+    }
+}
+
+/// the time of the seconds and nanoseconds since the unix epoch,
+/// None if `nanos` is not less than a second or the time is out of the range of SystemTime
+pub fn from_unix_timestamp(secs: i64, nanos: u32) -> Option<SystemTime> {
+    if nanos >= 1_000_000_000 {
+        return None;
+    }
+    if secs >= 0 {
+        UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+    } else {
+        UNIX_EPOCH
+            .checked_sub(Duration::from_secs(secs.unsigned_abs()))?
+            .checked_add(Duration::from_nanos(nanos as u64))
+    }
+}
+
+/// format the time as RFC3339 in the local offset,`digits` of the fractional seconds(at most 9),
+/// for example `2024-05-01T12:33:01.123+08:00`,`Z` if the offset is zero
+/// ```rust
+/// use std::time::{Duration, UNIX_EPOCH};
+/// let time = UNIX_EPOCH + Duration::from_millis(1_714_566_781_123);
+/// let s = fast_log::date::to_rfc3339(time, 3);
+/// assert_eq!(fast_log::date::parse_rfc3339(&s).unwrap(), time);
+/// ```
+pub fn to_rfc3339(time: SystemTime, digits: usize) -> String {
+    let (secs, nanos) = local_secs(time);
+    let mut s = String::with_capacity(35);
+    embedded::write_date(&mut s, secs, nanos, digits);
+    if let Some(i) = s.find(' ') {
+        s.replace_range(i..i + 1, "T");
+    }
+    let offset = crate::offset_sec();
+    if offset == 0 {
+        s.push('Z');
+    } else {
+        let sign = if offset < 0 { '-' } else { '+' };
+        let offset = offset.unsigned_abs() / 60;
+        write!(s, "{}{:02}:{:02}", sign, offset / 60, offset % 60);
+    }
+    s
+}
+
+/// parse the RFC3339/ISO-8601 time,for example `2024-05-01T12:33:01.123Z`,`2024-05-01 12:33:01+08:00`.
+/// the fractional seconds are optional,the time without an offset is the local time,
+/// so the timestamps written by fast_log are parsed back
+/// ```rust
+/// use std::time::{Duration, UNIX_EPOCH};
+/// let time = fast_log::date::parse_rfc3339("2024-05-01T12:33:01.123Z").unwrap();
+/// assert_eq!(time, UNIX_EPOCH + Duration::from_millis(1_714_566_781_123));
+/// assert_eq!(fast_log::date::parse_rfc3339("2024-05-01T14:33:01.123+02:00").unwrap(), time);
+/// ```
+pub fn parse_rfc3339(s: &str) -> Result<SystemTime, LogError> {
+    let invalid = || LogError::Parse(format!("invalid RFC3339 time {}", s));
+    let bytes = s.as_bytes();
+    if bytes.len() < 19
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return Err(invalid());
+    }
+    let num = |range: std::ops::Range<usize>| -> Result<u32, LogError> {
+        let v = s.get(range).ok_or_else(invalid)?;
+        if !v.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        v.parse().map_err(|_| invalid())
+    };
+    let (year, mon, day) = (num(0..4)? as i64, num(5..7)?, num(8..10)?);
+    let (hour, min, sec) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    let days_of_mon = match mon {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return Err(invalid()),
+    };
+    //60 is the leap second
+    if day == 0 || day > days_of_mon || hour > 23 || min > 59 || sec > 60 {
+        return Err(invalid());
+    }
+    let mut rest = &s[19..];
+    let mut nanos = 0;
+    if let Some(frac) = rest.strip_prefix('.') {
+        let len = frac.bytes().take_while(|b| b.is_ascii_digit()).count();
+        if len == 0 {
+            return Err(invalid());
+        }
+        //the digits after the nanoseconds are truncated
+        for (i, b) in frac.bytes().take(len.min(9)).enumerate() {
+            nanos += (b - b'0') as u32 * 10u32.pow(8 - i as u32);
+        }
+        rest = &frac[len..];
+    }
+    let offset = match rest {
+        "" => crate::offset_sec() as i64,
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return Err(invalid()),
+            };
+            let hm = rest[1..].replace(':', "");
+            if hm.len() != 4 || !hm.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let (h, m): (i64, i64) = (hm[0..2].parse().unwrap(), hm[2..4].parse().unwrap());
+            if h > 23 || m > 59 {
+                return Err(invalid());
+            }
+            sign * (h * 3600 + m * 60)
+        }
+    };
+    let secs = embedded::days_from_civil(year, mon, day) * 86400
+        + (hour * 3600 + min * 60 + sec) as i64
+        - offset;
+    from_unix_timestamp(secs, nanos).ok_or_else(invalid)
+}
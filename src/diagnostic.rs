@@ -0,0 +1,77 @@
+use crate::error::LogError;
+use crate::fast_log::level_filter;
+use crate::runtime::Instant;
+use log::{Level, LevelFilter};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+static ON_ERROR: RwLock<Option<fn(&LogError)>> = RwLock::new(None);
+
+/// the level of the internal events routed to the appenders,see `Config::diagnostics`
+static ROUTE_LEVEL: AtomicI32 = AtomicI32::new(0);
+
+/// at most so many routed events per second,so a failing appender can't flood itself
+const ROUTE_PER_SEC: u32 = 10;
+
+/// the start of the current second,the routed and the suppressed events in it
+static ROUTE_WINDOW: Mutex<Option<(Instant, u32, u64)>> = Mutex::new(None);
+
+/// route the internal events at or above `level` to the appenders,LevelFilter::Off disables it
+pub fn set_route_level(level: LevelFilter) {
+    ROUTE_LEVEL.store(level as i32, Ordering::Relaxed);
+}
+
+/// report an internal event of fast_log(rotation,pack failure...).
+/// with the feature "tracing" enabled,the event is also emitted as a tracing event with target "fast_log",
+/// so it appears in the application's existing tracing backend.
+/// with `Config::diagnostics`,the event is also written by the appenders as a record with target "fast_log"
+pub fn report(level: Level, msg: &str) {
+    if level <= level_filter(ROUTE_LEVEL.load(Ordering::Relaxed)) {
+        route(level, msg);
+    }
+    #[cfg(feature = "tracing")]
+    match level {
+        Level::Error => tracing::event!(target: "fast_log", tracing::Level::ERROR, "{}", msg),
+        Level::Warn => tracing::event!(target: "fast_log", tracing::Level::WARN, "{}", msg),
+        Level::Info => tracing::event!(target: "fast_log", tracing::Level::INFO, "{}", msg),
+        Level::Debug => tracing::event!(target: "fast_log", tracing::Level::DEBUG, "{}", msg),
+        Level::Trace => tracing::event!(target: "fast_log", tracing::Level::TRACE, "{}", msg),
+    }
+}
+
+/// send the event to the appenders without blocking,so it is safe on the appender threads
+fn route(level: Level, msg: &str) {
+    let suppressed = {
+        let mut window = ROUTE_WINDOW.lock().unwrap_or_else(|e| e.into_inner());
+        let (start, routed, suppressed) = window.get_or_insert((Instant::now(), 0, 0));
+        if start.elapsed() >= Duration::from_secs(1) {
+            *start = Instant::now();
+            *routed = 0;
+        }
+        if *routed >= ROUTE_PER_SEC {
+            *suppressed += 1;
+            return;
+        }
+        *routed += 1;
+        std::mem::take(suppressed)
+    };
+    let mut args = msg.replacen("[fast_log] ", "", 1);
+    if suppressed > 0 {
+        args.push_str(&format!(" ({} events suppressed before)", suppressed));
+    }
+    crate::fast_log::try_send_self_record(level, args);
+}
+
+/// set the callback of appender failures(disk full, broken pipe, rotation errors...)
+pub fn set_on_error(f: Option<fn(&LogError)>) {
+    *ON_ERROR.write().unwrap_or_else(|e| e.into_inner()) = f;
+}
+
+/// report an appender failure,the error is passed to the callback set by `Config::on_error`
+pub fn error(e: LogError) {
+    report(Level::Error, &e.to_string());
+    if let Some(f) = *ON_ERROR.read().unwrap_or_else(|e| e.into_inner()) {
+        f(&e);
+    }
+}
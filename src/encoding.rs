@@ -0,0 +1,69 @@
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicU8, Ordering};
+#[cfg(feature = "encoding")]
+use std::sync::RwLock;
+
+/// the record terminator
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum LineEnd {
+    /// "\n"
+    #[default]
+    LF,
+    /// "\r\n"
+    CRLF,
+    /// "\0",for `xargs -0`-style consumers
+    NUL,
+}
+
+impl LineEnd {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnd::LF => "\n",
+            LineEnd::CRLF => "\r\n",
+            LineEnd::NUL => "\0",
+        }
+    }
+
+    /// replace the trailing "\n" of a formatted record with this terminator
+    pub fn apply(&self, formated: &mut String) {
+        if *self != LineEnd::LF && formated.ends_with('\n') {
+            formated.pop();
+            formated.push_str(self.as_str());
+        }
+    }
+}
+
+static LINE_END: AtomicU8 = AtomicU8::new(0);
+
+/// set the line end of the formats,see [`Config::line_end`](crate::Config::line_end)
+pub fn set_line_end(line_end: LineEnd) {
+    LINE_END.store(line_end as u8, Ordering::Relaxed);
+}
+
+pub fn line_end() -> LineEnd {
+    match LINE_END.load(Ordering::Relaxed) {
+        1 => LineEnd::CRLF,
+        2 => LineEnd::NUL,
+        _ => LineEnd::LF,
+    }
+}
+
+#[cfg(feature = "encoding")]
+static ENCODING: RwLock<Option<&'static encoding_rs::Encoding>> = RwLock::new(None);
+
+/// you need enable fast_log = { ... ,features=["encoding"]}
+/// set the output encoding of the console and file appenders,for example encoding_rs::GBK
+#[cfg(feature = "encoding")]
+pub fn set_encoding(encoding: Option<&'static encoding_rs::Encoding>) {
+    *ENCODING.write().unwrap_or_else(|e| e.into_inner()) = encoding;
+}
+
+/// transcode the log data to the output encoding,default is UTF-8(not transcode)
+pub fn encode(data: &str) -> Cow<'_, [u8]> {
+    #[cfg(feature = "encoding")]
+    if let Some(encoding) = *ENCODING.read().unwrap_or_else(|e| e.into_inner()) {
+        let (bytes, _, _) = encoding.encode(data);
+        return bytes;
+    }
+    Cow::Borrowed(data.as_bytes())
+}
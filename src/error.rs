@@ -1,12 +1,30 @@
 use std::error::Error;
 use std::fmt;
 use std::fmt::Display;
+use std::sync::Arc;
 
 use log::SetLoggerError;
 
-#[derive(Clone, Debug)]
+/// the error of fast_log,new variants may come in a minor release
+#[derive(Debug)]
+#[non_exhaustive]
 pub enum LogError {
+    /// other errors
     E(String),
+    /// the config is invalid,see `Config::validate`
+    Config(String),
+    /// open,write,flush or sync fail
+    Io(std::io::Error),
+    /// init fail,for example another logger is already installed
+    Init(String),
+    /// parse fail,for example a level or a size
+    Parse(String),
+    /// the channel of the logger is closed
+    ChannelClosed,
+    /// the channel of the logger is full and the record is dropped,see `Config::non_blocking`
+    ChannelFull,
+    /// the packer(named by `Packer::pack_name`) fail to pack the rolled log file
+    Packer(String, Arc<dyn Error + Send + Sync>),
 }
 
 impl From<&str> for LogError {
@@ -23,7 +41,29 @@ impl From<std::string::String> for LogError {
 
 impl From<SetLoggerError> for LogError {
     fn from(arg: SetLoggerError) -> Self {
-        LogError::E(arg.to_string())
+        LogError::Init(arg.to_string())
+    }
+}
+
+impl From<std::io::Error> for LogError {
+    fn from(arg: std::io::Error) -> Self {
+        LogError::Io(arg)
+    }
+}
+
+impl Clone for LogError {
+    /// the clone of `Io` keeps the kind and the message of the io error,not its source
+    fn clone(&self) -> Self {
+        match self {
+            LogError::E(v) => LogError::E(v.clone()),
+            LogError::Config(v) => LogError::Config(v.clone()),
+            LogError::Io(e) => LogError::Io(std::io::Error::new(e.kind(), e.to_string())),
+            LogError::Init(v) => LogError::Init(v.clone()),
+            LogError::Parse(v) => LogError::Parse(v.clone()),
+            LogError::ChannelClosed => LogError::ChannelClosed,
+            LogError::ChannelFull => LogError::ChannelFull,
+            LogError::Packer(name, e) => LogError::Packer(name.clone(), e.clone()),
+        }
     }
 }
 
@@ -31,17 +71,40 @@ impl Display for LogError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         return match self {
             LogError::E(ref err) => {
-                write!(f, "Rbatis Error: {}", err)
+                write!(f, "[fast_log] {}", err)
+            }
+            LogError::Config(ref err) => {
+                write!(f, "[fast_log] invalid config: {}", err)
+            }
+            LogError::Io(ref err) => {
+                write!(f, "[fast_log] io fail:{}", err)
+            }
+            LogError::Init(ref err) => {
+                write!(f, "[fast_log] init fail:{}", err)
+            }
+            LogError::Parse(ref err) => {
+                write!(f, "[fast_log] parse fail:{}", err)
+            }
+            LogError::ChannelClosed => {
+                write!(f, "[fast_log] the channel of the logger is closed")
+            }
+            LogError::ChannelFull => {
+                write!(f, "[fast_log] the channel of the logger is full")
+            }
+            LogError::Packer(ref name, ref err) => {
+                write!(f, "[fast_log] {} packer fail:{}", name, err)
             }
         };
     }
 }
 
 impl Error for LogError {
-    fn description(&self) -> &str {
-        return match self {
-            LogError::E(data) => data.as_str(),
-        };
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LogError::Io(err) => Some(err),
+            LogError::Packer(_, err) => Some(err.as_ref()),
+            _ => None,
+        }
     }
 }
 
@@ -51,6 +114,37 @@ impl Default for LogError {
     }
 }
 
+/// the message was not logged and is given back,see [`try_print`](crate::try_print)
+#[derive(Clone, Debug)]
+pub enum TryPrintError {
+    /// the channel of the logger is full
+    Full(String),
+    /// the channel of the logger is closed
+    Closed(String),
+}
+
+impl TryPrintError {
+    /// the message given back
+    pub fn into_inner(self) -> String {
+        match self {
+            TryPrintError::Full(v) | TryPrintError::Closed(v) => v,
+        }
+    }
+}
+
+impl Display for TryPrintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TryPrintError::Full(_) => write!(f, "[fast_log] the channel of the logger is full"),
+            TryPrintError::Closed(_) => {
+                write!(f, "[fast_log] the channel of the logger is closed")
+            }
+        }
+    }
+}
+
+impl Error for TryPrintError {}
+
 pub trait AsStdResult<T>
 where
     T: Clone,
@@ -0,0 +1,39 @@
+//! the extension points of fast_log for the plugin crates,depend on this module only:
+//! * [`LogAppender`] write the batches of records(a file,a socket,a service...)
+//! * [`RecordFormat`] turn a record into the `formated` text
+//! * [`Filter`] drop the records before the channel
+//! * [`Packer`] pack the rolled files of `FileSplitAppender`
+//! * [`Interceptor`] change the records on the worker thread
+//! * [`Scrubber`] rewrite the formatted records
+//!
+//! semver: the items of this module follow the version of fast_log,a breaking change of a trait
+//! (a new required method,a changed signature) or of [`FastLogRecord`] happens in a major release only.
+//! the new methods of the traits come with a default,[`FastLogRecord::new`] builds a record
+//! with the other fields empty.
+//! the other modules may change the internals in a minor release.
+//!
+//! for example the appender of a plugin crate:
+//! ```rust
+//! use fast_log::ext::*;
+//!
+//! pub struct CountAppender {
+//!     count: std::cell::Cell<usize>,
+//! }
+//!
+//! impl LogAppender for CountAppender {
+//!     fn do_logs(&self, records: &[FastLogRecord]) {
+//!         let records = records.iter().filter(|v| v.command == Command::CommandRecord);
+//!         self.count.set(self.count.get() + records.count());
+//!     }
+//! }
+//!
+//! let config = fast_log::Config::new().custom(CountAppender {
+//!     count: std::cell::Cell::new(0),
+//! });
+//! ```
+pub use crate::appender::{Command, FastLogRecord, LogAppender, RecordFormat};
+pub use crate::error::LogError;
+pub use crate::filter::Filter;
+pub use crate::interceptor::Interceptor;
+pub use crate::plugin::file_split::Packer;
+pub use crate::scrub::Scrubber;
@@ -1,19 +1,41 @@
-use log::{LevelFilter, Log, Metadata, Record};
+use log::kv::{self, Key, Value, VisitSource};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::cell::Cell;
+use std::fmt::Write;
 use std::ops::Deref;
-use std::sync::atomic::{AtomicI32, AtomicI64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64, AtomicU64, AtomicUsize, Ordering};
 
-use crate::appender::{Command, FastLogRecord};
-use crate::config::Config;
-use crate::error::LogError;
-use crate::filter::Filter;
-use crate::{chan, spawn, Receiver, SendError, Sender, WaitGroup, try_send_num, RecvError};
+use crate::appender::{Command, FastLogRecord, KvValue, LogAppender, RecordFormat};
+use crate::config::{Batch, Config, FormatOn, FsyncPolicy, ProducerMode};
+use crate::date;
+use crate::diagnostic;
+use crate::encoding::LineEnd;
+use crate::error::{LogError, TryPrintError};
+use crate::filter::{Filter, NoFilter};
+use crate::formats::format_record;
+use crate::interceptor::{intercept, Interceptor};
+use crate::levels;
+use crate::metrics::{self, Counters, COUNTERS};
+use crate::pool;
+use crate::runtime::Instant;
+use crate::scrub;
+use crate::shard;
+use crate::sub_logger;
+use crate::FastLogFormat;
+use crate::{
+    chan, now, spawn, try_send_num, Receiver, RecvError, RecvTimeoutError, SendError, Sender,
+    WaitGroup,
+};
+use crossbeam_channel::TrySendError;
 use once_cell::sync::{Lazy, OnceCell};
 use std::result::Result::Ok;
-use std::sync::Arc;
-use std::time::SystemTime;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::ThreadId;
+use std::time::{Duration, SystemTime};
 
 pub struct Chan {
-    pub filter: OnceCell<Box<dyn Filter>>,
+    /// None before init() or after the logger is torn down
+    pub filter: RwLock<Option<Box<dyn Filter>>>,
     pub send: Sender<FastLogRecord>,
     pub recv: Receiver<FastLogRecord>,
 }
@@ -22,55 +44,310 @@ impl Chan {
     pub fn new(len: Option<usize>) -> Self {
         let (s, r) = chan(len);
         Chan {
-            filter: OnceCell::new(),
+            filter: RwLock::new(None),
             send: s,
             recv: r,
         }
     }
     pub fn set_filter(&self, f: Box<dyn Filter>) {
-        self.filter.get_or_init(|| f);
-        self.filter.get();
+        *self.filter.write().unwrap_or_else(|e| e.into_inner()) = Some(f);
+    }
+
+    pub fn take_filter(&self) -> Option<Box<dyn Filter>> {
+        self.filter
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+
+    /// None if the logger is not initialized,else is the record filtered
+    pub fn is_filter(&self, record: &Record) -> Option<bool> {
+        self.filter
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|f| f.filter(record))
+    }
+}
+
+/// the record of `print`,the message is written as is
+fn print_record(log: String) -> FastLogRecord {
+    FastLogRecord {
+        formated: log,
+        ..FastLogRecord::new(log::Level::Info, "", String::new())
     }
 }
 
+/// the meta data of an external message, see [`print_with`]
+#[derive(Clone, Debug)]
+pub struct PrintMeta {
+    pub level: Level,
+    pub target: String,
+    pub now: SystemTime,
+}
+
+impl PrintMeta {
+    pub fn new(level: Level, target: &str) -> Self {
+        Self {
+            level,
+            target: target.to_string(),
+            now: now(),
+        }
+    }
+
+    /// set the record timestamp,default is SystemTime::now()
+    pub fn now(mut self, now: SystemTime) -> Self {
+        self.now = now;
+        self
+    }
+}
+
+/// records emitted before init(),see [`pre_init`]
+struct PreInitBuffer {
+    max: usize,
+    records: Vec<FastLogRecord>,
+}
+
 pub struct Logger {
     level: AtomicI32,
+    sync_level: AtomicI32,
+    non_blocking: AtomicBool,
+    source: AtomicBool,
+    hostname: OnceCell<Arc<str>>,
+    with_hostname: AtomicBool,
+    with_pid: AtomicBool,
+    with_seq: AtomicBool,
+    /// the max bytes of a message,usize::MAX is unlimited
+    max_record_len: AtomicUsize,
     pub chan: Chan,
+    pre_init: Mutex<Option<PreInitBuffer>>,
+    /// set when formatting on the producer thread,see [`FormatOn`]
+    format: RwLock<Option<Arc<Box<dyn RecordFormat>>>>,
 }
 
 impl Logger {
     pub fn set_level(&self, level: LevelFilter) {
         self.level
             .swap(level as i32, std::sync::atomic::Ordering::Relaxed);
-        log::set_max_level(level);
+        log::set_max_level(levels::max_level(level));
     }
 
     pub fn get_level(&self) -> LevelFilter {
-        match self.level.load(std::sync::atomic::Ordering::Relaxed) {
-            0 => LevelFilter::Off,
-            1 => LevelFilter::Error,
-            2 => LevelFilter::Warn,
-            3 => LevelFilter::Info,
-            4 => LevelFilter::Debug,
-            5 => LevelFilter::Trace,
-            _ => panic!("error log level!"),
-        }
+        level_filter(self.level.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
+    /// records at or above this level are logged synchronously,see [`log_sync`]
+    pub fn set_sync_level(&self, level: LevelFilter) {
+        self.sync_level
+            .swap(level as i32, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn get_sync_level(&self) -> LevelFilter {
+        level_filter(self.sync_level.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
+    /// drop the records instead of blocking on the full channel,see [`Config::non_blocking`]
+    pub fn set_non_blocking(&self, non_blocking: bool) {
+        self.non_blocking.store(non_blocking, Ordering::Relaxed);
+    }
+
+    pub fn get_non_blocking(&self) -> bool {
+        self.non_blocking.load(Ordering::Relaxed)
     }
 
-    /// print no other info
+    /// print no other info,sent the same as `log` records(the journal,the shards and `Config::non_blocking`)
     pub fn print(&self, log: String) -> Result<(), SendError<FastLogRecord>> {
-        let fast_log_record = FastLogRecord {
-            command: Command::CommandRecord,
-            level: log::Level::Info,
-            target: "".to_string(),
-            args: "".to_string(),
-            module_path: "".to_string(),
-            file: "".to_string(),
-            line: None,
-            now: SystemTime::now(),
-            formated: log,
+        let non_blocking = self.get_non_blocking() || is_logger_thread();
+        self.dispatch(print_record(self.truncated(log)), non_blocking)
+            .map_err(|v| crossbeam_channel::SendError(*v))
+    }
+
+    /// print no other info if the channel has room,never blocks.
+    /// the message is given back if the channel is full or closed
+    pub fn try_print(&self, log: String) -> Result<(), TryPrintError> {
+        match self.chan.send.try_send(print_record(self.truncated(log))) {
+            Ok(_) => {
+                Counters::add(&COUNTERS.records_received, 1);
+                drain();
+                Ok(())
+            }
+            Err(e) => {
+                Counters::add(&COUNTERS.records_dropped, 1);
+                Err(match e {
+                    TrySendError::Full(v) => TryPrintError::Full(v.formated),
+                    TrySendError::Disconnected(v) => TryPrintError::Closed(v.formated),
+                })
+            }
+        }
+    }
+
+    /// print an external message with caller supplied meta data.
+    /// unlike `print`,the message is leveled,filtered,formatted and sent the same as `log` records
+    pub fn print_with(&self, meta: PrintMeta, log: String) -> Result<(), LogError> {
+        //the same level check as the log macros,the target levels are checked by send_log
+        if meta.level > log::max_level() {
+            return Ok(());
+        }
+        self.log_at(
+            &Record::builder()
+                .level(meta.level)
+                .target(&meta.target)
+                .module_path(Some(&meta.target))
+                .args(format_args!("{}", log))
+                .build(),
+            meta.now,
+        )
+    }
+
+    /// capture the name and id of the producing thread on records,see [`Config::with_source`]
+    pub fn set_source(&self, source: bool) {
+        self.source.store(source, Ordering::Relaxed);
+    }
+
+    pub fn get_source(&self) -> bool {
+        self.source.load(Ordering::Relaxed)
+    }
+
+    /// write the message into `buf`,truncated to [`Config::max_record_len`]
+    fn write_args(&self, buf: &mut String, args: &std::fmt::Arguments) {
+        let max = self.max_record_len.load(Ordering::Relaxed);
+        if max == usize::MAX {
+            buf.write_fmt(*args);
+            return;
+        }
+        let mut limited = LimitWriter {
+            buf,
+            max,
+            truncated: 0,
         };
-        LOGGER.chan.send.send(fast_log_record)
+        limited.write_fmt(*args);
+        let truncated = limited.truncated;
+        if truncated > 0 {
+            write!(buf, "…[truncated {} bytes]", truncated);
+        }
+    }
+
+    /// truncate the message of `print` or the string key-value to [`Config::max_record_len`]
+    fn truncated(&self, mut s: String) -> String {
+        let max = self.max_record_len.load(Ordering::Relaxed);
+        if s.len() <= max {
+            return s;
+        }
+        let mut end = max;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        let truncated = s.len() - end;
+        s.truncate(end);
+        write!(s, "…[truncated {} bytes]", truncated);
+        s
+    }
+
+    /// the name and id of the current thread,or empty if the source is not captured
+    fn current_thread(&self) -> (String, Option<ThreadId>) {
+        if !self.get_source() {
+            return (String::new(), None);
+        }
+        let thread = std::thread::current();
+        (
+            pool::copy(thread.name().unwrap_or_default()),
+            Some(thread.id()),
+        )
+    }
+
+    fn record_hostname(&self) -> Option<Arc<str>> {
+        if !self.with_hostname.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(self.hostname.get_or_init(|| Arc::from(hostname())).clone())
+    }
+
+    fn record_pid(&self) -> Option<u32> {
+        if !self.with_pid.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(std::process::id())
+    }
+
+    fn record_seq(&self) -> Option<u64> {
+        if !self.with_seq.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(SEQ.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// format the record if formatting on the producer thread,see [`FormatOn`]
+    fn producer_format(&self, record: &mut FastLogRecord) {
+        if let Some(format) = self
+            .format
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            format_record(format.as_ref().as_ref(), record);
+        }
+    }
+
+    /// send a record of the log macros,retry a few times and then block on the full channel
+    /// unless `non_blocking`,see [`Config::non_blocking`],[`Config::producer_mode`] and `Config::journal`.
+    /// gives back the record if it is not sent
+    fn send_record(
+        &self,
+        record: FastLogRecord,
+        non_blocking: bool,
+    ) -> Result<(), Box<FastLogRecord>> {
+        #[cfg(feature = "mmap")]
+        if crate::journal::enabled() {
+            return crate::journal::send(&self.chan.send, record, non_blocking);
+        }
+        match (shard::enabled(), non_blocking) {
+            (true, false) => shard::push(record).or_else(|v| {
+                try_send_num(&self.chan.send, 3, *v).map_err(|e| Box::new(e.into_inner()))
+            }),
+            (false, false) => {
+                try_send_num(&self.chan.send, 3, record).map_err(|e| Box::new(e.into_inner()))
+            }
+            (true, true) => shard::try_push(record).or_else(|v| {
+                self.chan
+                    .send
+                    .try_send(*v)
+                    .map_err(|e| Box::new(e.into_inner()))
+            }),
+            (false, true) => self
+                .chan
+                .send
+                .try_send(record)
+                .map_err(|e| Box::new(e.into_inner())),
+        }
+    }
+
+    /// send the record with [`Logger::send_record`],count it as received or dropped and wake the worker
+    fn dispatch(
+        &self,
+        record: FastLogRecord,
+        non_blocking: bool,
+    ) -> Result<(), Box<FastLogRecord>> {
+        let sent = self.send_record(record, non_blocking);
+        match sent {
+            Ok(_) => Counters::add(&COUNTERS.records_received, 1),
+            Err(_) => {
+                Counters::add(&COUNTERS.records_dropped, 1);
+                report_dropped();
+            }
+        }
+        drain();
+        sent
+    }
+
+    /// send a record of the logger,blocks on the full channel
+    fn send(&self, record: FastLogRecord) -> Result<(), LogError> {
+        let r = self.chan.send.send(record);
+        match r {
+            Ok(_) => Counters::add(&COUNTERS.records_received, 1),
+            Err(_) => Counters::add(&COUNTERS.records_dropped, 1),
+        }
+        drain();
+        r.map_err(|_| LogError::ChannelClosed)
     }
 
     pub fn wait(&self) {
@@ -80,27 +357,10 @@ impl Logger {
 
 impl Log for Logger {
     fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.level() <= self.get_level()
+        metadata.level() <= levels::target_level(metadata.target())
     }
     fn log(&self, record: &Record) {
-        //send
-        let f = LOGGER.chan.filter.get();
-        if f.is_some() {
-            if !f.as_ref().unwrap().filter(record) {
-                let fast_log_record = FastLogRecord {
-                    command: Command::CommandRecord,
-                    level: record.level(),
-                    target: record.metadata().target().to_string(),
-                    args: record.args().to_string(),
-                    module_path: record.module_path().unwrap_or_default().to_string(),
-                    file: record.file().unwrap_or_default().to_string(),
-                    line: record.line().clone(),
-                    now: SystemTime::now(),
-                    formated: String::new(),
-                };
-                try_send_num(&LOGGER.chan.send, 3, fast_log_record);
-            }
-        }
+        self.log_at(record, now());
     }
     fn flush(&self) {
         match flush() {
@@ -112,9 +372,410 @@ impl Log for Logger {
     }
 }
 
+impl Logger {
+    /// log the record without blocking on the full channel,the record is dropped instead
+    pub(crate) fn try_log(&self, record: &Record) {
+        self.send_log(record, now(), true);
+    }
+
+    /// send the record logged at `now`,and flush at or above the sync level
+    fn log_at(&self, record: &Record, now: SystemTime) -> Result<(), LogError> {
+        //the threads of the logger never wait for the worker,it may be waiting for them
+        let logger_thread = is_logger_thread();
+        let sent = self.send_log(record, now, self.get_non_blocking() || logger_thread)?;
+        if sent && record.level() <= self.get_sync_level() && !logger_thread {
+            self.flush();
+        }
+        Ok(())
+    }
+
+    /// the record of the log macros,without the seq and the formatted line
+    fn log_record(&self, record: &Record, now: SystemTime) -> FastLogRecord {
+        let mut args = pool::take();
+        self.write_args(&mut args, record.args());
+        let (thread_name, thread_id) = self.current_thread();
+        let (trace_id, span_id) = current_trace();
+        FastLogRecord {
+            command: Command::CommandRecord,
+            level: record.level(),
+            target: pool::copy(record.metadata().target()),
+            args,
+            module_path: pool::copy(record.module_path().unwrap_or_default()),
+            file: pool::copy(record.file().unwrap_or_default()),
+            line: record.line(),
+            thread_name,
+            thread_id,
+            hostname: self.record_hostname(),
+            pid: self.record_pid(),
+            seq: None,
+            journal_end: None,
+            kv: with_tracing_span(key_values(record)),
+            trace_id,
+            span_id,
+            now,
+            formated: String::new(),
+        }
+    }
+
+    /// filter and send the record,or keep it in the pre init buffer.
+    /// Ok(true) if the record is sent to the channel,Ok(false) if it is filtered or kept,
+    /// Err if it is dropped
+    /// None before init,true if the record is dropped by the target levels,the filter or its sub-logger.
+    /// the summaries held back by the filter are sent before the record
+    fn filtered(&self, record: &Record, non_blocking: bool) -> Option<bool> {
+        //the max level of the log macros is raised by the target levels
+        if levels::has_target_levels() && !self.enabled(record.metadata()) {
+            return Some(true);
+        }
+        let filtered = LOGGER.chan.is_filter(record);
+        for x in take_held() {
+            let _ = self.dispatch(x, non_blocking);
+        }
+        Some(filtered? || sub_logger::filter(record))
+    }
+
+    /// write the record by the appender threads ahead of the queued records and wait until they flushed it.
+    /// before init and on the threads of the logger it is sent like the records of `log`
+    fn write_sync(&self, record: &Record) -> Result<(), LogError> {
+        if is_logger_thread() {
+            return self.log_at(record, now());
+        }
+        match self.filtered(record, false) {
+            Some(true) => return Ok(()),
+            Some(false) => {}
+            None => return self.log_at(record, now()),
+        }
+        let sync = SYNC.read().unwrap_or_else(|e| e.into_inner());
+        let writer = match sync.as_ref() {
+            Some(v) => v,
+            None => return Err(LogError::ChannelClosed),
+        };
+        let mut x = self.log_record(record, now());
+        x.seq = self.record_seq();
+        format_record(writer.format.as_ref().as_ref(), &mut x);
+        scrub::scrub_record(&mut x);
+        writer.line_end.apply(&mut x.formated);
+        Counters::add(&COUNTERS.records_received, 1);
+        let wg = WaitGroup::new();
+        let data = Arc::new(vec![x, flush_record(wg.clone())]);
+        let mut closed = false;
+        for sender in &writer.senders {
+            closed |= sender.send(data.clone()).is_err();
+        }
+        drop(data);
+        drop(sync);
+        wg.wait();
+        match closed {
+            true => Err(LogError::ChannelClosed),
+            false => Ok(()),
+        }
+    }
+
+    fn send_log(
+        &self,
+        record: &Record,
+        now: SystemTime,
+        non_blocking: bool,
+    ) -> Result<bool, LogError> {
+        //send
+        if let Some(filtered) = self.filtered(record, non_blocking) {
+            if filtered {
+                return Ok(false);
+            }
+            let mut fast_log_record = self.log_record(record, now);
+            fast_log_record.seq = self.record_seq();
+            fast_log_record.formated = pool::take();
+            self.producer_format(&mut fast_log_record);
+            return match self.dispatch(fast_log_record, non_blocking) {
+                Ok(_) => Ok(true),
+                Err(_) => Err(LogError::ChannelFull),
+            };
+        }
+        //not init,keep it in the pre init buffer
+        let mut pre_init = self.pre_init.lock().unwrap_or_else(|e| e.into_inner());
+        if pre_init.is_none() && self.chan.is_filter(record).is_some() {
+            //init replayed the buffer while waiting for it,send after the replayed records
+            drop(pre_init);
+            return self.send_log(record, now, non_blocking);
+        }
+        //the pre init buffer is full,or the logger is stopped
+        let full = match pre_init.as_mut() {
+            Some(buffer) if buffer.records.len() < buffer.max => {
+                buffer.records.push(self.log_record(record, now));
+                return Ok(false);
+            }
+            Some(_) => LogError::ChannelFull,
+            None => LogError::ChannelClosed,
+        };
+        Counters::add(&COUNTERS.records_dropped, 1);
+        Err(full)
+    }
+}
+
+/// write up to `max` bytes and count the rest,the large messages are not copied
+struct LimitWriter<'a> {
+    buf: &'a mut String,
+    max: usize,
+    truncated: usize,
+}
+
+impl Write for LimitWriter<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        if self.truncated == 0 && self.buf.len() + s.len() <= self.max {
+            self.buf.push_str(s);
+            return Ok(());
+        }
+        let mut end = match self.truncated {
+            0 => self.max.saturating_sub(self.buf.len()).min(s.len()),
+            _ => 0,
+        };
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.buf.push_str(&s[..end]);
+        self.truncated += s.len() - end;
+        Ok(())
+    }
+}
+
+thread_local! {
+    /// the worker and the appender threads,they never wait for the logger
+    static LOGGER_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
+/// is the current thread the worker or an appender thread of the logger
+pub(crate) fn is_logger_thread() -> bool {
+    LOGGER_THREAD.with(|v| v.get())
+}
+
+pub(crate) fn set_logger_thread() {
+    LOGGER_THREAD.with(|v| v.set(true));
+}
+
 static CHAN_LEN: AtomicI64 = AtomicI64::new(-1);
+static START: OnceCell<Instant> = OnceCell::new();
+/// the second since START of the last report of dropped records
+static DROP_REPORTED: AtomicU64 = AtomicU64::new(u64::MAX);
+/// report the appenders whose do_logs takes longer
+const SLOW_APPENDER: Duration = Duration::from_secs(1);
+static BATCH: RwLock<Option<Batch>> = RwLock::new(None);
+/// is the logger installed by log::set_logger
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static RUNNING: Mutex<Option<Running>> = Mutex::new(None);
+
+/// the id of an appender added by [`add_appender`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AppenderId(u64);
+
+static NEXT_APPENDER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// the channel giving back the removed appender,None if the id is unknown
+type Reply = Sender<Option<Box<dyn LogAppender>>>;
+
+/// the appenders added and removed after init,applied by the worker before the next batch
+enum Control {
+    Add(AppenderId, Box<dyn LogAppender>),
+    Remove(AppenderId, Reply),
+}
+
+static CONTROL: Mutex<Vec<Control>> = Mutex::new(Vec::new());
+static CONTROL_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// the summaries made by the filters in `Filter::filter`,see [`hold`]
+static HELD: Mutex<Vec<FastLogRecord>> = Mutex::new(Vec::new());
+static HELD_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// the appenders,the format and the interceptors given back by the worker after exit
+type WorkerParts = (
+    Vec<Box<dyn LogAppender>>,
+    Arc<Box<dyn RecordFormat>>,
+    Vec<Box<dyn Interceptor>>,
+);
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+type Worker = std::thread::JoinHandle<WorkerParts>;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+type Worker = Direct;
+
+/// the worker without threads on wasm32-unknown-unknown,the queued records are written by the caller
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+struct Direct {
+    appenders: Vec<Box<dyn LogAppender>>,
+    /// the ids of the appenders added by add_appender,None for the appenders of the config
+    ids: Vec<Option<AppenderId>>,
+    format: Arc<Box<dyn RecordFormat>>,
+    interceptors: Vec<Box<dyn Interceptor>>,
+    line_end: LineEnd,
+    flush_level: LevelFilter,
+    flush_fsync: bool,
+    exited: bool,
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+impl Direct {
+    fn new(
+        appenders: Vec<Box<dyn LogAppender>>,
+        format: Arc<Box<dyn RecordFormat>>,
+        interceptors: Vec<Box<dyn Interceptor>>,
+        line_end: LineEnd,
+        flush_level: LevelFilter,
+        flush_fsync: bool,
+    ) -> Self {
+        for appender in &appenders {
+            appender.open();
+        }
+        Self {
+            ids: vec![None; appenders.len()],
+            appenders,
+            format,
+            interceptors,
+            line_end,
+            flush_level,
+            flush_fsync,
+            exited: false,
+        }
+    }
+
+    /// write the queued records until the channel is empty or the exit command
+    fn drain(&mut self) {
+        while !self.exited {
+            let mut records: Vec<FastLogRecord> = LOGGER.chan.recv.try_iter().collect();
+            if records.is_empty() {
+                break;
+            }
+            flush_filter(&mut records);
+            let mut records = intercept(&mut self.interceptors, records);
+            let mut exit = false;
+            let mut flush = false;
+            let mut need_flush = false;
+            for x in &mut records {
+                if x.formated.is_empty() {
+                    format_record(self.format.as_ref().as_ref(), x);
+                }
+                scrub::scrub_record(x);
+                self.line_end.apply(&mut x.formated);
+                match x.command {
+                    Command::CommandRecord => {
+                        if x.level <= self.flush_level {
+                            need_flush = true;
+                        }
+                    }
+                    Command::CommandExit => exit = true,
+                    Command::CommandFlush(_) => flush = true,
+                }
+            }
+            for appender in &self.appenders {
+                appender.do_logs(&records);
+                if flush || need_flush {
+                    appender.flush();
+                }
+                if need_flush && self.flush_fsync {
+                    appender.sync();
+                }
+                if exit {
+                    appender.close();
+                }
+            }
+            count_written(&records);
+            self.exited = exit;
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.exited
+    }
+
+    fn add(&mut self, id: AppenderId, appender: Box<dyn LogAppender>) {
+        self.drain();
+        appender.open();
+        self.appenders.push(appender);
+        self.ids.push(Some(id));
+    }
+
+    fn remove(&mut self, id: AppenderId) -> Option<Box<dyn LogAppender>> {
+        self.drain();
+        let index = self.ids.iter().position(|v| *v == Some(id))?;
+        self.ids.remove(index);
+        let appender = self.appenders.remove(index);
+        appender.close();
+        Some(appender)
+    }
+
+    fn join(mut self) -> std::thread::Result<WorkerParts> {
+        self.drain();
+        //the added appenders are closed and dropped
+        let appenders = self
+            .appenders
+            .into_iter()
+            .zip(self.ids)
+            .filter_map(|(appender, id)| id.is_none().then_some(appender))
+            .collect();
+        Ok((appenders, self.format, self.interceptors))
+    }
+}
+
+/// write the queued records on the caller,there is no worker thread on wasm32-unknown-unknown.
+/// skipped while init/teardown holds the logger or an appender logs while writing,
+/// the records are written by the outer call
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn drain() {
+    if let Ok(mut running) = RUNNING.try_lock() {
+        if let Some(running) = running.as_mut() {
+            running.worker.drain();
+        }
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+#[inline]
+fn drain() {}
+
+/// the running logger,the config keeps the settings without the appenders,filter and format
+struct Running {
+    worker: Worker,
+    config: Config,
+}
+
+/// the appender of `audit`,written synchronously by the caller
+struct Audit {
+    appender: Box<dyn LogAppender>,
+    format: Arc<Box<dyn RecordFormat>>,
+    line_end: LineEnd,
+}
+
+static AUDIT: Mutex<Option<Audit>> = Mutex::new(None);
+
+/// the appender threads and the format of the worker,`log_sync` writes to them without the worker
+struct SyncWriter {
+    senders: Vec<Sender<Arc<Vec<FastLogRecord>>>>,
+    format: Arc<Box<dyn RecordFormat>>,
+    line_end: LineEnd,
+}
+
+static SYNC: RwLock<Option<SyncWriter>> = RwLock::new(None);
+
+/// the batch tuning of the running logger,for the custom appenders that batch on their own
+pub fn batch() -> Batch {
+    BATCH
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or_default()
+}
+/// the next sequence number,see [`Config::with_seq`]
+static SEQ: AtomicU64 = AtomicU64::new(1);
+
 pub static LOGGER: Lazy<Logger> = Lazy::new(|| Logger {
     level: AtomicI32::new(1),
+    sync_level: AtomicI32::new(0),
+    non_blocking: AtomicBool::new(false),
+    source: AtomicBool::new(false),
+    hostname: OnceCell::new(),
+    with_hostname: AtomicBool::new(false),
+    with_pid: AtomicBool::new(false),
+    with_seq: AtomicBool::new(false),
+    max_record_len: AtomicUsize::new(usize::MAX),
+    pre_init: Mutex::new(None),
+    format: RwLock::new(None),
     chan: Chan::new({
         let len = CHAN_LEN.load(Ordering::SeqCst);
         match len {
@@ -124,10 +785,183 @@ pub static LOGGER: Lazy<Logger> = Lazy::new(|| Logger {
     }),
 });
 
+/// install the logger before init(),records emitted before init() are buffered(at most `max_records`)
+/// and replayed through the pipeline once init() completes,so early startup logs from libraries aren't lost.
+pub fn pre_init(max_records: usize) -> Result<(), LogError> {
+    *LOGGER.pre_init.lock().unwrap_or_else(|e| e.into_inner()) = Some(PreInitBuffer {
+        max: max_records,
+        records: vec![],
+    });
+    log::set_logger(LOGGER.deref()).map(|()| log::set_max_level(LevelFilter::Trace))?;
+    INSTALLED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// the name of the current host,from the environment or the kernel
+pub(crate) fn hostname() -> String {
+    for key in ["HOSTNAME", "COMPUTERNAME"] {
+        if let Ok(v) = std::env::var(key) {
+            if !v.trim().is_empty() {
+                return v.trim().to_string();
+            }
+        }
+    }
+    for path in ["/proc/sys/kernel/hostname", "/etc/hostname"] {
+        if let Ok(v) = std::fs::read_to_string(path) {
+            if !v.trim().is_empty() {
+                return v.trim().to_string();
+            }
+        }
+    }
+    "localhost".to_string()
+}
+
+/// the trace id and the span id of the current OpenTelemetry context,
+/// None outside of a valid context
+fn current_trace() -> (Option<u128>, Option<u64>) {
+    #[cfg(feature = "otel")]
+    {
+        use opentelemetry::trace::TraceContextExt;
+        let cx = opentelemetry::Context::current();
+        let span = cx.span().span_context().clone();
+        if span.is_valid() {
+            return (
+                Some(u128::from_be_bytes(span.trace_id().to_bytes())),
+                Some(u64::from_be_bytes(span.span_id().to_bytes())),
+            );
+        }
+    }
+    (None, None)
+}
+
+/// append the id of the current tracing span as the `tracing_span_id` key-value,
+/// the id is local to the subscriber,so it is kept apart from the OpenTelemetry span_id
+#[cfg(feature = "tracing")]
+fn with_tracing_span(mut kv: Vec<(String, KvValue)>) -> Vec<(String, KvValue)> {
+    if tracing::dispatcher::has_been_set() {
+        if let Some(id) = tracing::Span::current().id() {
+            kv.push(("tracing_span_id".to_string(), KvValue::U64(id.into_u64())));
+        }
+    }
+    kv
+}
+
+#[cfg(not(feature = "tracing"))]
+fn with_tracing_span(kv: Vec<(String, KvValue)>) -> Vec<(String, KvValue)> {
+    kv
+}
+
+/// the structured key-values of the record,the booleans and numbers keep their type,
+/// the strings are truncated to [`Config::max_record_len`]
+pub(crate) fn key_values(record: &Record) -> Vec<(String, KvValue)> {
+    struct Collect(Vec<(String, KvValue)>);
+    impl<'kvs> VisitSource<'kvs> for Collect {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+            let value = match KvValue::from_value(&value) {
+                KvValue::Str(v) => KvValue::Str(LOGGER.truncated(v)),
+                v => v,
+            };
+            self.0.push((key.to_string(), value));
+            Ok(())
+        }
+    }
+    let mut collect = Collect(vec![]);
+    record.key_values().visit(&mut collect);
+    collect.0
+}
+
+pub(crate) fn level_filter(v: i32) -> LevelFilter {
+    match v {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        5 => LevelFilter::Trace,
+        _ => panic!("error log level!"),
+    }
+}
+
+fn is_filter(filter: &dyn Filter, record: &FastLogRecord) -> bool {
+    filter.filter(
+        &Record::builder()
+            .level(record.level)
+            .target(&record.target)
+            .module_path(Some(&record.module_path))
+            .file(Some(&record.file))
+            .line(record.line)
+            .key_values(&record.kv)
+            .args(format_args!("{}", record.args))
+            .build(),
+    )
+}
+
 pub fn init(config: Config) -> Result<&'static Logger, LogError> {
-    if config.appends.is_empty() {
-        return Err(LogError::from("[fast_log] appenders can not be empty!"));
+    config.validate()?;
+    let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    let started = start(&mut running, config)?;
+    drop(running);
+    started.replay();
+    Ok(LOGGER.deref())
+}
+
+/// the logger started by [`start`],replayed after the lock of the running logger is released
+struct Started {
+    /// the filter replaced by the started logger
+    filter: Option<Box<dyn Filter>>,
+    /// the records of the journal the previous process didn't write
+    #[cfg(feature = "mmap")]
+    journal: Option<(String, Vec<FastLogRecord>)>,
+}
+
+impl Started {
+    fn replay(self) {
+        drain();
+        #[cfg(feature = "mmap")]
+        if let Some((path, records)) = self.journal {
+            if !records.is_empty() {
+                diagnostic::report(
+                    Level::Warn,
+                    &format!(
+                        "[fast_log] replay {} records of the journal {}",
+                        records.len(),
+                        path
+                    ),
+                );
+            }
+            for mut x in records {
+                x.hostname = LOGGER.record_hostname();
+                x.seq = LOGGER.record_seq();
+                //still in the journal,released after the appenders write them
+                LOGGER.send(x);
+            }
+        }
+    }
+}
+
+/// start the worker and the appenders of the validated `config` into `running`,
+/// the filter of `config` replaces the installed one at once
+fn start(running: &mut Option<Running>, mut config: Config) -> Result<Started, LogError> {
+    if running.is_some() {
+        return Err(LogError::Init(
+            "the logger is already initialized".to_string(),
+        ));
+    }
+    //fail before any record is sent or any global setting is changed,nothing is left to roll back
+    if !INSTALLED.load(Ordering::SeqCst) {
+        log::set_logger(LOGGER.deref())?;
+        INSTALLED.store(true, Ordering::SeqCst);
     }
+    config.create_files();
+    //the records the previous process didn't write,replayed after the worker starts
+    #[cfg(feature = "mmap")]
+    let journal = match &config.journal {
+        Some((path, size)) if !config.appends.is_empty() => Some((
+            path.clone(),
+            crate::journal::open(path, size.get_len(), config.appends.len())?,
+        )),
+        _ => None,
+    };
     match config.chan_len {
         None => {
             CHAN_LEN.store(-1, Ordering::SeqCst);
@@ -136,151 +970,1044 @@ pub fn init(config: Config) -> Result<&'static Logger, LogError> {
             CHAN_LEN.store(v as i64, Ordering::SeqCst);
         }
     }
+    START.get_or_init(Instant::now);
+    pool::init(config.pool_size);
     LOGGER.set_level(config.level);
-    LOGGER.chan.set_filter(config.filter);
+    if LOGGER.chan.recv.capacity() != config.chan_len {
+        diagnostic::report(
+            Level::Warn,
+            "[fast_log] the channel is created before init(),chan_len is ignored",
+        );
+    }
+    LOGGER.set_sync_level(config.sync_level);
+    LOGGER.set_non_blocking(config.non_blocking);
+    LOGGER.set_source(config.source);
+    LOGGER
+        .with_hostname
+        .store(config.hostname, Ordering::Relaxed);
+    LOGGER.with_pid.store(config.pid, Ordering::Relaxed);
+    LOGGER.with_seq.store(config.seq, Ordering::Relaxed);
+    LOGGER.max_record_len.store(
+        config.max_record_len.unwrap_or(usize::MAX),
+        Ordering::Relaxed,
+    );
+    date::set_time_precision(config.time_precision);
+    date::set_coarse_time(config.coarse_time);
+    crate::clock::set_clock(config.clock.clone());
+    crate::formats::set_level_names(config.level_names.clone());
+    crate::encoding::set_line_end(config.line_end);
+    scrub::set_scrubbers(std::mem::take(&mut config.scrubbers));
+    diagnostic::set_on_error(config.on_error);
+    diagnostic::set_route_level(config.diagnostics);
+    //the worker of wasm32-unknown-unknown reads the channel only
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    shard::set_enabled(config.producer_mode == ProducerMode::Sharded);
+    crate::plugin::file_split::set_on_rotate(config.on_rotate);
+    crate::plugin::file_split::set_pack_workers(config.pack_workers);
+    #[cfg(feature = "encoding")]
+    crate::encoding::set_encoding(config.encoding);
+    let banner = config.banner.then(|| config.describe());
+    if let Some(banner) = banner {
+        LOGGER.send(self_record(Level::Info, banner));
+    }
+    //the records buffered before init go before the records logged once the filter is installed,
+    //the buffer is held until then
+    let mut pre_init = LOGGER.pre_init.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(buffer) = pre_init.take() {
+        for mut x in buffer.records {
+            x.hostname = LOGGER.record_hostname();
+            x.pid = LOGGER.record_pid();
+            let filtered =
+                x.level > levels::target_level(&x.target) || is_filter(config.filter.as_ref(), &x);
+            for held in take_held() {
+                LOGGER.send(held);
+            }
+            if !filtered {
+                x.seq = LOGGER.record_seq();
+                LOGGER.send(x);
+            }
+        }
+    }
+    let filter = LOGGER
+        .chan
+        .filter
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .replace(std::mem::replace(&mut config.filter, Box::new(NoFilter {})));
+    drop(pre_init);
+    #[cfg(feature = "admin")]
+    let filter = crate::admin::reset_filter(filter);
     //main recv data
-    let appenders = config.appends;
-    let format = Arc::new(config.format);
+    let appenders = std::mem::take(&mut config.appends);
+    let mut interceptors = std::mem::take(&mut config.interceptors);
+    let format = Arc::new(std::mem::replace(
+        &mut config.format,
+        Box::new(FastLogFormat::new()),
+    ));
+    if config.format_on == FormatOn::Producer {
+        *LOGGER.format.write().unwrap_or_else(|e| e.into_inner()) = Some(format.clone());
+    }
     let level = config.level;
     let chan_len = config.chan_len;
-    let r = log::set_logger(LOGGER.deref()).map(|()| log::set_max_level(level));
-    if r.is_err() {
-        return Err(LogError::from(r.err().unwrap()));
-    } else {
-        std::thread::spawn(move || {
-            let mut recever_vec = vec![];
-            let mut sender_vec: Vec<Sender<Arc<Vec<FastLogRecord>>>> = vec![];
-            for a in appenders {
-                let (s, r) = chan(chan_len);
-                sender_vec.push(s);
-                recever_vec.push((r, a));
-            }
-            for (recever, appender) in recever_vec {
-                spawn(move || {
-                    let mut exit = false;
-                    loop {
-                        //batch fetch
-                        if let Ok(msg) = recever.recv() {
-                            appender.do_logs(msg.as_ref());
-                            for x in msg.iter() {
-                                match x.command {
-                                    Command::CommandRecord => {}
-                                    Command::CommandExit => {
-                                        exit = true;
-                                        continue;
-                                    }
-                                    Command::CommandFlush(_) => {
-                                        appender.flush();
-                                        continue;
+    let flush_level = config.flush_level;
+    let flush_fsync = config.flush_fsync;
+    let flush_interval = config.flush_interval;
+    let heartbeat = config.heartbeat;
+    let line_end = config.line_end;
+    let batch = config.batch;
+    let fsync = config.fsync;
+    *BATCH.write().unwrap_or_else(|e| e.into_inner()) = Some(batch);
+    //the logger is installed at the start
+    log::set_max_level(levels::max_level(level));
+    if let Some(abort) = config.panic_hook {
+        crate::panic::install(abort);
+    }
+    if let Some(appender) = config.audit.take() {
+        appender.open();
+        *AUDIT.lock().unwrap_or_else(|e| e.into_inner()) = Some(Audit {
+            appender,
+            format: format.clone(),
+            line_end,
+        });
+    }
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    let worker = Direct::new(
+        appenders,
+        format,
+        interceptors,
+        line_end,
+        flush_level,
+        flush_fsync,
+    );
+    //the appender threads are published to log_sync before init returns
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    let (ready, wait_ready) = chan::<()>(Some(1));
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    let worker = std::thread::spawn(move || {
+        set_logger_thread();
+        //the appenders of the config are given back after exit,see teardown()
+        let closed = Arc::new(Mutex::new(vec![]));
+        //start the thread of the appender,`give_back` is the slot of the reply of remove_appender
+        //for the appenders added by add_appender
+        let start = |index: usize,
+                     appender: Box<dyn LogAppender>,
+                     give_back: Option<Arc<Mutex<Option<Reply>>>>| {
+            let (sender, recever) = chan::<Arc<Vec<FastLogRecord>>>(chan_len);
+            let closed = closed.clone();
+            let handle = spawn(move || {
+                set_logger_thread();
+                appender.open();
+                let mut exit = false;
+                let idle = appender.idle_timeout();
+                let mut last_recv = Instant::now();
+                let mut fsync = FsyncState::new(fsync);
+                //the time of the last write not flushed yet
+                let mut unflushed: Option<Instant> = None;
+                loop {
+                    //batch fetch
+                    let timeout = [
+                        idle.map(|v| v.saturating_sub(last_recv.elapsed())),
+                        fsync.wait(),
+                        flush_interval
+                            .zip(unflushed)
+                            .map(|(v, since)| v.saturating_sub(since.elapsed())),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .min();
+                    let msg = match timeout {
+                        None => recever.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                        Some(timeout) => recever.recv_timeout(timeout),
+                    };
+                    if let Err(RecvTimeoutError::Timeout) = msg {
+                        fsync.on_timeout(appender.as_ref());
+                        if let (Some(v), Some(since)) = (flush_interval, unflushed) {
+                            if since.elapsed() >= v {
+                                timed_flush(appender.as_ref());
+                                unflushed = None;
+                            }
+                        }
+                        if idle.is_some_and(|v| last_recv.elapsed() >= v) {
+                            appender.on_idle();
+                            last_recv = Instant::now();
+                        }
+                    }
+                    if let Ok(msg) = msg {
+                        last_recv = Instant::now();
+                        appender.do_logs(msg.as_ref());
+                        if last_recv.elapsed() >= SLOW_APPENDER {
+                            diagnostic::report(
+                                Level::Warn,
+                                &format!(
+                                    "[fast_log] slow appender {} took {:?} to write {} records",
+                                    appender.describe(),
+                                    last_recv.elapsed(),
+                                    msg.len()
+                                ),
+                            );
+                        }
+                        //the journaled records leave the journal after they are flushed
+                        let journal_end = msg.iter().filter_map(|x| x.journal_end).max();
+                        let mut need_flush = journal_end.is_some();
+                        let mut records = 0;
+                        for x in msg.iter() {
+                            match x.command {
+                                Command::CommandRecord => {
+                                    records += 1;
+                                    if x.level <= flush_level {
+                                        need_flush = true;
                                     }
                                 }
+                                Command::CommandExit => {
+                                    exit = true;
+                                    continue;
+                                }
+                                Command::CommandFlush(_) => {
+                                    timed_flush(appender.as_ref());
+                                    unflushed = None;
+                                    continue;
+                                }
+                            }
+                        }
+                        if need_flush {
+                            timed_flush(appender.as_ref());
+                            if flush_fsync {
+                                appender.sync();
                             }
+                            unflushed = None;
+                        } else if records > 0 {
+                            unflushed = Some(Instant::now());
                         }
-                        if exit {
-                            break;
+                        #[cfg(feature = "mmap")]
+                        if let Some(end) = journal_end {
+                            crate::journal::commit(index, end);
+                        }
+                        fsync.written(records, appender.as_ref());
+                        //the last appender counts the written records and gives back the strings
+                        if let Some(records) = Arc::into_inner(msg) {
+                            count_written(&records);
+                            for x in records {
+                                pool::recycle(x.target);
+                                pool::recycle(x.args);
+                                pool::recycle(x.module_path);
+                                pool::recycle(x.file);
+                                pool::recycle(x.thread_name);
+                                pool::recycle(x.formated);
+                            }
                         }
                     }
-                });
-            }
-            loop {
-                //recv
+                    if exit {
+                        break;
+                    }
+                }
+                fsync.on_exit(appender.as_ref());
+                appender.close();
+                match give_back {
+                    //dropped if the logger is torn down before remove_appender
+                    Some(slot) => {
+                        if let Some(reply) = slot.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                            reply.send(Some(appender));
+                        }
+                    }
+                    None => closed
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .push((index, appender)),
+                }
+            });
+            (sender, handle)
+        };
+        let mut handles = vec![];
+        let mut sender_vec = vec![];
+        for (index, appender) in appenders.into_iter().enumerate() {
+            let (sender, handle) = start(index, appender, None);
+            handles.push(handle);
+            sender_vec.push((None, sender));
+        }
+        let sync_writer = |sender_vec: &[(_, Sender<Arc<Vec<FastLogRecord>>>)]| SyncWriter {
+            senders: sender_vec.iter().map(|(_, v)| v.clone()).collect(),
+            format: format.clone(),
+            line_end,
+        };
+        *SYNC.write().unwrap_or_else(|e| e.into_inner()) = Some(sync_writer(&sender_vec));
+        ready.send(());
+        let mut heartbeat = heartbeat.map(|(interval, level)| Heartbeat::new(interval, level));
+        //the filter may hold back summaries after the records,taken once the logger is idle
+        let mut filter_pending = false;
+        loop {
+            let timeout = heartbeat.as_ref().map(|v| v.timeout());
+            let timeout = match filter_pending {
+                true => Some(timeout.map_or(FILTER_IDLE, |v| v.min(FILTER_IDLE))),
+                false => timeout,
+            };
+            //recv
+            let mut remain = if shard::enabled() {
+                //the queues of the producers first,then the commands of the channel
+                shard::wait(&LOGGER.chan.recv, timeout);
+                let mut remain = Vec::new();
+                shard::drain(&mut remain, batch.max_records);
+                let drained = remain.len();
+                recv_batch(&mut remain, &LOGGER.chan.recv, &batch);
+                //the records pushed before a flush or exit are written before it,
+                //even beyond batch.max_records
+                let command = remain[drained..]
+                    .iter()
+                    .position(|v| v.command != Command::CommandRecord);
+                if let Some(index) = command {
+                    let mut before = Vec::new();
+                    shard::drain(&mut before, usize::MAX);
+                    remain.splice(drained + index..drained + index, before);
+                }
+                (!remain.is_empty()).then_some(remain)
+            } else {
                 let data = {
-                    if LOGGER.chan.recv.len() == 0 {
-                        LOGGER.chan.recv.recv()
-                    } else {
+                    if !LOGGER.chan.recv.is_empty() {
                         LOGGER.chan.recv.try_recv().map_err(|e| RecvError {})
+                    } else if let Some(timeout) = timeout {
+                        LOGGER
+                            .chan
+                            .recv
+                            .recv_timeout(timeout)
+                            .map_err(|e| RecvError {})
+                    } else {
+                        LOGGER.chan.recv.recv()
                     }
                 };
-                if let Ok(data) = data {
-                    let mut remain;
-                    if LOGGER.chan.recv.len() > 0 {
-                        remain = Vec::with_capacity(LOGGER.chan.recv.len() + 1);
-                        remain.push(data);
-                        recv_all(&mut remain, &LOGGER.chan.recv);
-                    } else {
-                        remain = vec![data];
+                data.ok().map(|data| {
+                    let mut remain = Vec::with_capacity(LOGGER.chan.recv.len() + 1);
+                    remain.push(data);
+                    recv_batch(&mut remain, &LOGGER.chan.recv, &batch);
+                    remain
+                })
+            };
+            if let Some(record) = heartbeat.as_mut().and_then(|v| v.poll()) {
+                remain.get_or_insert_with(Vec::new).push(record);
+            }
+            match remain.as_mut() {
+                Some(remain) => {
+                    flush_filter(remain);
+                    filter_pending = true;
+                }
+                None if filter_pending => {
+                    filter_pending = false;
+                    let held = filter_records();
+                    Counters::add(&COUNTERS.records_received, held.len() as u64);
+                    if !held.is_empty() {
+                        remain = Some(held);
                     }
-                    let mut exit = false;
-                    for x in &mut remain {
-                        if x.formated.is_empty() {
-                            format.do_format(x);
-                        }
-                        if x.command.eq(&Command::CommandExit) {
-                            exit = true;
+                }
+                None => {}
+            }
+            if let Some(remain) = remain {
+                if CONTROL_PENDING.swap(false, Ordering::SeqCst) {
+                    let controls =
+                        std::mem::take(&mut *CONTROL.lock().unwrap_or_else(|e| e.into_inner()));
+                    for control in controls {
+                        match control {
+                            //not journaled,the index is out of the journal
+                            Control::Add(id, appender) => {
+                                let slot = Arc::new(Mutex::new(None));
+                                let (sender, handle) =
+                                    start(usize::MAX, appender, Some(slot.clone()));
+                                handles.push(handle);
+                                sender_vec.push((Some((id, slot)), sender));
+                            }
+                            Control::Remove(id, reply) => {
+                                let index = sender_vec
+                                    .iter()
+                                    .position(|(v, _)| v.as_ref().is_some_and(|v| v.0 == id));
+                                match index {
+                                    Some(index) => {
+                                        let (added, sender) = sender_vec.remove(index);
+                                        if let Some((_, slot)) = added {
+                                            *slot.lock().unwrap_or_else(|e| e.into_inner()) =
+                                                Some(reply);
+                                        }
+                                        sender.send(Arc::new(vec![exit_record()]));
+                                    }
+                                    None => {
+                                        reply.send(None);
+                                    }
+                                }
+                            }
                         }
                     }
-                    let data = Arc::new(remain);
-                    for x in &sender_vec {
+                    *SYNC.write().unwrap_or_else(|e| e.into_inner()) =
+                        Some(sync_writer(&sender_vec));
+                }
+                let mut remain = intercept(&mut interceptors, remain);
+                let mut exit = false;
+                for x in &mut remain {
+                    if x.formated.is_empty() {
+                        format_record(format.as_ref().as_ref(), x);
+                    }
+                    scrub::scrub_record(x);
+                    line_end.apply(&mut x.formated);
+                    if x.command.eq(&Command::CommandExit) {
+                        exit = true;
+                    }
+                }
+                //the records of log_sync go before the exit command
+                if exit {
+                    SYNC.write().unwrap_or_else(|e| e.into_inner()).take();
+                }
+                //the appenders hold the only references,the last one to write counts the batch
+                let data = Arc::new(remain);
+                if let Some(((_, last), senders)) = sender_vec.split_last() {
+                    for (_, x) in senders {
                         x.send(data.clone());
                     }
-                    if exit {
-                        break;
+                    last.send(data);
+                }
+                if exit {
+                    break;
+                }
+            }
+        }
+        for handle in handles {
+            let _ = handle.join();
+        }
+        let mut closed = std::mem::take(&mut *closed.lock().unwrap_or_else(|e| e.into_inner()));
+        closed.sort_by_key(|(index, _)| *index);
+        (
+            closed.into_iter().map(|(_, v)| v).collect(),
+            format,
+            interceptors,
+        )
+    });
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    let _ = wait_ready.recv();
+    *running = Some(Running { worker, config });
+    Ok(Started {
+        filter,
+        #[cfg(feature = "mmap")]
+        journal,
+    })
+}
+
+/// stop the worker and the appenders after writing the pending records,
+/// and give back the config of the stopped logger
+/// `hold` keeps the channel open and the filter installed after the worker exits,
+/// so the records logged until the next [`start`] are filtered,queued and written by the next logger,
+/// the filter of the config is given back by the next start. else they are dropped
+fn teardown(running: &mut Option<Running>, hold: bool) -> Option<Config> {
+    let running = running.take()?;
+    if !running.worker.is_finished() {
+        LOGGER.chan.send.send(exit_record());
+    }
+    let joined = running.worker.join();
+    //the appenders not added yet are dropped,the pending remove_appender fail
+    CONTROL_PENDING.store(false, Ordering::SeqCst);
+    CONTROL.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    #[cfg(feature = "mmap")]
+    crate::journal::close();
+    let filter = if hold {
+        None
+    } else {
+        LOGGER.chan.take_filter()
+    };
+    LOGGER
+        .format
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+    let audit = AUDIT.lock().unwrap_or_else(|e| e.into_inner()).take();
+    let (appends, format, interceptors) = joined.ok()?;
+    let mut config = running.config;
+    config.audit = audit.map(|audit| {
+        audit.appender.close();
+        audit.appender
+    });
+    config.appends = appends;
+    config.interceptors = interceptors;
+    config.scrubbers = scrub::take_scrubbers();
+    config.filter = filter.unwrap_or_else(|| Box::new(NoFilter {}));
+    config.format = Arc::try_unwrap(format).unwrap_or_else(|_| Box::new(FastLogFormat::new()));
+    Some(config)
+}
+
+/// tears down the scoped logger and restores the previous one on drop,see [`init_scoped`]
+pub struct LoggerGuard {
+    prev: Option<Config>,
+}
+
+impl Drop for LoggerGuard {
+    fn drop(&mut self) {
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        teardown(&mut running, self.prev.is_some());
+        if let Some(prev) = self.prev.take() {
+            match start(&mut running, prev) {
+                Ok(started) => {
+                    drop(running);
+                    started.replay();
+                }
+                Err(e) => {
+                    LOGGER.chan.take_filter();
+                    diagnostic::error(e);
+                }
+            }
+        }
+    }
+}
+
+/// replace the running logger with `config` until the returned guard is dropped,
+/// then the scoped logger is torn down and the previous logger(if any) is restored.
+/// pending records are written by the logger that received them,
+/// so integration tests and plugin hosts can swap configs within one process
+/// for example:
+/// ```rust
+/// use fast_log::Config;
+/// {
+///     let _guard = fast_log::init_scoped(Config::new().console()).unwrap();
+///     log::info!("written by the scoped logger");
+/// }
+/// ```
+pub fn init_scoped(config: Config) -> Result<LoggerGuard, LogError> {
+    let prev = replace(config)?;
+    Ok(LoggerGuard { prev })
+}
+
+/// reload the config file and replace the running logger,see [`Config::from_file`].
+/// the running logger is kept if the config is invalid
+/// you need enable fast_log = { ... ,features=["config_file"]}
+#[cfg(feature = "config_file")]
+pub fn reload(path: &str) -> Result<(), LogError> {
+    replace(Config::from_file(path)?)?;
+    Ok(())
+}
+
+/// replace the running logger with `config`,return the config of the replaced logger.
+/// the replaced logger is restored if `config` fails to init.
+/// the logger is locked during the swap,so the swaps of `init_scoped` and `reload` don't interleave,
+/// and the replaced filter stays installed until the filter of `config` replaces it
+fn replace(config: Config) -> Result<Option<Config>, LogError> {
+    config.validate()?;
+    let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    let mut prev = teardown(&mut running, true);
+    match start(&mut running, config) {
+        Ok(mut started) => {
+            drop(running);
+            if let (Some(prev), Some(filter)) = (prev.as_mut(), started.filter.take()) {
+                prev.filter = filter;
+            }
+            started.replay();
+            Ok(prev)
+        }
+        Err(e) => {
+            match prev {
+                Some(mut prev) => {
+                    if let Some(filter) = LOGGER.chan.take_filter() {
+                        prev.filter = filter;
+                    }
+                    match start(&mut running, prev) {
+                        Ok(started) => {
+                            drop(running);
+                            started.replay();
+                        }
+                        Err(e) => diagnostic::error(e),
                     }
                 }
+                None => {
+                    LOGGER.chan.take_filter();
+                }
             }
-        });
-        return Ok(LOGGER.deref());
+            Err(e)
+        }
     }
 }
 
-fn recv_all<T>(data: &mut Vec<T>, recver: &Receiver<T>) {
-    loop {
-        match recver.try_recv() {
-            Ok(v) => {
-                data.push(v);
+/// the heartbeat record of the worker,see [`Config::heartbeat`]
+struct Heartbeat {
+    interval: Duration,
+    level: Level,
+    last: Instant,
+    records_written: u64,
+    bytes_written: u64,
+}
+
+impl Heartbeat {
+    fn new(interval: Duration, level: Level) -> Self {
+        let m = metrics::metrics();
+        Self {
+            interval,
+            level,
+            last: Instant::now(),
+            records_written: m.records_written,
+            bytes_written: m.bytes_written,
+        }
+    }
+
+    /// the time until the next heartbeat
+    fn timeout(&self) -> Duration {
+        self.interval.saturating_sub(self.last.elapsed())
+    }
+
+    /// the heartbeat record if it is due
+    fn poll(&mut self) -> Option<FastLogRecord> {
+        let elapsed = self.last.elapsed();
+        if elapsed < self.interval {
+            return None;
+        }
+        let m = metrics::metrics();
+        let secs = elapsed.as_secs_f64();
+        let mut record = self_record(self.level, "heartbeat".to_string());
+        record.kv = vec![
+            ("queue_depth".to_string(), KvValue::U64(m.chan_len as u64)),
+            (
+                "records_per_sec".to_string(),
+                KvValue::F64(round1(
+                    (m.records_written - self.records_written) as f64 / secs,
+                )),
+            ),
+            (
+                "bytes_per_sec".to_string(),
+                KvValue::F64(round1((m.bytes_written - self.bytes_written) as f64 / secs)),
+            ),
+        ];
+        self.last = Instant::now();
+        self.records_written = m.records_written;
+        self.bytes_written = m.bytes_written;
+        Some(record)
+    }
+}
+
+/// the rate with one decimal
+fn round1(v: f64) -> f64 {
+    (v * 10.0).round() / 10.0
+}
+
+/// applies the FsyncPolicy on the appender thread
+struct FsyncState {
+    policy: FsyncPolicy,
+    /// records written since the last fsync
+    unsynced: usize,
+    last: Instant,
+}
+
+impl FsyncState {
+    fn new(policy: FsyncPolicy) -> Self {
+        Self {
+            policy,
+            unsynced: 0,
+            last: Instant::now(),
+        }
+    }
+
+    /// the max wait of the next records before the interval fsync
+    fn wait(&self) -> Option<Duration> {
+        match self.policy {
+            FsyncPolicy::Interval(interval) if self.unsynced > 0 => {
+                Some(interval.saturating_sub(self.last.elapsed()))
             }
-            Err(_) => {
-                break;
+            _ => None,
+        }
+    }
+
+    fn sync(&mut self, appender: &dyn LogAppender) {
+        timed_flush(appender);
+        appender.sync();
+        self.unsynced = 0;
+        self.last = Instant::now();
+    }
+
+    fn written(&mut self, records: usize, appender: &dyn LogAppender) {
+        if records == 0 {
+            return;
+        }
+        self.unsynced += records;
+        match self.policy {
+            FsyncPolicy::Never => {}
+            FsyncPolicy::Always => self.sync(appender),
+            FsyncPolicy::EveryN(n) => {
+                if self.unsynced >= n {
+                    self.sync(appender);
+                }
+            }
+            FsyncPolicy::Interval(interval) => {
+                if self.last.elapsed() >= interval {
+                    self.sync(appender);
+                }
+            }
+        }
+    }
+
+    fn on_timeout(&mut self, appender: &dyn LogAppender) {
+        if let FsyncPolicy::Interval(interval) = self.policy {
+            if self.unsynced > 0 && self.last.elapsed() >= interval {
+                self.sync(appender);
             }
         }
     }
+
+    fn on_exit(&mut self, appender: &dyn LogAppender) {
+        if self.policy != FsyncPolicy::Never && self.unsynced > 0 {
+            self.sync(appender);
+        }
+    }
+}
+
+fn timed_flush(appender: &dyn LogAppender) {
+    let start = Instant::now();
+    appender.flush();
+    COUNTERS
+        .last_flush_nanos
+        .store(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+}
+
+/// hold a record made by a filter,sent before the record being filtered once the filter lock is released.
+/// the filters run under the lock of the logger filter,they must not send to the channel
+pub(crate) fn hold(record: FastLogRecord) {
+    HELD.lock().unwrap_or_else(|e| e.into_inner()).push(record);
+    HELD_PENDING.store(true, Ordering::SeqCst);
+}
+
+/// the records held by the filters,sent before the record they were filtering
+fn take_held() -> Vec<FastLogRecord> {
+    match HELD_PENDING.swap(false, Ordering::SeqCst) {
+        true => std::mem::take(&mut *HELD.lock().unwrap_or_else(|e| e.into_inner())),
+        false => vec![],
+    }
+}
+
+/// count the records and the bytes the appenders wrote
+fn count_written(records: &[FastLogRecord]) {
+    let mut written = 0;
+    let mut bytes = 0;
+    for x in records {
+        if x.command == Command::CommandRecord {
+            written += 1;
+            bytes += x.formated.len() as u64;
+        }
+    }
+    Counters::add(&COUNTERS.records_written, written);
+    Counters::add(&COUNTERS.bytes_written, bytes);
+}
+
+/// the idle time of the worker before the filter gives the records it holds back
+const FILTER_IDLE: Duration = Duration::from_secs(1);
+
+/// the records held back by the filter of the logger,see [`Filter::flush`]
+fn filter_records() -> Vec<FastLogRecord> {
+    LOGGER
+        .chan
+        .filter
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|f| f.flush())
+        .unwrap_or_default()
+}
+
+/// put the records held back by the filter before the first flush or exit command
+fn flush_filter(records: &mut Vec<FastLogRecord>) {
+    if let Some(index) = records
+        .iter()
+        .position(|v| v.command != Command::CommandRecord)
+    {
+        let held = filter_records();
+        Counters::add(&COUNTERS.records_received, held.len() as u64);
+        records.splice(index..index, held);
+    }
+}
+
+/// batch fetch until the batch is full,waiting for at most `batch.linger`
+fn recv_batch(data: &mut Vec<FastLogRecord>, recver: &Receiver<FastLogRecord>, batch: &Batch) {
+    let deadline = Instant::now() + batch.linger;
+    let mut bytes: usize = data.iter().map(|v| v.args.len() + v.formated.len()).sum();
+    let mut linger = !batch.linger.is_zero();
+    while data.len() < batch.max_records && bytes < batch.max_bytes {
+        if let Some(last) = data.last() {
+            if last.command != Command::CommandRecord {
+                //flush or exit,do not wait
+                linger = false;
+            }
+        }
+        let v = match recver.try_recv() {
+            Ok(v) => v,
+            Err(_) => {
+                let now = Instant::now();
+                if !linger || now >= deadline {
+                    break;
+                }
+                match recver.recv_timeout(deadline - now) {
+                    Ok(v) => v,
+                    Err(_) => break,
+                }
+            }
+        };
+        bytes += v.args.len() + v.formated.len();
+        data.push(v);
+    }
+}
+
+/// a record of the logger about itself,the target is "fast_log"
+pub(crate) fn self_record(level: Level, args: String) -> FastLogRecord {
+    FastLogRecord {
+        hostname: LOGGER.record_hostname(),
+        pid: LOGGER.record_pid(),
+        seq: LOGGER.record_seq(),
+        ..FastLogRecord::new(level, "fast_log", args)
+    }
+}
+
+/// report the dropped records at most once per second
+fn report_dropped() {
+    let sec = START
+        .get()
+        .map(|v| v.elapsed().as_secs())
+        .unwrap_or_default();
+    if DROP_REPORTED.swap(sec, Ordering::Relaxed) != sec {
+        diagnostic::report(
+            Level::Warn,
+            &format!(
+                "[fast_log] the channel is full,{} records dropped in total",
+                COUNTERS.records_dropped.load(Ordering::Relaxed)
+            ),
+        );
+    }
+}
+
+/// send a record of the logger about itself if the channel has room,never blocks
+pub(crate) fn try_send_self_record(level: Level, args: String) {
+    if !INSTALLED.load(Ordering::SeqCst) {
+        return;
+    }
+    match LOGGER.chan.send.try_send(self_record(level, args)) {
+        Ok(_) => Counters::add(&COUNTERS.records_received, 1),
+        Err(_) => Counters::add(&COUNTERS.records_dropped, 1),
+    }
 }
 
 pub fn exit() -> Result<(), LogError> {
-    let fast_log_record = FastLogRecord {
-        command: Command::CommandExit,
-        level: log::Level::Info,
-        target: String::new(),
-        args: String::new(),
-        module_path: String::new(),
-        file: String::new(),
-        line: None,
-        now: SystemTime::now(),
-        formated: String::new(),
-    };
-    let result = LOGGER.chan.send.send(fast_log_record);
+    exit_with("exit")
+}
+
+/// exit the logger,the last record describes the shutdown `reason`,uptime and totals,
+/// so log analysis can distinguish clean shutdowns from crashes by the presence of this record.
+/// it waits for the pending records to be written and stops the logger,`init` can start it again
+pub fn exit_with(reason: &str) -> Result<(), LogError> {
+    //the summaries held back by the filter go before the shutdown record
+    for record in filter_records() {
+        LOGGER.send(record);
+    }
+    let m = metrics::metrics();
+    let uptime = START.get().map(|v| v.elapsed()).unwrap_or_default();
+    let shutdown = self_record(
+        log::Level::Info,
+        format!(
+            "shutdown reason={} uptime={:?} received={} written={} dropped={}",
+            reason, uptime, m.records_received, m.records_written, m.records_dropped
+        ),
+    );
+    LOGGER.send(shutdown);
+    //the threads of the logger can't wait for themselves,their worker exits after the command
+    if !is_logger_thread() {
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        if running.is_some() {
+            teardown(&mut running, false);
+            return Ok(());
+        }
+    }
+    let result = LOGGER.chan.send.send(exit_record());
+    drain();
     match result {
         Ok(()) => {
             return Ok(());
         }
         _ => {}
     }
-    return Err(LogError::E("[fast_log] exit fail!".to_string()));
+    return Err(LogError::ChannelClosed);
 }
 
 pub fn flush() -> Result<WaitGroup, LogError> {
     let wg = WaitGroup::new();
-    let fast_log_record = FastLogRecord {
-        command: Command::CommandFlush(wg.clone()),
-        level: log::Level::Info,
-        target: String::new(),
-        args: String::new(),
-        module_path: String::new(),
-        file: String::new(),
-        line: None,
-        now: SystemTime::now(),
-        formated: String::new(),
-    };
-    let result = LOGGER.chan.send.send(fast_log_record);
+    let result = LOGGER.chan.send.send(flush_record(wg.clone()));
+    drain();
     match result {
         Ok(()) => {
             return Ok(wg);
         }
         _ => {}
     }
-    return Err(LogError::E("[fast_log] flush fail!".to_string()));
+    return Err(LogError::ChannelClosed);
+}
+
+/// flush without blocking on the full channel and wait at most `timeout`,for the panic hook.
+/// false if not flushed in time,and on the threads of the logger which would wait for themselves
+pub(crate) fn flush_timeout(timeout: Duration) -> bool {
+    if is_logger_thread() {
+        return false;
+    }
+    let wg = WaitGroup::new();
+    if LOGGER.chan.send.try_send(flush_record(wg.clone())).is_err() {
+        return false;
+    }
+    drain();
+    //the records are written by drain() without the worker thread
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    return true;
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    {
+        let (send, recv) = chan::<()>(Some(1));
+        std::thread::spawn(move || {
+            wg.wait();
+            send.send(());
+        });
+        recv.recv_timeout(timeout).is_ok()
+    }
+}
+
+fn flush_record(wg: WaitGroup) -> FastLogRecord {
+    FastLogRecord {
+        command: Command::CommandFlush(wg),
+        ..FastLogRecord::new(log::Level::Info, "", String::new())
+    }
+}
+
+/// the command stopping the worker or an appender thread
+fn exit_record() -> FastLogRecord {
+    FastLogRecord {
+        command: Command::CommandExit,
+        ..FastLogRecord::new(log::Level::Info, "", String::new())
+    }
+}
+
+/// attach the appender to the running logger,for example a network or ring buffer appender of a live-debug session.
+/// it writes the records logged after the call until [`remove_appender`].
+/// the added appenders are dropped by the teardown of the logger(`init_scoped`,`reload`) and not journaled
+/// for example:
+/// ```rust
+/// use fast_log::plugin::ring_buffer::RingBufferAppender;
+/// use fast_log::Config;
+/// fast_log::init(Config::new().console()).unwrap();
+/// let ring = RingBufferAppender::new(100);
+/// let recent = ring.recent();
+/// let id = fast_log::add_appender(Box::new(ring)).unwrap();
+/// log::info!("written by the console and the ring buffer");
+/// let ring = fast_log::remove_appender(id).unwrap();
+/// let records = fast_log::dump_recent(&recent);
+/// ```
+pub fn add_appender(appender: Box<dyn LogAppender>) -> Result<AppenderId, LogError> {
+    let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    let running = running
+        .as_mut()
+        .ok_or_else(|| LogError::Init("the logger is not initialized".to_string()))?;
+    let id = AppenderId(NEXT_APPENDER_ID.fetch_add(1, Ordering::SeqCst));
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    running.worker.add(id, appender);
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    {
+        CONTROL
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Control::Add(id, appender));
+        CONTROL_PENDING.store(true, Ordering::SeqCst);
+        //wake the worker
+        flush()?;
+    }
+    Ok(id)
+}
+
+/// detach the appender added by [`add_appender`] after it wrote the records logged before the call,
+/// and give it back closed
+pub fn remove_appender(id: AppenderId) -> Result<Box<dyn LogAppender>, LogError> {
+    flush()?.wait();
+    let unknown = || LogError::E(format!("[fast_log] unknown appender {:?}", id));
+    let mut guard = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    let running = guard
+        .as_mut()
+        .ok_or_else(|| LogError::Init("the logger is not initialized".to_string()))?;
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    return running.worker.remove(id).ok_or_else(unknown);
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    {
+        let (reply, recv) = chan(Some(1));
+        CONTROL
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Control::Remove(id, reply));
+        CONTROL_PENDING.store(true, Ordering::SeqCst);
+        flush()?;
+        //the teardown may wait for the lock
+        drop(guard);
+        recv.recv()
+            .map_err(|_| LogError::ChannelClosed)?
+            .ok_or_else(unknown)
+    }
+}
+
+/// write an audit record(compliance events) to the audit appender,see [`Config::audit`].
+/// returns after the record is written and fsynced,so it is not lost even on power failure.
+/// the record skips the level,the filter and the async channel
+/// for example:
+/// ```rust
+/// use fast_log::Config;
+/// use fast_log::plugin::file::FileAppender;
+/// fast_log::init(Config::new().console().audit(FileAppender::new("target/logs/audit.log"))).unwrap();
+/// fast_log::audit("payment", "user 1 paid 10$").unwrap();
+/// fast_log::audit!("user {} paid {}$", 1, 10).unwrap();
+/// ```
+pub fn audit(target: &str, log: &str) -> Result<(), LogError> {
+    let audit = AUDIT.lock().unwrap_or_else(|e| e.into_inner());
+    let audit = audit
+        .as_ref()
+        .ok_or_else(|| LogError::Init("the audit appender is not configured".to_string()))?;
+    let (thread_name, thread_id) = LOGGER.current_thread();
+    let (trace_id, span_id) = current_trace();
+    let mut record = FastLogRecord {
+        thread_name,
+        thread_id,
+        hostname: LOGGER.record_hostname(),
+        pid: LOGGER.record_pid(),
+        kv: with_tracing_span(vec![]),
+        trace_id,
+        span_id,
+        ..FastLogRecord::new(Level::Info, target, log.to_string())
+    };
+    audit.format.do_format(&mut record);
+    scrub::scrub_record(&mut record);
+    audit.line_end.apply(&mut record.formated);
+    audit.appender.try_do_logs(std::slice::from_ref(&record))?;
+    audit.appender.flush();
+    audit.appender.try_sync()
 }
 
 pub fn print(log: String) -> Result<(), SendError<FastLogRecord>> {
     LOGGER.print(log)
 }
+
+/// print no other info without ever blocking the caller,for latency-critical threads(audio,trading)
+/// which would rather skip the message than wait for the worker on the full channel.
+/// the message is given back if the channel is full or closed
+/// ```rust
+/// fast_log::init(fast_log::Config::new().console().chan_len(Some(100000))).unwrap();
+/// if let Err(e) = fast_log::try_print("tick 42\n".to_string()) {
+///     //skipped,the message is in e.into_inner()
+/// }
+/// ```
+pub fn try_print(log: String) -> Result<(), TryPrintError> {
+    LOGGER.try_print(log)
+}
+
+pub fn print_with(meta: PrintMeta, log: String) -> Result<(), LogError> {
+    LOGGER.print_with(meta, log)
+}
+
+/// log a critical record synchronously,see [`log_sync!`](crate::log_sync!).
+/// the record is formatted by the caller and written by all appenders ahead of the queued records,
+/// without the worker and the interceptors,returns after the appenders wrote and flushed it.
+/// guarantees durability for fatal messages even if the async queue would be lost on crash.
+/// before init the record is kept by [`pre_init`],on the worker and the appender threads it is sent without waiting
+pub fn log_sync(level: Level, target: &str, log: &str) -> Result<(), LogError> {
+    if level > log::max_level() {
+        return Ok(());
+    }
+    LOGGER.write_sync(
+        &Record::builder()
+            .level(level)
+            .target(target)
+            .module_path(Some(target))
+            .args(format_args!("{}", log))
+            .build(),
+    )
+}
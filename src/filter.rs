@@ -1,7 +1,60 @@
+use crate::appender::FastLogRecord;
+use crate::embedded;
+use crate::error::LogError;
+use crate::fast_log::hold;
+use crate::runtime::Instant;
+use log::LevelFilter;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
 ///log filter
+///
+/// the record has the level,args,target,module_path,file,line and the structured key-values,
+/// also for the messages of `print_with` and the records logged before `init`.
+/// a closure taking the record is a filter,for example drop the health-check access logs:
+/// ```rust
+/// use fast_log::filter::Filter;
+/// use fast_log::Config;
+/// use log::kv::Key;
+/// let health_check = |r: &log::Record| {
+///     r.target().starts_with("http::probe")
+///         && r.key_values().get(Key::from("path")).is_some_and(|v| v.to_string() == "/health")
+/// };
+/// let kv = [("path", "/health")];
+/// assert!(health_check.filter(&log::Record::builder().target("http::probe").key_values(&kv).build()));
+/// let config = Config::new().console().filter(health_check);
+/// ```
 pub trait Filter: Send + Sync {
     //return is filter
     fn filter(&self, record: &log::Record) -> bool;
+
+    /// the records the filter holds back,for example the summary of the suppressed records,
+    /// taken before a flush or exit and when the logger is idle
+    fn flush(&self) -> Vec<FastLogRecord> {
+        vec![]
+    }
+}
+
+impl Filter for Box<dyn Filter> {
+    fn filter(&self, record: &log::Record) -> bool {
+        self.as_ref().filter(record)
+    }
+
+    fn flush(&self) -> Vec<FastLogRecord> {
+        self.as_ref().flush()
+    }
+}
+
+impl<F> Filter for F
+where
+    F: Fn(&log::Record) -> bool + Send + Sync,
+{
+    fn filter(&self, record: &log::Record) -> bool {
+        self(record)
+    }
 }
 
 pub struct NoFilter {}
@@ -64,3 +117,485 @@ impl Filter for ModuleFilter {
         return false;
     }
 }
+
+/// limit records to `max` per `interval` for each call site(file:line),
+/// once the interval of a site is over,a "suppressed X similar messages" record is emitted for the dropped records,
+/// the sites are checked by the next record of any site,on flush,exit and when the logger is idle
+pub struct RateLimitFilter {
+    pub max: u64,
+    pub interval: Duration,
+    sites: Mutex<RateLimitSites>,
+}
+
+struct RateLimitSites {
+    windows: HashMap<(String, u32), RateLimitWindow>,
+    /// the last check of the windows of all the sites
+    swept: Instant,
+}
+
+struct RateLimitWindow {
+    start: Instant,
+    count: u64,
+    suppressed: u64,
+    /// the first suppressed record of the window,for the summary
+    first: Option<FastLogRecord>,
+}
+
+impl RateLimitWindow {
+    /// the summary of the suppressed records,None if no record was suppressed
+    fn summary(&mut self) -> Option<FastLogRecord> {
+        let mut summary = self.first.take()?;
+        summary.args = format!("suppressed {} similar messages", self.suppressed);
+        summary.now = crate::now();
+        self.suppressed = 0;
+        Some(summary)
+    }
+}
+
+impl RateLimitFilter {
+    pub fn new(max: u64, interval: Duration) -> Self {
+        Self {
+            max,
+            interval,
+            sites: Mutex::new(RateLimitSites {
+                windows: HashMap::new(),
+                swept: Instant::now(),
+            }),
+        }
+    }
+}
+
+impl Filter for RateLimitFilter {
+    fn filter(&self, record: &log::Record) -> bool {
+        let key = (
+            record.file().unwrap_or(record.target()).to_string(),
+            record.line().unwrap_or_default(),
+        );
+        let mut summaries = vec![];
+        let filtered = {
+            let mut sites = match self.sites.lock() {
+                Ok(v) => v,
+                Err(e) => e.into_inner(),
+            };
+            let now = Instant::now();
+            //the sites logging no more report their suppressed records too
+            if now.duration_since(sites.swept) >= self.interval {
+                sites.swept = now;
+                for window in sites.windows.values_mut() {
+                    if now.duration_since(window.start) >= self.interval {
+                        summaries.extend(window.summary());
+                    }
+                }
+            }
+            let window = sites.windows.entry(key).or_insert(RateLimitWindow {
+                start: now,
+                count: 0,
+                suppressed: 0,
+                first: None,
+            });
+            if now.duration_since(window.start) >= self.interval {
+                summaries.extend(window.summary());
+                window.start = now;
+                window.count = 0;
+            }
+            if window.count >= self.max {
+                if window.first.is_none() {
+                    window.first = Some(to_fast_log_record(record));
+                }
+                window.suppressed += 1;
+                true
+            } else {
+                window.count += 1;
+                false
+            }
+        };
+        //sent by the logger after the filter,this runs under the lock of the logger filter
+        for summary in summaries {
+            hold(summary);
+        }
+        filtered
+    }
+
+    fn flush(&self) -> Vec<FastLogRecord> {
+        let mut sites = match self.sites.lock() {
+            Ok(v) => v,
+            Err(e) => e.into_inner(),
+        };
+        sites
+            .windows
+            .values_mut()
+            .filter_map(|window| window.summary())
+            .collect()
+    }
+}
+
+/// collapse identical consecutive messages within `window` into one record,
+/// the repeats are reported by a "last message repeated N times" record,
+/// when a different message arrives or on flush,exit or when the logger is idle
+pub struct DedupFilter {
+    pub window: Duration,
+    last: Mutex<Option<DedupLast>>,
+}
+
+struct DedupLast {
+    record: FastLogRecord,
+    start: Instant,
+    repeated: u64,
+}
+
+impl DedupFilter {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last: Mutex::new(None),
+        }
+    }
+}
+
+impl Filter for DedupFilter {
+    fn filter(&self, record: &log::Record) -> bool {
+        let current = to_fast_log_record(record);
+        let mut summary = None;
+        {
+            let mut last = match self.last.lock() {
+                Ok(v) => v,
+                Err(e) => e.into_inner(),
+            };
+            let now = Instant::now();
+            if let Some(l) = last.as_mut() {
+                if l.record.level == current.level
+                    && l.record.target == current.target
+                    && l.record.args == current.args
+                    && now.duration_since(l.start) < self.window
+                {
+                    l.repeated += 1;
+                    //filter
+                    return true;
+                }
+                if l.repeated > 0 {
+                    let mut record = l.record.clone();
+                    record.args = format!("last message repeated {} times", l.repeated);
+                    record.now = crate::now();
+                    summary = Some(record);
+                }
+            }
+            *last = Some(DedupLast {
+                record: current,
+                start: now,
+                repeated: 0,
+            });
+        }
+        //sent by the logger before this record,this runs under the lock of the logger filter
+        if let Some(summary) = summary {
+            hold(summary);
+        }
+        //not filter
+        false
+    }
+
+    fn flush(&self) -> Vec<FastLogRecord> {
+        let mut last = match self.last.lock() {
+            Ok(v) => v,
+            Err(e) => e.into_inner(),
+        };
+        match last.as_mut() {
+            Some(l) if l.repeated > 0 => {
+                let mut summary = l.record.clone();
+                summary.args = format!("last message repeated {} times", l.repeated);
+                summary.now = crate::now();
+                l.repeated = 0;
+                vec![summary]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+fn to_fast_log_record(record: &log::Record) -> FastLogRecord {
+    FastLogRecord {
+        module_path: record.module_path().unwrap_or_default().to_string(),
+        file: record.file().unwrap_or_default().to_string(),
+        line: record.line(),
+        kv: crate::fast_log::key_values(record),
+        ..FastLogRecord::new(record.level(), record.target(), record.args().to_string())
+    }
+}
+
+/// keep 1 in `rate` records at `level` and the less severe levels,keep all of the more severe records.
+/// for example:
+/// ```rust
+/// use fast_log::filter::SamplingFilter;
+/// // keep 1 in 100 of debug/trace,keep all info/warn/error
+/// let f = SamplingFilter::new(100);
+/// // keep 1 in 100 of info/debug/trace randomly,keep all warn/error
+/// let f = SamplingFilter::new(100).level(log::LevelFilter::Info).random(true);
+/// ```
+pub struct SamplingFilter {
+    pub rate: u64,
+    pub level: LevelFilter,
+    /// true => probabilistic sampling,false => systematic sampling(every `rate`th record)
+    pub random: bool,
+    counter: AtomicU64,
+}
+
+impl SamplingFilter {
+    pub fn new(rate: u64) -> Self {
+        Self {
+            rate,
+            level: LevelFilter::Debug,
+            random: false,
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// sample records at this level and the less severe levels,default is Debug
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// use probabilistic sampling instead of systematic sampling
+    pub fn random(mut self, random: bool) -> Self {
+        self.random = random;
+        if random {
+            let seed = crate::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos() as u64;
+            self.counter = AtomicU64::new(seed | 1);
+        }
+        self
+    }
+}
+
+impl Filter for SamplingFilter {
+    fn filter(&self, record: &log::Record) -> bool {
+        if self.rate <= 1 || record.level() < self.level {
+            //not filter
+            return false;
+        }
+        let n = if self.random {
+            //xorshift,a race between threads only costs a little randomness
+            let mut x = self.counter.load(Ordering::Relaxed);
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            self.counter.store(x, Ordering::Relaxed);
+            x
+        } else {
+            self.counter.fetch_add(1, Ordering::Relaxed)
+        };
+        n % self.rate != 0
+    }
+}
+
+/// filter by the `RUST_LOG` style directives,for example `info`,`my_crate=debug,hyper=warn`,`warn,my_crate::db=trace/select`.
+/// a directive is `module=level`,`module`(trace) or `level`(the default of all modules),
+/// the longest module prefix of the target wins,records matching no directive are filtered.
+/// the optional `/text` keeps only the messages containing text
+/// ```rust
+/// use fast_log::filter::EnvFilter;
+/// let f: EnvFilter = "info,hyper=warn".parse().unwrap();
+/// assert_eq!(f.max_level(), log::LevelFilter::Info);
+/// ```
+pub struct EnvFilter {
+    /// sorted by the module length
+    pub directives: Vec<(Option<String>, LevelFilter)>,
+    pub message: Option<String>,
+}
+
+impl EnvFilter {
+    /// the most verbose level of the directives,use it as the `Config::level`
+    pub fn max_level(&self) -> LevelFilter {
+        embedded::max_level(&self.directives)
+    }
+}
+
+impl FromStr for EnvFilter {
+    type Err = LogError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let embedded::Directives {
+            directives,
+            message,
+        } = spec
+            .parse()
+            .map_err(|d| LogError::Parse(format!("invalid level in the directive {}", d)))?;
+        Ok(Self {
+            directives,
+            message,
+        })
+    }
+}
+
+impl Filter for EnvFilter {
+    fn filter(&self, record: &log::Record) -> bool {
+        !embedded::enabled(&self.directives, self.message.as_deref(), record)
+    }
+}
+
+/// the records filtered by both filters,
+/// the second filter is skipped if the first keeps the record(mind the stateful filters like `RateLimitFilter`)
+pub struct AndFilter<A: Filter, B: Filter>(pub A, pub B);
+
+impl<A: Filter, B: Filter> Filter for AndFilter<A, B> {
+    fn filter(&self, record: &log::Record) -> bool {
+        self.0.filter(record) && self.1.filter(record)
+    }
+
+    fn flush(&self) -> Vec<FastLogRecord> {
+        let mut records = self.0.flush();
+        records.extend(self.1.flush());
+        records
+    }
+}
+
+/// the records filtered by either filter,
+/// the second filter is skipped if the first filters the record(mind the stateful filters like `RateLimitFilter`)
+pub struct OrFilter<A: Filter, B: Filter>(pub A, pub B);
+
+impl<A: Filter, B: Filter> Filter for OrFilter<A, B> {
+    fn filter(&self, record: &log::Record) -> bool {
+        self.0.filter(record) || self.1.filter(record)
+    }
+
+    fn flush(&self) -> Vec<FastLogRecord> {
+        let mut records = self.0.flush();
+        records.extend(self.1.flush());
+        records
+    }
+}
+
+/// the records kept by the filter
+pub struct NotFilter<F: Filter>(pub F);
+
+impl<F: Filter> Filter for NotFilter<F> {
+    fn filter(&self, record: &log::Record) -> bool {
+        !self.0.filter(record)
+    }
+
+    fn flush(&self) -> Vec<FastLogRecord> {
+        self.0.flush()
+    }
+}
+
+/// compose the conditions of the kept records,each condition narrows the kept records,the others are filtered.
+/// `not` keeps the records filtered before and filters the kept ones.
+/// for example keep only the info and more severe records of sqlx:
+/// ```rust
+/// use fast_log::filter::{Filter, FilterBuilder};
+/// use log::LevelFilter;
+/// let f = FilterBuilder::level(LevelFilter::Info).target_prefix("sqlx");
+/// assert!(!f.filter(&log::Record::builder().level(log::Level::Warn).target("sqlx::query").build()));
+/// assert!(f.filter(&log::Record::builder().level(log::Level::Debug).target("sqlx::query").build()));
+/// assert!(f.filter(&log::Record::builder().level(log::Level::Warn).target("hyper").build()));
+/// // filter the info and more severe records of sqlx,keep the others
+/// let f = FilterBuilder::level(LevelFilter::Info).target_prefix("sqlx").not();
+/// assert!(f.filter(&log::Record::builder().level(log::Level::Warn).target("sqlx::query").build()));
+/// assert!(!f.filter(&log::Record::builder().level(log::Level::Debug).target("sqlx::query").build()));
+/// assert!(!f.filter(&log::Record::builder().level(log::Level::Warn).target("hyper").build()));
+/// let config = fast_log::Config::new().console().filter(f);
+/// ```
+pub struct FilterBuilder {
+    filter: Box<dyn Filter>,
+}
+
+impl FilterBuilder {
+    /// keep the records at `level` and the more severe levels
+    pub fn level(level: LevelFilter) -> Self {
+        Self {
+            filter: Box::new(move |r: &log::Record| r.level() > level),
+        }
+    }
+
+    /// keep the records whose target starts with `prefix`
+    pub fn target_prefix(self, prefix: &str) -> Self {
+        let prefix = prefix.to_string();
+        self.and(move |r: &log::Record| !r.target().starts_with(&prefix))
+    }
+
+    /// keep the records whose module path starts with `prefix`
+    pub fn module_prefix(self, prefix: &str) -> Self {
+        let prefix = prefix.to_string();
+        self.and(move |r: &log::Record| !r.module_path().unwrap_or_default().starts_with(&prefix))
+    }
+
+    /// keep the records whose message contains `text`
+    pub fn message_contains(self, text: &str) -> Self {
+        let text = text.to_string();
+        self.and(move |r: &log::Record| !r.args().to_string().contains(&text))
+    }
+
+    /// keep the records with the key-value `key=value`
+    pub fn kv(self, key: &str, value: &str) -> Self {
+        let (key, value) = (key.to_string(), value.to_string());
+        self.and(move |r: &log::Record| {
+            r.key_values()
+                .get(log::kv::Key::from(key.as_str()))
+                .is_none_or(|v| v.to_string() != value)
+        })
+    }
+
+    /// keep the records kept by `filter` too
+    pub fn and<F: Filter + 'static>(self, filter: F) -> Self {
+        Self {
+            filter: Box::new(OrFilter(self.filter, filter)),
+        }
+    }
+
+    /// keep the records kept by `filter` as well
+    pub fn or<F: Filter + 'static>(self, filter: F) -> Self {
+        Self {
+            filter: Box::new(AndFilter(self.filter, filter)),
+        }
+    }
+
+    /// keep the records filtered before,filter the kept ones
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Self {
+            filter: Box::new(NotFilter(self.filter)),
+        }
+    }
+}
+
+impl Filter for FilterBuilder {
+    fn filter(&self, record: &log::Record) -> bool {
+        self.filter.filter(record)
+    }
+
+    fn flush(&self) -> Vec<FastLogRecord> {
+        self.filter.flush()
+    }
+}
+
+/// filter the records whose message matches the pattern
+/// you need enable fast_log = { ... ,features=["regex"]}
+/// ```rust
+/// use fast_log::filter::{Filter, RegexFilter};
+/// let f = RegexFilter::new(r"GET /health(z)? ").unwrap();
+/// assert!(f.filter(&log::Record::builder().args(format_args!("GET /healthz 200")).build()));
+/// ```
+#[cfg(feature = "regex")]
+pub struct RegexFilter {
+    pub regex: regex::Regex,
+}
+
+#[cfg(feature = "regex")]
+impl RegexFilter {
+    pub fn new(pattern: &str) -> Result<Self, LogError> {
+        let regex = regex::Regex::new(pattern)
+            .map_err(|e| LogError::Parse(format!("invalid pattern {}: {}", pattern, e)))?;
+        Ok(Self { regex })
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Filter for RegexFilter {
+    fn filter(&self, record: &log::Record) -> bool {
+        match record.args().as_str() {
+            Some(v) => self.regex.is_match(v),
+            None => self.regex.is_match(&record.args().to_string()),
+        }
+    }
+}
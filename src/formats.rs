@@ -1,32 +1,110 @@
 use crate::appender::{Command, FastLogRecord, RecordFormat};
-use log::LevelFilter;
+use crate::date;
+pub use crate::embedded::escape_json;
+use crate::embedded::{write_fields, Line};
+use log::{Level, LevelFilter};
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+static LEVEL_NAMES: RwLock<Option<[String; 5]>> = RwLock::new(None);
+
+/// set the global level names used by the text formats,in the order of ERROR,WARN,INFO,DEBUG,TRACE,
+/// None is the standard names
+pub fn set_level_names(names: Option<[String; 5]>) {
+    *LEVEL_NAMES.write().unwrap_or_else(|e| e.into_inner()) = names;
+}
+
+/// get the global name of the level used by the text formats
+pub fn level_name(level: Level) -> Cow<'static, str> {
+    match LEVEL_NAMES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+    {
+        Some(names) => Cow::Owned(names[level as usize - 1].clone()),
+        None => Cow::Borrowed(level.as_str()),
+    }
+}
+
+/// the prefix of the targets whose messages are written verbatim by every format,
+/// without the timestamp,level and the other fields,for embedding the pre-formatted output
+/// of a child process or a third-party library:
+/// `log::info!(target: "raw::child", "{}", line)`
+pub const RAW_TARGET: &str = "raw::";
+
+/// write the message of a [`RAW_TARGET`] record verbatim into `formated` and return true,
+/// false for the other records
+pub fn format_raw(arg: &mut FastLogRecord) -> bool {
+    if arg.command != Command::CommandRecord || !arg.target.starts_with(RAW_TARGET) {
+        return false;
+    }
+    arg.formated.clear();
+    arg.formated.push_str(&arg.args);
+    if !arg.formated.ends_with('\n') {
+        arg.formated.push('\n');
+    }
+    true
+}
+
+/// format the record with `format`,the message of a [`RAW_TARGET`] record verbatim
+/// after the format changed it,so the redaction of [`RedactFormat`] applies to it too
+pub(crate) fn format_record(format: &dyn RecordFormat, arg: &mut FastLogRecord) {
+    format.do_format(arg);
+    format_raw(arg);
+}
 
 pub struct FastLogFormat {
     // show line level
     pub display_line_level: log::LevelFilter,
+    // show the name(or id) of the producing thread
+    pub display_thread: bool,
+    // the separator of the module path segments,default is "::"
+    pub module_path_separator: &'static str,
+    // shorten the module path to the width,0 is unlimited
+    pub module_path_width: usize,
 }
 
 impl RecordFormat for FastLogFormat {
     fn do_format(&self, arg: &mut FastLogRecord) {
         match &arg.command {
             Command::CommandRecord => {
-                let now = fastdate::DateTime::now();
-                if arg.level.to_level_filter() <= self.display_line_level {
-                    arg.formated = format!(
-                        "{:29} {} {} - {}  {}:{}\n",
-                        &now,
-                        arg.level,
-                        arg.module_path,
-                        arg.args,
-                        arg.file,
-                        arg.line.unwrap_or_default()
-                    );
-                } else {
-                    arg.formated = format!(
-                        "{:29} {} {} - {}\n",
-                        &now, arg.level, arg.module_path, arg.args
-                    );
-                }
+                let (secs, nanos) = date::local_secs(arg.now);
+                let id;
+                let thread = match arg.thread_id {
+                    _ if !self.display_thread => "",
+                    Some(v) if arg.thread_name.is_empty() => {
+                        id = format!("{:?}", v);
+                        &id
+                    }
+                    _ => &arg.thread_name,
+                };
+                let level_name = level_name(arg.level);
+                let module_path = shorten_module_path(
+                    &arg.module_path,
+                    self.module_path_separator,
+                    self.module_path_width,
+                );
+                let line = Line {
+                    secs,
+                    nanos,
+                    level: arg.level,
+                    level_name: &level_name,
+                    thread,
+                    module_path: &module_path,
+                    args: &arg.args,
+                    file: &arg.file,
+                    line: arg.line,
+                    seq: arg.seq,
+                    trace_id: arg.trace_id,
+                    span_id: arg.span_id,
+                    kv: &arg.kv,
+                };
+                arg.formated.clear();
+                date::write_local_date(&mut arg.formated, arg.now, date::time_precision().digits());
+                write_fields(&mut arg.formated, &line, self.display_line_level);
             }
             Command::CommandExit => {}
             Command::CommandFlush(_) => {}
@@ -38,34 +116,139 @@ impl FastLogFormat {
     pub fn new() -> FastLogFormat {
         Self {
             display_line_level: LevelFilter::Warn,
+            display_thread: false,
+            module_path_separator: "::",
+            module_path_width: 0,
         }
     }
 
+    /// show the name of the producing thread,or the id if the thread is unnamed.
+    /// the thread is captured only with `Config::with_source(true)`
+    pub fn set_display_thread(mut self, display: bool) -> Self {
+        self.display_thread = display;
+        self
+    }
+
     ///show line level
     pub fn set_display_line_level(mut self, level: LevelFilter) -> Self {
         self.display_line_level = level;
         self
     }
+
+    /// abbreviate the module path,see [`shorten_module_path`].
+    /// to keep the console compact and the files at full length,set it on the format of the console only:
+    /// `Config::new().custom_format(ConsoleAppender::new(), FastLogFormat::new().set_module_path(".", 24))`
+    pub fn set_module_path(mut self, separator: &'static str, max_width: usize) -> Self {
+        self.module_path_separator = separator;
+        self.module_path_width = max_width;
+        self
+    }
+}
+
+/// join the segments of `path` with `separator`,
+/// and replace the leading segments by `…` if longer than `max_width`(0 is unlimited),
+/// the last segment is always kept
+/// for example:
+/// ```rust
+/// use fast_log::shorten_module_path;
+/// assert_eq!(shorten_module_path("a::b::c::Handler", ".", 0), "a.b.c.Handler");
+/// assert_eq!(shorten_module_path("a::b::c::Handler", "::", 12), "…::Handler");
+/// assert_eq!(shorten_module_path("a::b::c::Handler", "::", 13), "…::c::Handler");
+/// ```
+pub fn shorten_module_path<'a>(path: &'a str, separator: &str, max_width: usize) -> Cow<'a, str> {
+    if separator == "::" && (max_width == 0 || path.chars().count() <= max_width) {
+        return Cow::Borrowed(path);
+    }
+    let segments: Vec<&str> = path.split("::").collect();
+    let joined = segments.join(separator);
+    if max_width == 0 || segments.len() == 1 || joined.chars().count() <= max_width {
+        return Cow::Owned(joined);
+    }
+    let separator_len = separator.chars().count();
+    //"…" and the last segment
+    let mut len = 1 + separator_len + segments[segments.len() - 1].chars().count();
+    let mut start = segments.len() - 1;
+    while start > 1 {
+        let next = len + segments[start - 1].chars().count() + separator_len;
+        if next > max_width {
+            break;
+        }
+        len = next;
+        start -= 1;
+    }
+    Cow::Owned(format!(
+        "…{}{}",
+        separator,
+        segments[start..].join(separator)
+    ))
 }
 
-pub struct FastLogFormatJson {}
+pub struct FastLogFormatJson {
+    // show the write time(when the record is formatted by the worker) beside the capture time
+    pub display_write_time: bool,
+}
 
 impl RecordFormat for FastLogFormatJson {
     fn do_format(&self, arg: &mut FastLogRecord) {
         match &arg.command {
             Command::CommandRecord => {
-                let now = fastdate::DateTime::now();
+                let now = date::time_precision().format(arg.now);
+                let write_date = if self.display_write_time {
+                    format!(
+                        ",\"write_date\":\"{}\"",
+                        date::time_precision().format(crate::now())
+                    )
+                } else {
+                    String::new()
+                };
                 //{"args":"Commencing yak shaving","date":"2022-08-19 09:53:47.798674","file":"example/src/split_log.rs","level":"INFO","line":21}
-                arg.formated = format!(
-                    "{}\"args\":\"{}\",\"date\":\"{}\",\"file\":\"{}\",\"level\":\"{}\",\"line\":{}{}",
-                    "{",
-                    arg.args,
-                    now,
-                    arg.file,
+                arg.formated.clear();
+                arg.formated.push_str("{\"args\":\"");
+                escape_json(&mut arg.formated, &arg.args);
+                write!(
+                    arg.formated,
+                    "\",\"date\":\"{}\"{},\"file\":\"",
+                    now, write_date
+                );
+                escape_json(&mut arg.formated, &arg.file);
+                arg.formated.push_str("\",");
+                if let Some(hostname) = &arg.hostname {
+                    arg.formated.push_str("\"hostname\":\"");
+                    escape_json(&mut arg.formated, hostname);
+                    arg.formated.push_str("\",");
+                }
+                write!(
+                    arg.formated,
+                    "\"level\":\"{}\",\"line\":{}",
                     arg.level,
-                    arg.line.unwrap_or_default(),
-                    "}\n"
+                    arg.line.unwrap_or_default()
                 );
+                if let Some(pid) = arg.pid {
+                    write!(arg.formated, ",\"pid\":{}", pid);
+                }
+                if let Some(seq) = arg.seq {
+                    write!(arg.formated, ",\"seq\":{}", seq);
+                }
+                if let Some(span_id) = arg.span_id {
+                    write!(arg.formated, ",\"span_id\":\"{:016x}\"", span_id);
+                }
+                if let Some(trace_id) = arg.trace_id {
+                    write!(arg.formated, ",\"trace_id\":\"{:032x}\"", trace_id);
+                }
+                if !arg.kv.is_empty() {
+                    arg.formated.push_str(",\"kv\":{");
+                    for (i, (k, v)) in arg.kv.iter().enumerate() {
+                        if i > 0 {
+                            arg.formated.push(',');
+                        }
+                        arg.formated.push('"');
+                        escape_json(&mut arg.formated, k);
+                        arg.formated.push_str("\":");
+                        v.write_json(&mut arg.formated);
+                    }
+                    arg.formated.push('}');
+                }
+                arg.formated.push_str("}\n");
             }
             Command::CommandExit => {}
             Command::CommandFlush(_) => {}
@@ -75,6 +258,448 @@ impl RecordFormat for FastLogFormatJson {
 
 impl FastLogFormatJson {
     pub fn new() -> FastLogFormatJson {
-        Self {}
+        Self {
+            display_write_time: false,
+        }
+    }
+
+    ///show the write time as "write_date",so consumers can measure the end-to-end logging latency
+    pub fn set_display_write_time(mut self, display: bool) -> Self {
+        self.display_write_time = display;
+        self
+    }
+}
+
+/// the colored multi-line format for local development:
+/// a level badge,dimmed date and metadata,targets aligned in a column,
+/// and the following lines of multi-line messages(backtraces...) indented under the first one.
+/// the colors are disabled if stdout is not a terminal or `NO_COLOR` is set
+/// for example(the file keeps the default format):
+/// ```rust
+/// use fast_log::Config;
+/// fast_log::init(Config::new().pretty().file("target/logs/pretty.log"));
+/// log::info!("hello");
+/// ```
+pub struct PrettyFormat {
+    // write ANSI colors
+    pub colored: bool,
+    // show `at {file}:{line}` at or above the level
+    pub display_line_level: LevelFilter,
+    // the targets are padded to the longest target seen,up to it
+    pub max_target_width: usize,
+    // the separator of the target segments,default is "::"
+    pub module_path_separator: &'static str,
+    // shorten the target to the width,0 is unlimited
+    pub module_path_width: usize,
+    target_width: AtomicUsize,
+}
+
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+
+impl PrettyFormat {
+    pub fn new() -> Self {
+        Self {
+            colored: std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            display_line_level: LevelFilter::Warn,
+            max_target_width: 32,
+            module_path_separator: "::",
+            module_path_width: 0,
+            target_width: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn colored(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+
+    ///show line level
+    pub fn set_display_line_level(mut self, level: LevelFilter) -> Self {
+        self.display_line_level = level;
+        self
+    }
+
+    pub fn max_target_width(mut self, width: usize) -> Self {
+        self.max_target_width = width;
+        self
+    }
+
+    /// abbreviate the target,see [`shorten_module_path`]
+    pub fn set_module_path(mut self, separator: &'static str, max_width: usize) -> Self {
+        self.module_path_separator = separator;
+        self.module_path_width = max_width;
+        self
+    }
+
+    fn badge(level: Level) -> &'static str {
+        match level {
+            Level::Error => "\x1b[1;37;41m",
+            Level::Warn => "\x1b[1;30;43m",
+            Level::Info => "\x1b[1;30;42m",
+            Level::Debug => "\x1b[1;30;44m",
+            Level::Trace => "\x1b[1;30;45m",
+        }
+    }
+
+    /// write `v` dimmed
+    fn dim(&self, buf: &mut String, v: &dyn std::fmt::Display) {
+        if self.colored {
+            write!(buf, "{}{}{}", DIM, v, RESET);
+        } else {
+            write!(buf, "{}", v);
+        }
+    }
+}
+
+impl Default for PrettyFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecordFormat for PrettyFormat {
+    fn do_format(&self, arg: &mut FastLogRecord) {
+        match &arg.command {
+            Command::CommandRecord => {
+                let target = shorten_module_path(
+                    &arg.target,
+                    self.module_path_separator,
+                    self.module_path_width,
+                );
+                let target_len = target.chars().count();
+                let width = self
+                    .target_width
+                    .fetch_max(target_len.min(self.max_target_width), Ordering::Relaxed)
+                    .max(target_len.min(self.max_target_width));
+                let date = date::time_precision().format(arg.now);
+                let level_name = level_name(arg.level);
+                let buf = &mut arg.formated;
+                buf.clear();
+                self.dim(buf, &date);
+                if self.colored {
+                    write!(
+                        buf,
+                        " {} {:<5} {} ",
+                        Self::badge(arg.level),
+                        level_name,
+                        RESET
+                    );
+                } else {
+                    write!(buf, " {:<5} ", level_name);
+                }
+                let target = format!("{:<w$}", target, w = width);
+                if self.colored {
+                    write!(buf, "\x1b[36m{}{} ", target, RESET);
+                } else {
+                    write!(buf, "{} ", target);
+                }
+                //the column of the message,the badge has a space on both sides if colored
+                let indent = " ".repeat(
+                    date.chars().count()
+                        + level_name.chars().count().max(5)
+                        + 2
+                        + if self.colored { 2 } else { 0 }
+                        + width.max(target_len)
+                        + 1,
+                );
+                for (i, line) in arg.args.lines().enumerate() {
+                    if i > 0 {
+                        buf.push('\n');
+                        buf.push_str(&indent);
+                    }
+                    buf.push_str(line);
+                }
+                let mut meta = String::new();
+                for (k, v) in &arg.kv {
+                    write!(meta, " {}={}", k, v);
+                }
+                if let Some(seq) = arg.seq {
+                    write!(meta, " seq={}", seq);
+                }
+                if let Some(trace_id) = arg.trace_id {
+                    write!(meta, " trace_id={:032x}", trace_id);
+                }
+                if let Some(span_id) = arg.span_id {
+                    write!(meta, " span_id={:016x}", span_id);
+                }
+                if !meta.is_empty() {
+                    self.dim(buf, &meta);
+                }
+                if arg.level.to_level_filter() <= self.display_line_level && !arg.file.is_empty() {
+                    buf.push('\n');
+                    buf.push_str(&indent);
+                    self.dim(
+                        buf,
+                        &format_args!("at {}:{}", arg.file, arg.line.unwrap_or_default()),
+                    );
+                }
+                buf.push('\n');
+            }
+            Command::CommandExit => {}
+            Command::CommandFlush(_) => {}
+        }
+    }
+}
+
+/// the columns of [`CsvFormat`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CsvColumn {
+    Date,
+    Level,
+    Target,
+    ModulePath,
+    File,
+    Line,
+    Args,
+    Thread,
+    Hostname,
+    Pid,
+    Seq,
+    TraceId,
+    SpanId,
+}
+
+/// format records as CSV/TSV rows,so logs can be bulk-loaded into analytics databases.
+/// CSV fields are quoted per RFC 4180,TSV fields escape `\`,tab and line breaks with `\`
+/// for example:
+/// ```rust
+/// use fast_log::{CsvColumn, CsvFormat};
+/// let format = CsvFormat::tsv().columns(vec![CsvColumn::Date, CsvColumn::Level, CsvColumn::Args]);
+/// ```
+pub struct CsvFormat {
+    pub columns: Vec<CsvColumn>,
+    pub delimiter: char,
+}
+
+impl CsvFormat {
+    /// comma separated,the columns are Date,Level,Target,File,Line,Args
+    pub fn new() -> Self {
+        Self {
+            columns: vec![
+                CsvColumn::Date,
+                CsvColumn::Level,
+                CsvColumn::Target,
+                CsvColumn::File,
+                CsvColumn::Line,
+                CsvColumn::Args,
+            ],
+            delimiter: ',',
+        }
+    }
+
+    /// tab separated
+    pub fn tsv() -> Self {
+        Self::new().delimiter('\t')
+    }
+
+    /// set the columns and their order
+    pub fn columns(mut self, columns: Vec<CsvColumn>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    fn write_field(&self, buf: &mut String, v: &str) {
+        if self.delimiter == '\t' {
+            for c in v.chars() {
+                match c {
+                    '\\' => buf.push_str("\\\\"),
+                    '\t' => buf.push_str("\\t"),
+                    '\n' => buf.push_str("\\n"),
+                    '\r' => buf.push_str("\\r"),
+                    c => buf.push(c),
+                }
+            }
+        } else if v.contains([self.delimiter, '"', '\n', '\r']) {
+            buf.push('"');
+            buf.push_str(&v.replace('"', "\"\""));
+            buf.push('"');
+        } else {
+            buf.push_str(v);
+        }
+    }
+}
+
+impl Default for CsvFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecordFormat for CsvFormat {
+    fn do_format(&self, arg: &mut FastLogRecord) {
+        match &arg.command {
+            Command::CommandRecord => {
+                let row = &mut arg.formated;
+                row.clear();
+                for (i, column) in self.columns.iter().enumerate() {
+                    if i > 0 {
+                        row.push(self.delimiter);
+                    }
+                    match column {
+                        CsvColumn::Date => {
+                            row.push_str(&date::time_precision().format(arg.now));
+                        }
+                        CsvColumn::Level => {
+                            row.push_str(arg.level.as_str());
+                        }
+                        CsvColumn::Target => self.write_field(row, &arg.target),
+                        CsvColumn::ModulePath => self.write_field(row, &arg.module_path),
+                        CsvColumn::File => self.write_field(row, &arg.file),
+                        CsvColumn::Line => {
+                            if let Some(line) = arg.line {
+                                write!(row, "{}", line);
+                            }
+                        }
+                        CsvColumn::Args => self.write_field(row, &arg.args),
+                        CsvColumn::Thread => {
+                            if !arg.thread_name.is_empty() {
+                                self.write_field(row, &arg.thread_name);
+                            } else if let Some(id) = arg.thread_id {
+                                write!(row, "{:?}", id);
+                            }
+                        }
+                        CsvColumn::Hostname => {
+                            if let Some(hostname) = &arg.hostname {
+                                self.write_field(row, hostname);
+                            }
+                        }
+                        CsvColumn::Pid => {
+                            if let Some(pid) = arg.pid {
+                                write!(row, "{}", pid);
+                            }
+                        }
+                        CsvColumn::Seq => {
+                            if let Some(seq) = arg.seq {
+                                write!(row, "{}", seq);
+                            }
+                        }
+                        CsvColumn::TraceId => {
+                            if let Some(trace_id) = arg.trace_id {
+                                write!(row, "{:032x}", trace_id);
+                            }
+                        }
+                        CsvColumn::SpanId => {
+                            if let Some(span_id) = arg.span_id {
+                                write!(row, "{:016x}", span_id);
+                            }
+                        }
+                    }
+                }
+                row.push('\n');
+            }
+            Command::CommandExit => {}
+            Command::CommandFlush(_) => {}
+        }
+    }
+}
+
+/// replace the secrets matched by the patterns with `***` in the args and the key-values of the records,
+/// then format them by the inner format. the records are changed in place,
+/// so the appenders formatting the records again(for example `Config::custom_format`) never see the secrets.
+/// a pattern with capture groups replaces the groups only,else the whole match.
+/// the messages of `print` are written as is
+/// you need enable fast_log = { ... ,features=["regex"]}
+/// ```rust
+/// use fast_log::{FastLogFormat, RedactFormat};
+/// let format = RedactFormat::new(
+///     FastLogFormat::new(),
+///     &[RedactFormat::CARD_NUMBER, RedactFormat::BEARER_TOKEN],
+/// )
+/// .unwrap();
+/// assert_eq!(format.redact("paid by 4111 1111 1111 1111"), "paid by ***");
+/// assert_eq!(format.redact("Authorization: Bearer eyJhbGciOi.x"), "Authorization: Bearer ***");
+/// let config = fast_log::Config::new().console().format(format);
+/// ```
+#[cfg(feature = "regex")]
+pub struct RedactFormat {
+    pub format: Box<dyn RecordFormat>,
+    pub patterns: Vec<regex::Regex>,
+    /// default is `***`
+    pub replacement: String,
+}
+
+#[cfg(feature = "regex")]
+impl RedactFormat {
+    /// the payment card numbers,13 to 19 digits separated by spaces or dashes
+    pub const CARD_NUMBER: &'static str = r"\b(?:\d[ -]?){12,18}\d\b";
+    /// the token of a bearer authorization
+    pub const BEARER_TOKEN: &'static str = r"(?i)\bbearer\s+([A-Za-z0-9._~+/=-]+)";
+
+    pub fn new<F: RecordFormat + 'static>(
+        format: F,
+        patterns: &[&str],
+    ) -> Result<Self, crate::error::LogError> {
+        let mut regexes = Vec::with_capacity(patterns.len());
+        for p in patterns {
+            regexes.push(regex::Regex::new(p).map_err(|e| {
+                crate::error::LogError::Parse(format!("invalid pattern {}: {}", p, e))
+            })?);
+        }
+        Ok(Self {
+            format: Box::new(format),
+            patterns: regexes,
+            replacement: "***".to_string(),
+        })
+    }
+
+    pub fn replacement(mut self, replacement: &str) -> Self {
+        self.replacement = replacement.to_string();
+        self
+    }
+
+    /// replace the secrets of `text`
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for regex in &self.patterns {
+            let mut spans = vec![];
+            for caps in regex.captures_iter(&text) {
+                if caps.len() > 1 {
+                    spans.extend(caps.iter().skip(1).flatten().map(|m| m.range()));
+                } else if let Some(m) = caps.get(0) {
+                    spans.push(m.range());
+                }
+            }
+            if spans.is_empty() {
+                continue;
+            }
+            let mut redacted = String::with_capacity(text.len());
+            let mut last = 0;
+            for span in spans {
+                if span.start < last {
+                    continue;
+                }
+                redacted.push_str(&text[last..span.start]);
+                redacted.push_str(&self.replacement);
+                last = span.end;
+            }
+            redacted.push_str(&text[last..]);
+            text = Cow::Owned(redacted);
+        }
+        text
+    }
+}
+
+#[cfg(feature = "regex")]
+impl RecordFormat for RedactFormat {
+    fn do_format(&self, arg: &mut FastLogRecord) {
+        if arg.command == Command::CommandRecord {
+            if let Cow::Owned(v) = self.redact(&arg.args) {
+                arg.args = v;
+            }
+            for (_, v) in arg.kv.iter_mut() {
+                if let crate::appender::KvValue::Str(v) = v {
+                    if let Cow::Owned(redacted) = self.redact(v) {
+                        *v = redacted;
+                    }
+                }
+            }
+        }
+        self.format.do_format(arg);
     }
 }
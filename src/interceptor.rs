@@ -0,0 +1,57 @@
+use crate::appender::{Command, FastLogRecord};
+
+/// mutate,enrich,duplicate or drop the records on the worker thread before the format and the appenders,
+/// push the records to keep into `out`,push none to drop the record.
+/// the records formatted on the producer(`FormatOn::Producer`) are formatted again if `formated` is cleared
+/// for example:
+/// ```rust
+/// use fast_log::appender::FastLogRecord;
+/// use fast_log::Config;
+/// let config = Config::new()
+///     .console()
+///     .interceptor(|mut record: FastLogRecord, out: &mut Vec<FastLogRecord>| {
+///         //drop the health checks
+///         if record.target == "http::probe" {
+///             return;
+///         }
+///         record.kv.push(("region".to_string(), "eu-west-1".into()));
+///         out.push(record);
+///     });
+/// ```
+pub trait Interceptor: Send {
+    fn intercept(&mut self, record: FastLogRecord, out: &mut Vec<FastLogRecord>);
+}
+
+impl Interceptor for Box<dyn Interceptor> {
+    fn intercept(&mut self, record: FastLogRecord, out: &mut Vec<FastLogRecord>) {
+        self.as_mut().intercept(record, out)
+    }
+}
+
+impl<F> Interceptor for F
+where
+    F: FnMut(FastLogRecord, &mut Vec<FastLogRecord>) + Send,
+{
+    fn intercept(&mut self, record: FastLogRecord, out: &mut Vec<FastLogRecord>) {
+        self(record, out)
+    }
+}
+
+/// run the records through the chain in order,the commands(flush,exit) pass through in place
+pub(crate) fn intercept(
+    chain: &mut [Box<dyn Interceptor>],
+    records: Vec<FastLogRecord>,
+) -> Vec<FastLogRecord> {
+    let mut records = records;
+    for interceptor in chain.iter_mut() {
+        let mut out = Vec::with_capacity(records.len());
+        for x in records {
+            match x.command {
+                Command::CommandRecord => interceptor.intercept(x, &mut out),
+                _ => out.push(x),
+            }
+        }
+        records = out;
+    }
+    records
+}
@@ -0,0 +1,391 @@
+//! the crash journal,see [`Config::journal`](crate::Config::journal).
+//! the records of the log macros are appended to a memory-mapped ring before they are sent to the channel,
+//! and released after every appender wrote and flushed them,
+//! the records still in the ring when the process died are replayed by the next `init`.
+//!
+//! crash consistency:
+//! * a process crash(panic=abort,SIGKILL,OOM) loses nothing,the mapped pages belong to the kernel
+//! * a power failure loses the records appended after the last writeback of the kernel
+//! * a record bigger than the ring is sent without the journal
+//!
+//! the layout is `FLJ2`,the head and the tail(little endian u64 offsets of the ring),then the ring of
+//! entries,each is the u32 length and the encoded record,`u32::MAX` marks the wrap to the start
+#![allow(unsafe_code)]
+
+use crate::appender::{Command, FastLogRecord, KvValue};
+use crate::diagnostic;
+use crate::error::LogError;
+use crate::runtime::{try_send_num, Sender};
+use crossbeam_channel::TrySendError;
+use log::Level;
+use memmap2::MmapMut;
+use std::fs::OpenOptions;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+
+const MAGIC: &[u8; 4] = b"FLJ2";
+
+/// the magic,the head and the tail
+const HEADER: usize = 24;
+
+const WRAP: u32 = u32::MAX;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static JOURNAL: Mutex<Option<Journal>> = Mutex::new(None);
+
+/// notified when the appenders release the written records
+static SPACE: Condvar = Condvar::new();
+
+struct Journal {
+    map: MmapMut,
+    /// the size of the ring
+    cap: u64,
+    /// the end of the last entry
+    head: u64,
+    /// the start of the first entry not written by all appenders
+    tail: u64,
+    /// the end of the entries written by each appender
+    done: Vec<u64>,
+}
+
+impl Journal {
+    fn read_u32(&self, pos: u64) -> u32 {
+        let at = HEADER + (pos % self.cap) as usize;
+        u32::from_le_bytes(self.map[at..at + 4].try_into().unwrap())
+    }
+
+    fn write(&mut self, pos: u64, data: &[u8]) {
+        let at = HEADER + (pos % self.cap) as usize;
+        self.map[at..at + data.len()].copy_from_slice(data);
+    }
+
+    fn store_header(&mut self) {
+        let (head, tail) = (self.head, self.tail);
+        self.map[4..12].copy_from_slice(&head.to_le_bytes());
+        self.map[12..20].copy_from_slice(&tail.to_le_bytes());
+    }
+
+    /// the bytes skipped to the start of the ring before an entry of `len` bytes at `pos`
+    fn padding(&self, pos: u64, len: u64) -> u64 {
+        let remain = self.cap - pos % self.cap;
+        if remain < 4 + len {
+            remain
+        } else {
+            0
+        }
+    }
+
+    /// append the entry,None if the ring is full
+    fn append(&mut self, entry: &[u8]) -> Option<u64> {
+        let len = entry.len() as u64;
+        let padding = self.padding(self.head, len);
+        if self.head + padding + 4 + len - self.tail > self.cap {
+            return None;
+        }
+        if padding >= 4 {
+            self.write(self.head, &WRAP.to_le_bytes());
+        }
+        let start = self.head + padding;
+        self.write(start, &(len as u32).to_le_bytes());
+        self.write(start + 4, entry);
+        Some(start + 4 + len)
+    }
+
+    /// the entries between the tail and the head,each with its end in the ring.
+    /// the head is moved back to the end of the last readable entry
+    fn pending(&mut self) -> Vec<FastLogRecord> {
+        let mut records = vec![];
+        let mut pos = self.tail;
+        while pos < self.head {
+            let remain = self.cap - pos % self.cap;
+            if remain < 4 || self.read_u32(pos) == WRAP {
+                pos += remain;
+                continue;
+            }
+            let len = self.read_u32(pos) as u64;
+            if len + 4 > remain {
+                break;
+            }
+            let at = HEADER + (pos % self.cap) as usize + 4;
+            match decode(&self.map[at..at + len as usize]) {
+                Some(mut v) => {
+                    v.journal_end = Some(pos + 4 + len);
+                    records.push(v);
+                }
+                None => break,
+            }
+            pos += 4 + len;
+        }
+        self.head = self.head.min(pos);
+        records
+    }
+}
+
+/// open the journal at `path` for `appenders` appenders,and give back the records not written before
+/// the last exit. they stay in the journal until the appenders write them again,
+/// the journal is recreated with `size` only if it has no such records
+pub(crate) fn open(
+    path: &str,
+    size: usize,
+    appenders: usize,
+) -> Result<Vec<FastLogRecord>, LogError> {
+    let path = path.replace('\\', "/");
+    if let Some(right) = path.rfind('/') {
+        std::fs::create_dir_all(&path[0..right])?;
+    }
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)?;
+    let len = file.metadata()?.len() as usize;
+    if len > HEADER {
+        //the journal is opened read/write by this process only
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        if &map[0..4] == MAGIC {
+            let head = u64::from_le_bytes(map[4..12].try_into().unwrap());
+            let tail = u64::from_le_bytes(map[12..20].try_into().unwrap());
+            let cap = (len - HEADER) as u64;
+            if tail <= head && head - tail <= cap {
+                let mut journal = Journal {
+                    map,
+                    cap,
+                    head,
+                    tail,
+                    done: vec![tail; appenders],
+                };
+                let pending = journal.pending();
+                if !pending.is_empty() {
+                    journal.store_header();
+                    *JOURNAL.lock().unwrap_or_else(|e| e.into_inner()) = Some(journal);
+                    ENABLED.store(true, Ordering::SeqCst);
+                    return Ok(pending);
+                }
+            }
+        }
+    }
+    let size = size.max(4096);
+    file.set_len(0)?;
+    file.set_len((HEADER + size) as u64)?;
+    let mut map = unsafe { MmapMut::map_mut(&file)? };
+    map[0..4].copy_from_slice(MAGIC);
+    *JOURNAL.lock().unwrap_or_else(|e| e.into_inner()) = Some(Journal {
+        map,
+        cap: size as u64,
+        head: 0,
+        tail: 0,
+        done: vec![0; appenders],
+    });
+    ENABLED.store(true, Ordering::SeqCst);
+    Ok(vec![])
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// append the record to the journal and send it to the channel,in the same order.
+/// waits while the journal or the channel is full,gives back the record if the channel is closed,
+/// or full with `non_blocking`. the record larger than the journal is sent without journaling
+pub(crate) fn send(
+    sender: &Sender<FastLogRecord>,
+    record: FastLogRecord,
+    non_blocking: bool,
+) -> Result<(), Box<FastLogRecord>> {
+    let entry = encode(&record);
+    let mut record = record;
+    let mut guard = JOURNAL.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        let journal = match guard.as_mut() {
+            Some(v) if entry.len() as u64 + 4 <= v.cap => v,
+            _ => {
+                drop(guard);
+                if non_blocking {
+                    return sender
+                        .try_send(record)
+                        .map_err(|e| Box::new(e.into_inner()));
+                }
+                return try_send_num(sender, 3, record).map_err(|e| Box::new(e.into_inner()));
+            }
+        };
+        if let Some(end) = journal.append(&entry) {
+            record.journal_end = Some(end);
+            match sender.try_send(record) {
+                Ok(()) => {
+                    journal.head = end;
+                    journal.store_header();
+                    return Ok(());
+                }
+                Err(TrySendError::Disconnected(mut v)) => {
+                    v.journal_end = None;
+                    return Err(Box::new(v));
+                }
+                Err(TrySendError::Full(v)) => record = v,
+            }
+        }
+        record.journal_end = None;
+        if non_blocking {
+            return Err(Box::new(record));
+        }
+        //the appender threads take the lock to release the written records,it is released while waiting.
+        //the timeout retries the full channel,the worker takes records without the lock
+        guard = SPACE
+            .wait_timeout(guard, Duration::from_millis(1))
+            .unwrap_or_else(|e| e.into_inner())
+            .0;
+    }
+}
+
+/// the appender `index` wrote and flushed the records until `end`
+pub(crate) fn commit(index: usize, end: u64) {
+    if let Some(journal) = JOURNAL.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        if let Some(done) = journal.done.get_mut(index) {
+            *done = (*done).max(end);
+        }
+        let tail = journal.done.iter().copied().min().unwrap_or(journal.head);
+        if tail > journal.tail {
+            journal.tail = tail;
+            journal.store_header();
+            SPACE.notify_all();
+        }
+    }
+}
+
+/// stop journaling,the records not written stay in the journal until the next `init`
+pub(crate) fn close() {
+    ENABLED.store(false, Ordering::SeqCst);
+    if let Some(journal) = JOURNAL.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        if let Err(e) = journal.map.flush() {
+            diagnostic::error(LogError::from(e));
+        }
+    }
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// the time,level,line,target,module path,file,thread name,args,formatted line and key-values of the record
+fn encode(record: &FastLogRecord) -> Vec<u8> {
+    let since = record.now.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let mut buf = Vec::with_capacity(64 + record.args.len() + record.formated.len());
+    buf.extend_from_slice(&since.as_secs().to_le_bytes());
+    buf.extend_from_slice(&since.subsec_nanos().to_le_bytes());
+    buf.push(record.level as u8);
+    buf.extend_from_slice(&record.line.unwrap_or(u32::MAX).to_le_bytes());
+    put_str(&mut buf, &record.target);
+    put_str(&mut buf, &record.module_path);
+    put_str(&mut buf, &record.file);
+    put_str(&mut buf, &record.thread_name);
+    put_str(&mut buf, &record.args);
+    //the line of `print` and of the producer format,written as is
+    put_str(&mut buf, &record.formated);
+    buf.extend_from_slice(&(record.kv.len() as u32).to_le_bytes());
+    for (k, v) in &record.kv {
+        put_str(&mut buf, k);
+        match v {
+            KvValue::Str(v) => {
+                buf.push(0);
+                put_str(&mut buf, v);
+            }
+            KvValue::Bool(v) => buf.extend_from_slice(&[1, *v as u8]),
+            KvValue::I64(v) => {
+                buf.push(2);
+                buf.extend_from_slice(&v.to_le_bytes());
+            }
+            KvValue::U64(v) => {
+                buf.push(3);
+                buf.extend_from_slice(&v.to_le_bytes());
+            }
+            KvValue::F64(v) => {
+                buf.push(4);
+                buf.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+    }
+    buf
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (v, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Some(v)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.bytes(len)?.to_vec()).ok()
+    }
+}
+
+fn decode(entry: &[u8]) -> Option<FastLogRecord> {
+    let mut r = Reader(entry);
+    let secs = r.u64()?;
+    let nanos = r.u32()?;
+    let level = match r.bytes(1)?[0] {
+        1 => Level::Error,
+        2 => Level::Warn,
+        3 => Level::Info,
+        4 => Level::Debug,
+        5 => Level::Trace,
+        _ => return None,
+    };
+    let line = r.u32()?;
+    let target = r.string()?;
+    let module_path = r.string()?;
+    let file = r.string()?;
+    let thread_name = r.string()?;
+    let args = r.string()?;
+    let formated = r.string()?;
+    let mut kv = vec![];
+    for _ in 0..r.u32()? {
+        let k = r.string()?;
+        let v = match r.bytes(1)?[0] {
+            0 => KvValue::Str(r.string()?),
+            1 => KvValue::Bool(r.bytes(1)?[0] != 0),
+            2 => KvValue::I64(r.u64()? as i64),
+            3 => KvValue::U64(r.u64()?),
+            4 => KvValue::F64(f64::from_bits(r.u64()?)),
+            _ => return None,
+        };
+        kv.push((k, v));
+    }
+    Some(FastLogRecord {
+        command: Command::CommandRecord,
+        level,
+        target,
+        args,
+        module_path,
+        file,
+        line: (line != u32::MAX).then_some(line),
+        thread_name,
+        thread_id: None,
+        hostname: None,
+        pid: None,
+        seq: None,
+        journal_end: None,
+        kv,
+        trace_id: None,
+        span_id: None,
+        now: UNIX_EPOCH + Duration::new(secs, nanos),
+        formated,
+    })
+}
@@ -0,0 +1,93 @@
+//! the per-target levels changeable at runtime,layered on top of the level of the logger.
+//! a record whose target is the name or starts with "name::" is checked by the level of the longest name
+//! instead of the level of the logger,for surgical debugging in production:
+//! ```rust
+//! use log::LevelFilter;
+//! fast_log::set_target_level("my_app::db", LevelFilter::Trace);
+//! assert_eq!(fast_log::target_level("my_app::db::pool"), LevelFilter::Trace);
+//! fast_log::clear_target_level("my_app::db");
+//! ```
+use crate::fast_log::LOGGER;
+use log::LevelFilter;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+static HAS_TARGET_LEVELS: AtomicBool = AtomicBool::new(false);
+/// sorted by the name length,longest first
+static TARGET_LEVELS: RwLock<Vec<(String, LevelFilter)>> = RwLock::new(vec![]);
+
+fn contains(name: &str, target: &str) -> bool {
+    target == name || (target.starts_with(name) && target[name.len()..].starts_with("::"))
+}
+
+/// set the level of the records of `target` and its sub modules,replacing the previous level of `target`
+pub fn set_target_level(target: &str, level: LevelFilter) {
+    let mut levels = TARGET_LEVELS.write().unwrap_or_else(|e| e.into_inner());
+    levels.retain(|(v, _)| v != target);
+    levels.push((target.to_string(), level));
+    levels.sort_by_key(|(v, _)| std::cmp::Reverse(v.len()));
+    HAS_TARGET_LEVELS.store(true, Ordering::SeqCst);
+    drop(levels);
+    //the logger sets the max level on init if not created yet
+    if let Some(logger) = Lazy::get(&LOGGER) {
+        logger.set_level(logger.get_level());
+    }
+}
+
+/// remove the level of `target`,its records are checked by the level of the logger again.
+/// return the removed level
+pub fn clear_target_level(target: &str) -> Option<LevelFilter> {
+    let mut levels = TARGET_LEVELS.write().unwrap_or_else(|e| e.into_inner());
+    let index = levels.iter().position(|(v, _)| v == target)?;
+    let (_, level) = levels.remove(index);
+    HAS_TARGET_LEVELS.store(!levels.is_empty(), Ordering::SeqCst);
+    drop(levels);
+    //the logger sets the max level on init if not created yet
+    if let Some(logger) = Lazy::get(&LOGGER) {
+        logger.set_level(logger.get_level());
+    }
+    Some(level)
+}
+
+/// the targets and the levels set by `set_target_level`
+pub fn target_levels() -> Vec<(String, LevelFilter)> {
+    TARGET_LEVELS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// the effective level of the records of `target`
+pub fn target_level(target: &str) -> LevelFilter {
+    level_of(target)
+        .or_else(|| Lazy::get(&LOGGER).map(|v| v.get_level()))
+        .unwrap_or(LevelFilter::Off)
+}
+
+pub(crate) fn has_target_levels() -> bool {
+    HAS_TARGET_LEVELS.load(Ordering::Relaxed)
+}
+
+/// the level set for `target` or its parent module,None if the level of the logger applies
+pub(crate) fn level_of(target: &str) -> Option<LevelFilter> {
+    if !has_target_levels() {
+        return None;
+    }
+    TARGET_LEVELS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(name, _)| contains(name, target))
+        .map(|(_, level)| *level)
+}
+
+/// the max level of the `log` macros,the most verbose of `level` and the target levels
+pub(crate) fn max_level(level: LevelFilter) -> LevelFilter {
+    TARGET_LEVELS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|(_, level)| *level)
+        .fold(level, |max, v| max.max(v))
+}
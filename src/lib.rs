@@ -1,22 +1,79 @@
-#![forbid(unsafe_code)]
+#![cfg_attr(
+    not(any(feature = "mmap", feature = "logcat", feature = "capture")),
+    forbid(unsafe_code)
+)]
+#![cfg_attr(
+    any(feature = "mmap", feature = "logcat", feature = "capture"),
+    deny(unsafe_code)
+)]
 #![allow(unused_variables)]
 #![allow(unused_assignments)]
 #![allow(unused_must_use)]
+//the worker thread and the file appenders are unused without threads
+#![cfg_attr(
+    all(target_arch = "wasm32", target_os = "unknown"),
+    allow(dead_code, unused_imports)
+)]
 
+extern crate alloc;
 extern crate core;
 
+#[macro_use]
+mod macros;
+
+#[cfg(feature = "admin")]
+pub mod admin;
 pub mod appender;
 pub mod bencher;
+#[cfg(all(feature = "capture", unix))]
+pub mod capture;
+pub mod clock;
 pub mod config;
+#[cfg(feature = "config_file")]
+pub mod config_file;
 pub mod consts;
+pub mod date;
+pub mod diagnostic;
+pub use fast_log_core as embedded;
+pub mod encoding;
 pub mod error;
+pub mod ext;
 pub mod fast_log;
 pub mod filter;
 pub mod formats;
+pub mod interceptor;
+#[cfg(feature = "mmap")]
+pub mod journal;
+pub mod levels;
+pub mod metrics;
+pub mod panic;
+pub mod pipe;
 pub mod plugin;
+pub mod pool;
 pub mod runtime;
+pub mod scrub;
+pub mod shard;
+pub mod sub_logger;
+pub mod test;
+pub mod timer;
+#[cfg(feature = "watch")]
+pub mod watch;
 
-pub use crate::config::Config;
+pub use crate::bencher::bench_mode;
+#[cfg(all(feature = "capture", unix))]
+pub use crate::capture::capture_stdout;
+pub use crate::clock::Clock;
+pub use crate::config::{Config, FormatOn, FsyncPolicy, ProducerMode};
 pub use crate::fast_log::*;
 pub use crate::formats::*;
+pub use crate::levels::{clear_target_level, set_target_level, target_level, target_levels};
+pub use crate::metrics::metrics;
+pub use crate::panic::install_panic_hook;
+#[cfg(feature = "encrypt")]
+pub use crate::plugin::encrypt::decrypt_log;
+pub use crate::plugin::ring_buffer::dump_recent;
+#[cfg(feature = "watch")]
+pub use crate::watch::watch;
 pub use runtime::*;
+
+pub use log;
@@ -0,0 +1,137 @@
+/// make a PrintMeta,the target is derived from the calling module
+/// for example:
+/// ```rust
+/// fast_log::print_with(fast_log::print_meta!(log::Level::Info), "hello".to_string());
+/// ```
+#[macro_export]
+macro_rules! print_meta {
+    ($level:expr) => {
+        $crate::PrintMeta::new($level, module_path!())
+    };
+}
+
+/// log a critical record synchronously,see `fast_log::log_sync`.
+/// the target is the calling module,or set by `target:`
+/// for example:
+/// ```rust
+/// let _ = fast_log::log_sync!(log::Level::Error, "disk {} is full", "/data");
+/// let _ = fast_log::log_sync!(target: "payments", log::Level::Error, "charge {} failed", 1);
+/// ```
+#[macro_export]
+macro_rules! log_sync {
+    (target: $target:expr, $level:expr, $($arg:tt)+) => {
+        $crate::log_sync($level, $target, &format!($($arg)+))
+    };
+    ($level:expr, $($arg:tt)+) => {
+        $crate::log_sync($level, module_path!(), &format!($($arg)+))
+    };
+}
+
+/// log the first occurrence of each call site at WARN, and the subsequent occurrences at DEBUG.
+/// useful for deprecation notices and config fallbacks in hot paths
+/// for example:
+/// ```rust
+/// fast_log::warn_once!("config key {} is deprecated", "chan_len");
+/// ```
+#[macro_export]
+macro_rules! warn_once {
+    ($($arg:tt)+) => {{
+        static WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+        if WARNED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+            $crate::log::debug!($($arg)+);
+        } else {
+            $crate::log::warn!($($arg)+);
+        }
+    }};
+}
+
+/// write an audit record synchronously,see `fast_log::audit`.
+/// the target is the calling module,or set by `target:`
+/// for example:
+/// ```rust
+/// let _ = fast_log::audit!("user {} logged in", 1);
+/// let _ = fast_log::audit!(target: "access", "user {} logged out", 1);
+/// ```
+#[macro_export]
+macro_rules! audit {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::audit($target, &format!($($arg)+))
+    };
+    ($($arg:tt)+) => {
+        $crate::audit(module_path!(), &format!($($arg)+))
+    };
+}
+
+/// log a message with structured key-values,the keys are identifiers or string literals.
+/// the key-values are written as `key=value` by `FastLogFormat`,the "kv" object by `FastLogFormatJson`
+/// and the attributes by the OTLP exporter,the same as `log::info!(order_id = 1; "paid")`
+/// for example:
+/// ```rust
+/// let (id, amount) = (1, 9.5);
+/// fast_log::log_kv!(log::Level::Info, "paid", {"order_id": id, "amount": amount});
+/// fast_log::info_kv!("paid", {order_id: id, amount: amount});
+/// ```
+#[macro_export]
+macro_rules! log_kv {
+    ($level:expr, $msg:literal, {$($key:tt : $value:expr),+ $(,)?}) => {
+        $crate::log::log!($level, $($key = $value),+; $msg)
+    };
+}
+
+/// see [`log_kv!`]
+#[macro_export]
+macro_rules! trace_kv {
+    ($($arg:tt)+) => {
+        $crate::log_kv!($crate::log::Level::Trace, $($arg)+)
+    };
+}
+
+/// see [`log_kv!`]
+#[macro_export]
+macro_rules! debug_kv {
+    ($($arg:tt)+) => {
+        $crate::log_kv!($crate::log::Level::Debug, $($arg)+)
+    };
+}
+
+/// see [`log_kv!`]
+#[macro_export]
+macro_rules! info_kv {
+    ($($arg:tt)+) => {
+        $crate::log_kv!($crate::log::Level::Info, $($arg)+)
+    };
+}
+
+/// see [`log_kv!`]
+#[macro_export]
+macro_rules! warn_kv {
+    ($($arg:tt)+) => {
+        $crate::log_kv!($crate::log::Level::Warn, $($arg)+)
+    };
+}
+
+/// see [`log_kv!`]
+#[macro_export]
+macro_rules! error_kv {
+    ($($arg:tt)+) => {
+        $crate::log_kv!($crate::log::Level::Error, $($arg)+)
+    };
+}
+
+/// run the block and log its start and finish with the elapsed time,the value of the block is returned.
+/// the level is Info or set by `level:`,the target is the calling module,see [`ScopeTimer`](crate::timer::ScopeTimer)
+/// for example:
+/// ```rust
+/// let config = fast_log::timed!("load_config", { "config".to_string() });
+/// let sum = fast_log::timed!(level: log::Level::Debug, "sum", { 1 + 2 });
+/// ```
+#[macro_export]
+macro_rules! timed {
+    (level: $level:expr, $name:expr, $body:block) => {{
+        let _timer = $crate::timer::ScopeTimer::new(module_path!(), $level, $name);
+        $body
+    }};
+    ($name:expr, $body:block) => {
+        $crate::timed!(level: $crate::log::Level::Info, $name, $body)
+    };
+}
@@ -0,0 +1,142 @@
+use crate::LOGGER;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// the snapshot of the logger health,see [`metrics`]
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    /// records accepted into the channel
+    pub records_received: u64,
+    /// records written by all the appenders,counted after the last appender wrote them
+    pub records_written: u64,
+    /// records lost because the channel was full or closed,the pre init buffer was full
+    /// or the logger was stopped
+    pub records_dropped: u64,
+    /// records waiting in the channel
+    pub chan_len: usize,
+    /// formatted bytes of the written records
+    pub bytes_written: u64,
+    /// the duration of the last appender flush
+    pub last_flush_duration: Duration,
+}
+
+pub(crate) struct Counters {
+    pub records_received: AtomicU64,
+    pub records_written: AtomicU64,
+    pub records_dropped: AtomicU64,
+    pub bytes_written: AtomicU64,
+    pub last_flush_nanos: AtomicU64,
+}
+
+pub(crate) static COUNTERS: Counters = Counters {
+    records_received: AtomicU64::new(0),
+    records_written: AtomicU64::new(0),
+    records_dropped: AtomicU64::new(0),
+    bytes_written: AtomicU64::new(0),
+    last_flush_nanos: AtomicU64::new(0),
+};
+
+impl Counters {
+    pub fn add(counter: &AtomicU64, v: u64) {
+        counter.fetch_add(v, Ordering::Relaxed);
+    }
+}
+
+/// get the counters/gauges of the logger,for example export logger health to Prometheus
+pub fn metrics() -> Metrics {
+    Metrics {
+        records_received: COUNTERS.records_received.load(Ordering::Relaxed),
+        records_written: COUNTERS.records_written.load(Ordering::Relaxed),
+        records_dropped: COUNTERS.records_dropped.load(Ordering::Relaxed),
+        chan_len: LOGGER.chan.recv.len(),
+        bytes_written: COUNTERS.bytes_written.load(Ordering::Relaxed),
+        last_flush_duration: Duration::from_nanos(
+            COUNTERS.last_flush_nanos.load(Ordering::Relaxed),
+        ),
+    }
+}
+
+/// you need enable fast_log = { ... ,features=["metrics-prometheus"]}
+/// gather the metrics in the prometheus text exposition format
+#[cfg(feature = "metrics-prometheus")]
+pub fn gather() -> String {
+    let m = metrics();
+    let mut s = String::with_capacity(1024);
+    let items: [(&str, &str, &str, String); 6] = [
+        (
+            "fast_log_records_received_total",
+            "counter",
+            "records accepted into the channel",
+            m.records_received.to_string(),
+        ),
+        (
+            "fast_log_records_written_total",
+            "counter",
+            "records written by all the appenders",
+            m.records_written.to_string(),
+        ),
+        (
+            "fast_log_records_dropped_total",
+            "counter",
+            "records lost because the channel was full or closed,the pre init buffer was full or the logger was stopped",
+            m.records_dropped.to_string(),
+        ),
+        (
+            "fast_log_chan_len",
+            "gauge",
+            "records waiting in the channel",
+            m.chan_len.to_string(),
+        ),
+        (
+            "fast_log_bytes_written_total",
+            "counter",
+            "formatted bytes of the written records",
+            m.bytes_written.to_string(),
+        ),
+        (
+            "fast_log_last_flush_duration_seconds",
+            "gauge",
+            "the duration of the last appender flush",
+            m.last_flush_duration.as_secs_f64().to_string(),
+        ),
+    ];
+    for (name, kind, help, value) in items {
+        s.push_str(&format!(
+            "# HELP {} {}\n# TYPE {} {}\n{} {}\n",
+            name, help, name, kind, name, value
+        ));
+    }
+    s
+}
+
+/// you need enable fast_log = { ... ,features=["metrics-prometheus"]}
+/// spawn a tiny http exporter serving gather() on every request,for example serve_prometheus("0.0.0.0:9898").
+/// every connection is served on its own thread with a 5s read and write timeout,so an idle client never blocks the others
+#[cfg(feature = "metrics-prometheus")]
+pub fn serve_prometheus(addr: &str) -> std::io::Result<()> {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind(addr)?;
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            std::thread::spawn(move || {
+                stream.set_read_timeout(Some(Duration::from_secs(5)));
+                stream.set_write_timeout(Some(Duration::from_secs(5)));
+                //the request is ignored,every path returns the metrics
+                let mut buf = [0u8; 1024];
+                if stream.read(&mut buf).is_err() {
+                    return;
+                }
+                let body = gather();
+                stream.write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                    .as_bytes(),
+                );
+            });
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use crate::fast_log::{flush_timeout, LOGGER};
+use std::backtrace::Backtrace;
+use std::panic::PanicHookInfo;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// the longest wait of the panic hook for the appenders
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// the hook is installed,by `install_panic_hook` or `Config::panic_hook`
+static PANIC_HOOK: AtomicBool = AtomicBool::new(false);
+
+/// install a panic hook that logs the panic payload and backtrace at error level through the normal pipeline,
+/// and flushes so the panic isn't lost when the process dies
+pub fn install_panic_hook() {
+    install(false);
+}
+
+/// install a panic hook that logs panics of any thread(include the threads of dependencies)
+/// at error level,tagged with the thread name. the logger is flushed before the previous hook runs.
+/// the hook never blocks on the full channel(the record is dropped) and waits for the flush
+/// at most 3 seconds,it doesn't wait on the worker and the appender threads.
+/// if `abort` is true,the process is aborted after flushing,for services that prefer fail-fast behavior.
+/// the hook is installed once,the later calls(and `Config::panic_hook`) do nothing
+pub fn install(abort: bool) {
+    if PANIC_HOOK.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let prev = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log_panic(info);
+        flush_timeout(FLUSH_TIMEOUT);
+        prev(info);
+        if abort {
+            std::process::abort();
+        }
+    }));
+}
+
+fn log_panic(info: &PanicHookInfo) {
+    let thread = std::thread::current();
+    let thread_name = thread.name().unwrap_or("<unnamed>");
+    let payload = if let Some(s) = info.payload().downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = info.payload().downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    };
+    let location = info.location();
+    LOGGER.try_log(
+        &log::Record::builder()
+            .level(log::Level::Error)
+            .target("panic")
+            .module_path(Some("panic"))
+            .file(location.map(|v| v.file()))
+            .line(location.map(|v| v.line()))
+            .args(format_args!(
+                "thread '{}' panicked at '{}'\nstack backtrace:\n{}",
+                thread_name,
+                payload,
+                Backtrace::force_capture()
+            ))
+            .build(),
+    );
+}
@@ -0,0 +1,69 @@
+//! forward the output of a child process into the log,for supervisors and test harnesses.
+//! every line is a record of the target with the key-value `stream`(`stdout` or `stderr`),
+//! a [`RAW_TARGET`](crate::RAW_TARGET) target keeps the lines of a child already formatting its logs:
+//! ```rust
+//! use std::process::{Command, Stdio};
+//! let mut child = Command::new("cargo")
+//!     .arg("--version")
+//!     .stdout(Stdio::piped())
+//!     .stderr(Stdio::piped())
+//!     .spawn()
+//!     .unwrap();
+//! let readers = fast_log::pipe::capture(&mut child, log::Level::Info, "cargo");
+//! child.wait().unwrap();
+//! for reader in readers {
+//!     reader.join().unwrap();
+//! }
+//! ```
+use log::{Level, Record};
+use std::io::{BufRead, BufReader, Read};
+use std::process::Child;
+use std::thread::JoinHandle;
+
+/// take the piped stdout and stderr of the child and spawn a reader thread for each,
+/// the readers end with the output of the child,join them to wait for the last lines.
+/// the streams not set to `Stdio::piped()` are not captured
+pub fn capture(child: &mut Child, level: Level, target: &str) -> Vec<JoinHandle<()>> {
+    let mut readers = vec![];
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(spawn_reader(stdout, level, target, "stdout"));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(spawn_reader(stderr, level, target, "stderr"));
+    }
+    readers
+}
+
+pub(crate) fn spawn_reader<R: Read + Send + 'static>(
+    stream: R,
+    level: Level,
+    target: &str,
+    name: &'static str,
+) -> JoinHandle<()> {
+    let target = target.to_string();
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut buf = vec![];
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            if level > log::max_level() {
+                continue;
+            }
+            let line = String::from_utf8_lossy(&buf);
+            let line = line.trim_end_matches(['\n', '\r']);
+            log::logger().log(
+                &Record::builder()
+                    .level(level)
+                    .target(&target)
+                    .module_path(Some(&target))
+                    .args(format_args!("{}", line))
+                    .key_values(&[("stream", name)])
+                    .build(),
+            );
+        }
+    })
+}
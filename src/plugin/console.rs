@@ -1,17 +1,112 @@
 use crate::appender::{FastLogRecord, LogAppender};
+use crate::diagnostic;
+use crate::encoding;
+use crate::error::LogError;
+use log::LevelFilter;
+use std::borrow::Cow;
+use std::io::Write;
 
-/// only write append into console
-pub struct ConsoleAppender {}
+/// only write append into console,build it with `ConsoleAppender::new()` or `Default`
+#[derive(Default)]
+#[non_exhaustive]
+pub struct ConsoleAppender {
+    /// mute the console below a level during the local hours
+    quiet_hours: Option<QuietHours>,
+    /// mute the console entirely
+    muted: bool,
+}
+
+/// between the local hours [start_hour,end_hour),only records at or above `level` are written to console.
+/// for example QuietHours{start_hour:22,end_hour:6,level:LevelFilter::Warn}
+#[derive(Copy, Clone, Debug)]
+pub struct QuietHours {
+    pub start_hour: u8,
+    pub end_hour: u8,
+    /// LevelFilter::Off => mute entirely
+    pub level: LevelFilter,
+}
+
+impl QuietHours {
+    pub fn contains(&self, hour: u8) -> bool {
+        if self.start_hour <= self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+impl ConsoleAppender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// suppress console below `level` between the local hours [start_hour,end_hour),
+    /// the file appenders continue receiving everything
+    pub fn quiet_hours(mut self, start_hour: u8, end_hour: u8, level: LevelFilter) -> Self {
+        self.quiet_hours = Some(QuietHours {
+            start_hour,
+            end_hour,
+            level,
+        });
+        self
+    }
+
+    /// mute the console entirely when running in CI(the env `CI` is set)
+    pub fn mute_in_ci(mut self) -> Self {
+        if std::env::var_os("CI").is_some() {
+            self.muted = true;
+        }
+        self
+    }
+
+    fn quiet_level(&self) -> LevelFilter {
+        if self.muted {
+            return LevelFilter::Off;
+        }
+        if let Some(quiet) = &self.quiet_hours {
+            let hour = fastdate::DateTime::from(crate::now())
+                .set_offset(crate::offset_sec())
+                .hour;
+            if quiet.contains(hour) {
+                return quiet.level;
+            }
+        }
+        LevelFilter::Trace
+    }
+}
 
 impl LogAppender for ConsoleAppender {
     fn do_logs(&self, records: &[FastLogRecord]) {
+        if let Err(e) = self.try_do_logs(records) {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
         if records.len() == 0 {
-            return;
+            return Ok(());
         }
+        let level = self.quiet_level();
         let mut buffer = String::with_capacity(records.len() * 10);
         for x in records {
+            if x.level > level {
+                continue;
+            }
             buffer.push_str(&x.formated);
         }
-        print!("{}", buffer);
+        let bytes = encoding::encode(&buffer);
+        #[cfg(all(feature = "capture", unix))]
+        if let Some(mut stdout) = crate::capture::original_stdout() {
+            return stdout.write_all(&bytes).map_err(LogError::from);
+        }
+        match bytes {
+            //print! is captured by the test harness
+            Cow::Borrowed(_) => {
+                print!("{}", buffer);
+                Ok(())
+            }
+            Cow::Owned(bytes) => std::io::stdout().write_all(&bytes).map_err(LogError::from),
+        }
     }
 }
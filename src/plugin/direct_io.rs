@@ -0,0 +1,149 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::{FileExt, OpenOptionsExt};
+
+/// the alignment of the O_DIRECT buffer,the offset and the length of the writes
+pub const ALIGN: usize = 4096;
+
+/// a log file opened with O_DIRECT(and O_DSYNC),bypassing the page cache.
+/// the records are buffered in an aligned buffer and written in whole blocks,
+/// the partial last block is written zero padded on flush and rewritten with the next records.
+/// the padding is truncated on sync,[`DirectFile::trim`] and drop,the padding left by a crash is dropped on open
+pub struct DirectFile {
+    file: File,
+    buf: Vec<u8>,
+    /// the aligned start of the block buffer in `buf`
+    start: usize,
+    /// the bytes in the block buffer
+    len: usize,
+    /// the capacity of the block buffer,a multiple of ALIGN
+    cap: usize,
+    /// the file offset of the block buffer
+    pos: u64,
+    /// the file ends with the zero padding of the partial last block
+    padded: bool,
+}
+
+impl DirectFile {
+    pub fn open(path: &str, dsync: bool, buffer_size: usize) -> std::io::Result<Self> {
+        let mut flags = libc::O_DIRECT;
+        if dsync {
+            flags |= libc::O_DSYNC;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .custom_flags(flags)
+            .open(path)?;
+        let cap = buffer_size.max(ALIGN).div_ceil(ALIGN) * ALIGN;
+        let buf = vec![0u8; cap + ALIGN];
+        let start = buf.as_ptr().align_offset(ALIGN);
+        let mut direct = Self {
+            file,
+            buf,
+            start,
+            len: 0,
+            cap,
+            pos: 0,
+            padded: false,
+        };
+        //continue the last block of the existing file
+        let file_len = direct.file.metadata()?.len();
+        direct.pos = file_len.saturating_sub(1) / ALIGN as u64 * ALIGN as u64;
+        let tail = (file_len - direct.pos) as usize;
+        if tail > 0 {
+            let mut reader = File::open(path)?;
+            reader.seek(SeekFrom::Start(direct.pos))?;
+            reader.read_exact(&mut direct.block()[..tail])?;
+            //the zero padding of a file not trimmed(the process crashed)
+            direct.len = direct.block()[..tail]
+                .iter()
+                .rposition(|v| *v != 0)
+                .map_or(0, |v| v + 1);
+            direct.padded = direct.len < tail;
+        }
+        Ok(direct)
+    }
+
+    fn block(&mut self) -> &mut [u8] {
+        &mut self.buf[self.start..self.start + self.cap]
+    }
+
+    /// write the buffered records and fsync
+    pub fn sync_data(&mut self) -> std::io::Result<()> {
+        self.trim()?;
+        self.file.sync_data()
+    }
+
+    /// write the buffered records and truncate the zero padding of the last block,
+    /// for example before the file is renamed
+    pub fn trim(&mut self) -> std::io::Result<()> {
+        self.flush()?;
+        if self.padded {
+            self.file.set_len(self.pos + self.len as u64)?;
+            self.padded = false;
+        }
+        Ok(())
+    }
+
+    pub fn get_ref(&self) -> &File {
+        &self.file
+    }
+
+    /// empty the file
+    pub fn truncate(&mut self) -> std::io::Result<()> {
+        self.file.set_len(0)?;
+        self.pos = 0;
+        self.len = 0;
+        self.padded = false;
+        Ok(())
+    }
+}
+
+impl Drop for DirectFile {
+    fn drop(&mut self) {
+        let _ = self.trim();
+    }
+}
+
+impl Write for DirectFile {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        let n = data.len().min(self.cap - self.len);
+        let len = self.len;
+        self.block()[len..len + n].copy_from_slice(&data[..n]);
+        self.len += n;
+        if self.len == self.cap {
+            let pos = self.pos;
+            let cap = self.cap;
+            self.file
+                .write_all_at(&self.buf[self.start..self.start + cap], pos)?;
+            self.pos += cap as u64;
+            self.len = 0;
+            self.padded = false;
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.len == 0 {
+            return Ok(());
+        }
+        let len = self.len;
+        let padded = len.div_ceil(ALIGN) * ALIGN;
+        self.block()[len..padded].fill(0);
+        let pos = self.pos;
+        self.file
+            .write_all_at(&self.buf[self.start..self.start + padded], pos)?;
+        self.padded = padded > len;
+        //keep only the partial last block,it is rewritten with the next records
+        let full = len / ALIGN * ALIGN;
+        if full > 0 {
+            let start = self.start;
+            self.buf.copy_within(start + full..start + len, start);
+            self.pos += full as u64;
+            self.len -= full;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use crate::error::LogError;
+use crate::plugin::file_split::Packer;
+use crate::plugin::packer::{finish_staging, staging_path};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::stream::{DecryptorBE32, EncryptorBE32};
+use aes_gcm::aead::{KeyInit, OsRng};
+use aes_gcm::Aes256Gcm;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::sync::Arc;
+
+/// the header of the encrypted file,followed by the 7 bytes nonce prefix
+const MAGIC: &[u8; 8] = b"FLENC1\0\0";
+/// the plaintext size of one chunk
+const CHUNK: usize = 64 * 1024;
+/// the size of the authentication tag of each chunk
+const TAG: usize = 16;
+
+/// you need enable fast_log = { ... ,features=["encrypt"]}
+/// pack the rolled file with the inner packer,then encrypt the archive with AES-256-GCM(in 64KB chunks)
+/// to `{archive}.enc` and remove the plaintext archive,see [`decrypt_log`] to restore it
+/// ```rust
+/// use fast_log::plugin::encrypt::EncryptPacker;
+/// use fast_log::plugin::packer::LogPacker;
+/// let packer = EncryptPacker::new(LogPacker {}, [7u8; 32]);
+/// ```
+pub struct EncryptPacker {
+    inner: Box<dyn Packer>,
+    key: [u8; 32],
+}
+
+impl EncryptPacker {
+    pub fn new<P: Packer + 'static>(inner: P, key: [u8; 32]) -> Self {
+        Self {
+            inner: Box::new(inner),
+            key,
+        }
+    }
+}
+
+impl Packer for EncryptPacker {
+    fn pack_name(&self) -> &'static str {
+        self.inner.pack_name()
+    }
+
+    fn do_pack(&self, log_file: File, log_file_path: &str) -> Result<bool, LogError> {
+        let remove = self.inner.do_pack(log_file, log_file_path)?;
+        let archive = self.inner.archive_path(log_file_path);
+        let encrypted = format!("{}.enc", archive);
+        encrypt_file(&self.key, &archive, &staging_path(&encrypted)).map_err(|e| self.error(e))?;
+        finish_staging(self.pack_name(), &encrypted)?;
+        if archive == log_file_path {
+            //the log file is the archive,removed by the caller
+            return Ok(true);
+        }
+        std::fs::remove_file(&archive)?;
+        Ok(remove)
+    }
+
+    fn retry(&self) -> i32 {
+        self.inner.retry()
+    }
+
+    fn archive_path(&self, log_file_path: &str) -> String {
+        format!("{}.enc", self.inner.archive_path(log_file_path))
+    }
+}
+
+impl EncryptPacker {
+    fn error(&self, e: LogError) -> LogError {
+        match e {
+            LogError::Io(e) => LogError::Packer(self.pack_name().to_string(), Arc::new(e)),
+            e => e,
+        }
+    }
+}
+
+fn encrypt_file(key: &[u8; 32], from: &str, to: &str) -> Result<(), LogError> {
+    let mut prefix = [0u8; 7];
+    OsRng.fill_bytes(&mut prefix);
+    let mut encryptor = EncryptorBE32::from_aead(Aes256Gcm::new(key.into()), &prefix.into());
+    let mut reader = BufReader::new(File::open(from)?);
+    let mut writer = BufWriter::new(File::create(to)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&prefix)?;
+    let mut chunk = vec![0u8; CHUNK];
+    let mut len = read_full(&mut reader, &mut chunk)?;
+    loop {
+        //read ahead to find the last chunk
+        let mut next = vec![0u8; CHUNK];
+        let next_len = read_full(&mut reader, &mut next)?;
+        if next_len == 0 {
+            let data = encryptor
+                .encrypt_last(&chunk[..len])
+                .map_err(|_| LogError::from("[fast_log] encrypt fail"))?;
+            writer.write_all(&data)?;
+            break;
+        }
+        let data = encryptor
+            .encrypt_next(&chunk[..len])
+            .map_err(|_| LogError::from("[fast_log] encrypt fail"))?;
+        writer.write_all(&data)?;
+        chunk = next;
+        len = next_len;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// decrypt the file encrypted by [`EncryptPacker`] with the same key,
+/// fails if the file is modified,truncated or the key is wrong
+pub fn decrypt_log(from: &str, to: &str, key: &[u8; 32]) -> Result<(), LogError> {
+    let mut reader = BufReader::new(File::open(from)?);
+    let mut header = [0u8; 15];
+    reader.read_exact(&mut header)?;
+    if &header[..8] != MAGIC {
+        return Err(LogError::Parse(format!("{} is not an encrypted log", from)));
+    }
+    let mut decryptor = DecryptorBE32::from_aead(Aes256Gcm::new(key.into()), header[8..].into());
+    let mut writer = BufWriter::new(File::create(to)?);
+    let fail = || LogError::Parse(format!("decrypt {} fail,wrong key or corrupted", from));
+    let mut chunk = vec![0u8; CHUNK + TAG];
+    let mut len = read_full(&mut reader, &mut chunk)?;
+    loop {
+        let mut next = vec![0u8; CHUNK + TAG];
+        let next_len = read_full(&mut reader, &mut next)?;
+        if next_len == 0 {
+            writer.write_all(&decryptor.decrypt_last(&chunk[..len]).map_err(|_| fail())?)?;
+            break;
+        }
+        writer.write_all(&decryptor.decrypt_next(&chunk[..len]).map_err(|_| fail())?)?;
+        chunk = next;
+        len = next_len;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// read until `buf` is full or the end of file
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    Ok(len)
+}
@@ -0,0 +1,131 @@
+use crate::appender::{FastLogRecord, LogAppender};
+use crate::diagnostic;
+use crate::error::LogError;
+use crate::runtime::Instant;
+use log::Level;
+use std::cell::Cell;
+use std::time::Duration;
+
+/// write to the primary appender(for example a network appender),
+/// if the primary fails `max_failures` times in a row,records are routed to the secondary appender(for example a local file),
+/// the primary is retried every `retry_interval` and records fail back to it once it recovers
+pub struct FallbackAppender {
+    primary: Box<dyn LogAppender>,
+    secondary: Box<dyn LogAppender>,
+    pub max_failures: u32,
+    pub retry_interval: Duration,
+    failures: Cell<u32>,
+    last_retry: Cell<Option<Instant>>,
+}
+
+impl FallbackAppender {
+    pub fn new(primary: Box<dyn LogAppender>, secondary: Box<dyn LogAppender>) -> Self {
+        Self {
+            primary,
+            secondary,
+            max_failures: 3,
+            retry_interval: Duration::from_secs(10),
+            failures: Cell::new(0),
+            last_retry: Cell::new(None),
+        }
+    }
+
+    /// consecutive failures of the primary before routing to the secondary,default is 3
+    pub fn max_failures(mut self, max_failures: u32) -> Self {
+        self.max_failures = max_failures;
+        self
+    }
+
+    /// the interval of retrying the failed primary,default is 10s
+    pub fn retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// is the records routed to the secondary appender
+    pub fn is_fallback(&self) -> bool {
+        self.failures.get() >= self.max_failures
+    }
+
+    fn try_primary(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
+        let was_fallback = self.is_fallback();
+        match self.primary.try_do_logs(records) {
+            Ok(_) => {
+                self.failures.set(0);
+                if was_fallback {
+                    diagnostic::report(Level::Info, "[fast_log] primary appender recovered");
+                }
+                Ok(())
+            }
+            Err(e) => {
+                self.failures.set(self.failures.get().saturating_add(1));
+                if !was_fallback && self.is_fallback() {
+                    diagnostic::report(
+                        Level::Warn,
+                        "[fast_log] primary appender failed,fall back to the secondary appender",
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl LogAppender for FallbackAppender {
+    fn open(&self) {
+        self.primary.open();
+        self.secondary.open();
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "FallbackAppender({} -> {})",
+            self.primary.describe(),
+            self.secondary.describe()
+        )
+    }
+
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        if let Err(e) = self.try_do_logs(records) {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
+        if self.is_fallback() {
+            let now = Instant::now();
+            let retry = match self.last_retry.get() {
+                None => true,
+                Some(last) => now.duration_since(last) >= self.retry_interval,
+            };
+            if retry {
+                self.last_retry.set(Some(now));
+                if self.try_primary(records).is_ok() {
+                    return Ok(());
+                }
+            }
+            return self.secondary.try_do_logs(records);
+        }
+        if let Err(e) = self.try_primary(records) {
+            //the failed records are not lost
+            diagnostic::error(e);
+            return self.secondary.try_do_logs(records);
+        }
+        Ok(())
+    }
+
+    fn flush(&self) {
+        self.primary.flush();
+        self.secondary.flush();
+    }
+
+    fn sync(&self) {
+        self.primary.sync();
+        self.secondary.sync();
+    }
+
+    fn close(&self) {
+        self.primary.close();
+        self.secondary.close();
+    }
+}
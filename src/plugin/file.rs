@@ -1,11 +1,110 @@
 use crate::appender::{FastLogRecord, LogAppender};
+use crate::diagnostic;
+use crate::encoding;
+use crate::error::LogError;
+#[cfg(target_os = "linux")]
+use crate::plugin::direct_io::DirectFile;
 use std::cell::RefCell;
 use std::fs::{File, OpenOptions};
-use std::io::Write;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::time::Duration;
+
+/// the opened log file,shared by the file appenders
+pub(crate) enum LogFile {
+    Buffered(BufWriter<File>),
+    #[cfg(target_os = "linux")]
+    Direct(DirectFile),
+}
+
+impl LogFile {
+    /// open with O_DIRECT if `direct` is Some(dsync),else with `options` buffered by `buffer_size`
+    pub(crate) fn open(
+        path: &str,
+        options: &OpenOptions,
+        #[cfg_attr(not(target_os = "linux"), allow(unused_variables))] direct: Option<bool>,
+        buffer_size: usize,
+    ) -> std::io::Result<LogFile> {
+        #[cfg(target_os = "linux")]
+        if let Some(dsync) = direct {
+            return Ok(LogFile::Direct(DirectFile::open(path, dsync, buffer_size)?));
+        }
+        Ok(LogFile::Buffered(BufWriter::with_capacity(
+            buffer_size,
+            options.open(path)?,
+        )))
+    }
+
+    pub(crate) fn get_ref(&self) -> &File {
+        match self {
+            LogFile::Buffered(file) => file.get_ref(),
+            #[cfg(target_os = "linux")]
+            LogFile::Direct(file) => file.get_ref(),
+        }
+    }
+
+    /// write the buffered records,and truncate the zero padding of O_DIRECT,
+    /// for example before the file is renamed
+    pub(crate) fn trim(&mut self) -> std::io::Result<()> {
+        match self {
+            LogFile::Buffered(file) => file.flush(),
+            #[cfg(target_os = "linux")]
+            LogFile::Direct(file) => file.trim(),
+        }
+    }
+
+    /// empty the file and write from the start
+    pub(crate) fn truncate(&mut self) -> std::io::Result<()> {
+        match self {
+            LogFile::Buffered(file) => {
+                file.flush()?;
+                file.get_ref().set_len(0)?;
+                file.get_mut().seek(SeekFrom::Start(0)).map(|_| ())
+            }
+            #[cfg(target_os = "linux")]
+            LogFile::Direct(file) => file.truncate(),
+        }
+    }
+
+    pub(crate) fn sync_data(&mut self) -> std::io::Result<()> {
+        match self {
+            LogFile::Buffered(file) => {
+                file.flush()?;
+                file.get_ref().sync_data()
+            }
+            #[cfg(target_os = "linux")]
+            LogFile::Direct(file) => file.sync_data(),
+        }
+    }
+}
+
+impl Write for LogFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            LogFile::Buffered(file) => file.write(buf),
+            #[cfg(target_os = "linux")]
+            LogFile::Direct(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            LogFile::Buffered(file) => file.flush(),
+            #[cfg(target_os = "linux")]
+            LogFile::Direct(file) => file.flush(),
+        }
+    }
+}
 
 /// only write append into file
 pub struct FileAppender {
-    file: RefCell<File>,
+    file: RefCell<Option<LogFile>>,
+    path: String,
+    /// the capacity of the write buffer,0 is unbuffered
+    buffer_size: usize,
+    /// Some(dsync) => open with O_DIRECT,and O_DSYNC if dsync is true
+    direct: Option<bool>,
+    /// close the file after a period of inactivity,and reopen it on demand
+    idle_close: Option<Duration>,
 }
 
 impl FileAppender {
@@ -15,29 +114,150 @@ impl FileAppender {
             let path = &log_file_path[0..right];
             std::fs::create_dir_all(path);
         }
-        Self {
-            file: RefCell::new(
-                OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(&log_file_path)
-                    .unwrap(),
-            ),
+        let mut s = Self {
+            file: RefCell::new(None),
+            path: log_file_path,
+            buffer_size: 0,
+            direct: None,
+            idle_close: None,
+        };
+        *s.file.get_mut() = Some(s.open_file().unwrap());
+        s
+    }
+
+    /// close the file after `idle` inactivity and reopen it on demand,
+    /// so long-running processes with many rare log files don't hold file descriptors indefinitely
+    pub fn idle_close(mut self, idle: Duration) -> Self {
+        self.idle_close = Some(idle);
+        self
+    }
+
+    /// buffer the writes up to `size` bytes(for example 256*1024) to save write syscalls of chatty services,
+    /// the buffer is written on flush(`fast_log::flush`,`Config::flush_level`) and exit.
+    /// default is 0(every batch is written at once)
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        self.buffer_size = size;
+        self.reopen();
+        self
+    }
+
+    /// open the file with O_DIRECT to bypass the page cache(for example a dedicated log volume
+    /// on a database host),and with O_DSYNC if `dsync` is true.
+    /// the writes are aligned to 4KB internally,the partial last block is rewritten until it is full.
+    /// if the file system doesn't support O_DIRECT(for example tmpfs),the error is reported and
+    /// the file is kept in the buffered mode
+    #[cfg(target_os = "linux")]
+    pub fn direct_io(mut self, dsync: bool) -> Self {
+        self.direct = Some(dsync);
+        if !self.reopen() {
+            self.direct = None;
+            self.reopen();
+        }
+        self
+    }
+
+    /// reopen the file with the current settings,return false on failure
+    fn reopen(&mut self) -> bool {
+        if let Some(mut file) = self.file.get_mut().take() {
+            if let Err(e) = file.flush() {
+                diagnostic::error(LogError::from(e));
+            }
         }
+        match self.open_file() {
+            Ok(file) => {
+                *self.file.get_mut() = Some(file);
+                true
+            }
+            Err(e) => {
+                diagnostic::error(LogError::from(e));
+                false
+            }
+        }
+    }
+
+    fn open_file(&self) -> std::io::Result<LogFile> {
+        let path = &self.path;
+        LogFile::open(
+            path,
+            OpenOptions::new().create(true).append(true),
+            self.direct,
+            self.buffer_size,
+        )
+        .map_err(|e| std::io::Error::new(e.kind(), format!("open log file {} fail:{}", path, e)))
+    }
+
+    fn with_file<R>(
+        &self,
+        f: impl FnOnce(&mut LogFile) -> std::io::Result<R>,
+    ) -> Result<R, LogError> {
+        let mut file = self.file.borrow_mut();
+        if file.is_none() {
+            *file = Some(self.open_file()?);
+        }
+        f(file.as_mut().unwrap()).map_err(LogError::from)
     }
 }
 
 impl LogAppender for FileAppender {
     fn do_logs(&self, records: &[FastLogRecord]) {
-        let mut log_file = self.file.borrow_mut();
+        if let Err(e) = self.try_do_logs(records) {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
         let mut buf = String::new();
         for x in records {
             buf.push_str(&x.formated);
         }
-        log_file.write_all(buf.as_bytes());
+        self.with_file(|f| {
+            f.write_all(&encoding::encode(&buf))?;
+            //unbuffered,write the partial last block of O_DIRECT at once
+            match f {
+                #[cfg(target_os = "linux")]
+                LogFile::Direct(f) if self.buffer_size == 0 => f.flush(),
+                _ => Ok(()),
+            }
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("FileAppender({})", self.path)
     }
 
     fn flush(&self) {
-        self.file.borrow_mut().flush();
+        if let Some(file) = self.file.borrow_mut().as_mut() {
+            if let Err(e) = file.flush() {
+                diagnostic::error(LogError::from(e));
+            }
+        }
+    }
+
+    fn sync(&self) {
+        if let Err(e) = self.try_sync() {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_sync(&self) -> Result<(), LogError> {
+        match self.file.borrow_mut().as_mut() {
+            Some(file) => file.sync_data().map_err(LogError::from),
+            None => Ok(()),
+        }
+    }
+
+    fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_close
+    }
+
+    fn on_idle(&self) {
+        if self.file.borrow().is_some() {
+            self.flush();
+            self.file.borrow_mut().take();
+        }
+    }
+
+    fn close(&self) {
+        self.flush();
     }
 }
@@ -1,5 +1,6 @@
 use crate::appender::{FastLogRecord, LogAppender};
 use crate::consts::LogSize;
+use crate::error::LogError;
 use crate::plugin::file_split::{FileSplitAppender, RollingType};
 use crate::plugin::packer::LogPacker;
 
@@ -19,6 +20,13 @@ impl FileLoopAppender {
             ),
         }
     }
+
+    /// see [`FileSplitAppender::direct_io`]
+    #[cfg(target_os = "linux")]
+    pub fn direct_io(mut self, dsync: bool) -> Self {
+        self.file = self.file.direct_io(dsync);
+        self
+    }
 }
 
 impl LogAppender for FileLoopAppender {
@@ -29,4 +37,22 @@ impl LogAppender for FileLoopAppender {
     fn flush(&self) {
         self.file.flush();
     }
+
+    fn describe(&self) -> String {
+        self.file
+            .describe()
+            .replacen("FileSplitAppender", "FileLoopAppender", 1)
+    }
+
+    fn sync(&self) {
+        self.file.sync();
+    }
+
+    fn try_sync(&self) -> Result<(), LogError> {
+        self.file.try_sync()
+    }
+
+    fn close(&self) {
+        self.file.close();
+    }
 }
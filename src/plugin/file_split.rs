@@ -1,13 +1,23 @@
 use std::cell::RefCell;
 use std::fs::{DirEntry, File, OpenOptions};
-use std::io::{Seek, SeekFrom, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 
 use crate::appender::{Command, FastLogRecord, LogAppender};
 use crate::consts::LogSize;
+use crate::diagnostic;
+use crate::encoding;
 use crate::error::LogError;
-use crate::{chan, Receiver, Sender};
+use crate::plugin::file::LogFile;
+use crate::plugin::packer::staging_path;
+use crate::{chan, Sender};
+use log::Level;
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::path::PathBuf;
 use std::str::FromStr;
-use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
 
 /// .zip or .lz4 or any one packer
 pub trait Packer: Send {
@@ -18,9 +28,34 @@ pub trait Packer: Send {
     fn retry(&self) -> i32 {
         return 0;
     }
+    /// the path of the archive made by do_pack,default is the log file path with the pack_name extension
+    fn archive_path(&self, log_file_path: &str) -> String {
+        log_file_path.replace(".log", &format!(".{}", self.pack_name()))
+    }
+}
+
+/// the rolled file of `Config::on_rotate`
+#[derive(Clone, Debug)]
+pub struct RotateEvent {
+    /// the log file being written,for example logs/temp.log
+    pub old_path: PathBuf,
+    /// the rolled log file,for example logs/temp2023-01-01 00:00:00.000000000.log
+    pub new_path: PathBuf,
+    /// the archive made by the packer(the rolled log file for LogPacker)
+    pub archive_path: PathBuf,
+    pub archive_size: u64,
+}
+
+static ON_ROTATE: RwLock<Option<fn(&RotateEvent)>> = RwLock::new(None);
+
+/// set the callback invoked after a file is rolled and packed
+pub fn set_on_rotate(f: Option<fn(&RotateEvent)>) {
+    *ON_ROTATE.write().unwrap_or_else(|e| e.into_inner()) = f;
 }
 
 /// split log file allow compress log
+/// the active file is always `temp.log`(or the file name of the path),on roll it is renamed to the timestamped name,
+/// and the packer writes the archive to `{archive}.tmp` then renames it,so no half-written archive is visible
 pub struct FileSplitAppender {
     cell: RefCell<FileSplitAppenderData>,
 }
@@ -30,6 +65,63 @@ pub struct LogPack {
     pub dir: String,
     pub rolling: RollingType,
     pub new_log_name: String,
+    /// the records of the pack
+    pub stats: SplitStats,
+    /// write the pack to the manifest.json of the dir
+    pub manifest: bool,
+}
+
+/// the record count and the time range of a log file
+#[derive(Clone, Debug, Default)]
+pub struct SplitStats {
+    pub records: u64,
+    /// None if the file is written before the process starts
+    pub first: Option<SystemTime>,
+    pub last: Option<SystemTime>,
+}
+
+impl SplitStats {
+    fn add(&mut self, now: SystemTime) {
+        self.records += 1;
+        if self.first.is_none() && self.records == 1 {
+            self.first = Some(now);
+        }
+        self.last = Some(now);
+    }
+
+    fn merge(&mut self, other: SplitStats) {
+        if self.records == 0 {
+            self.first = other.first;
+        }
+        self.records += other.records;
+        if other.last.is_some() {
+            self.last = other.last;
+        }
+    }
+}
+
+/// the split trigger of FileSplitAppender
+pub enum SplitBy {
+    /// split when the file reaches the size
+    Size(LogSize),
+    /// split when the file reaches the records(lines),each file has the same records
+    Records(u64),
+}
+
+impl From<LogSize> for SplitBy {
+    fn from(v: LogSize) -> Self {
+        SplitBy::Size(v)
+    }
+}
+
+impl SplitBy {
+    /// is the trigger zero
+    pub fn is_zero(&self) -> bool {
+        match self {
+            SplitBy::Size(size) => size.get_len() == 0,
+            SplitBy::Records(records) => *records == 0,
+        }
+    }
 }
 
 ///rolling keep type
@@ -47,7 +139,10 @@ pub enum RollingType {
 }
 
 impl RollingType {
+    /// the packs of the temp file,the newest first.
+    /// the packs of the earlier runs of a [`per_process_path`] are matched by the stable name
     fn read_paths(&self, dir: &str, temp_name: &str) -> Vec<DirEntry> {
+        let base = stable_name(temp_name);
         let paths = std::fs::read_dir(dir);
         if let Ok(paths) = paths {
             let mut paths_vec = vec![];
@@ -55,11 +150,12 @@ impl RollingType {
                 match path {
                     Ok(path) => {
                         if let Some(v) = path.file_name().to_str() {
-                            //filter temp.log and not start with temp
-                            if (v.ends_with(".log")
-                                && v.trim_end_matches(".log").ends_with(temp_name))
-                                || !v.starts_with(temp_name)
-                            {
+                            //filter the temp files,the staging files,the lock file and not start with temp
+                            let is_pack = match time_part(v, base) {
+                                Some(time) => !time.starts_with('.'),
+                                None => false,
+                            };
+                            if !is_pack || v.ends_with(".tmp") || v.ends_with(".lock") {
                                 continue;
                             }
                         }
@@ -68,12 +164,50 @@ impl RollingType {
                     _ => {}
                 }
             }
-            paths_vec.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+            //by the time of the pack,the names of the runs differ before it
+            paths_vec.sort_by(|a, b| {
+                let (a, b) = (a.file_name(), b.file_name());
+                let a = a.to_str().and_then(|v| time_part(v, base));
+                let b = b.to_str().and_then(|v| time_part(v, base));
+                b.cmp(&a)
+            });
             return paths_vec;
         }
         return vec![];
     }
 
+    /// the temp files of the earlier runs of a [`per_process_path`],not written for `duration`
+    fn stale_temps(&self, dir: &str, temp_name: &str, duration: Duration) -> Vec<DirEntry> {
+        let base = stable_name(temp_name);
+        if base == temp_name {
+            return vec![];
+        }
+        let own = format!("{}.log", temp_name);
+        let mut temps = vec![];
+        if let Ok(paths) = std::fs::read_dir(dir) {
+            for path in paths.flatten() {
+                let name = path.file_name();
+                let name = name.to_str().unwrap_or_default();
+                //the temp file of this run and of the appender without the suffix are kept
+                if name == own || name == format!("{}.log", base) {
+                    continue;
+                }
+                if time_part(name, base) != Some(".log") {
+                    continue;
+                }
+                let idle = path
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|v| crate::now().duration_since(v).ok());
+                if idle.is_some_and(|v| v > duration) {
+                    temps.push(path);
+                }
+            }
+        }
+        temps
+    }
+
     pub fn do_rolling(&self, temp_name: &str, dir: &str) {
         match self {
             RollingType::KeepNum(n) => {
@@ -86,8 +220,11 @@ impl RollingType {
                 }
             }
             RollingType::KeepTime(duration) => {
+                for item in self.stale_temps(dir, temp_name, *duration) {
+                    std::fs::remove_file(item.path());
+                }
                 let paths_vec = self.read_paths(dir, temp_name);
-                let now = fastdate::DateTime::now();
+                let now = fastdate::DateTime::from(crate::now()).set_offset(crate::offset_sec());
                 for index in 0..paths_vec.len() {
                     let item = &paths_vec[index];
                     let file_name = item.file_name();
@@ -104,8 +241,8 @@ impl RollingType {
     }
 
     fn file_name_parse_time(&self, name: &str, temp_name: &str) -> Option<fastdate::DateTime> {
-        if name.starts_with(temp_name) {
-            let mut time_str = name.replace(temp_name, "");
+        if let Some(time_str) = time_part(name, stable_name(temp_name)) {
+            let mut time_str = time_str.to_string();
             if let Some(v) = time_str.find(".") {
                 time_str = time_str[0..v].to_string();
             }
@@ -118,54 +255,180 @@ impl RollingType {
     }
 }
 
+/// the name without the suffix of [`per_process_path`],"temp-4242-20231001120000" => "temp"
+fn stable_name(temp_name: &str) -> &str {
+    if let Some((rest, _)) = temp_name.rsplit_once('-') {
+        if let Some((base, _)) = rest.rsplit_once('-') {
+            if process_suffix(&temp_name[base.len()..]) == Some("") {
+                return base;
+            }
+        }
+    }
+    temp_name
+}
+
+/// the rest of `name` after the suffix of [`per_process_path`](`-{pid}-{start}`),None without it
+fn process_suffix(name: &str) -> Option<&str> {
+    let is_digits = |v: &str| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit());
+    let rest = name.strip_prefix('-')?;
+    let (pid, rest) = rest.split_once('-')?;
+    if !is_digits(pid) || rest.len() < 14 || !is_digits(&rest[..14]) {
+        return None;
+    }
+    Some(&rest[14..])
+}
+
+/// the time and the extension of a file of the temp file `base`,
+/// for example "2023-10-01 12:00:00.000000000.zip" of a pack and ".log" of a temp file,
+/// after the suffix of [`per_process_path`] if any. None if it is not a file of `base`
+fn time_part<'a>(name: &'a str, base: &str) -> Option<&'a str> {
+    let rest = name.strip_prefix(base)?;
+    Some(process_suffix(rest).unwrap_or(rest))
+}
+
 /// split log file allow pack compress log
 /// Memory space swop running time , reduces the number of repeated queries for IO
 pub struct FileSplitAppenderData {
     max_split_bytes: usize,
+    max_split_records: u64,
     dir_path: String,
-    file: File,
-    sender: Sender<LogPack>,
+    file: LogFile,
+    saver: Arc<Saver>,
     rolling_type: RollingType,
     //cache data
     temp_bytes: usize,
     temp_name: String,
+    stats: SplitStats,
+    manifest: bool,
+    /// the advisory lock file of the processes sharing the dir,see [`FileSplitAppender::shared`]
+    lock: Option<File>,
+    pack_name: &'static str,
+    /// the temp file is renamed to a pack and can't be reopened,the records are not written
+    stopped: bool,
+    /// Some(dsync) => the temp file is opened with O_DIRECT,see [`FileSplitAppender::direct_io`]
+    direct: Option<bool>,
 }
 
 impl FileSplitAppenderData {
     /// send data make an pack,and truncate data when finish.
+    /// the temp file is renamed to the pack and a new temp file is created,so the appender thread
+    /// never waits for copying a large file,the packer compresses the pack on a pack worker,see [`set_pack_workers`]
     pub fn send_pack(&mut self) {
+        //the zero padding of O_DIRECT is not rolled
+        if let Err(e) = self.file.trim() {
+            diagnostic::error(LogError::from(e));
+        }
         let first_file_path = format!("{}{}.log", self.dir_path, &self.temp_name);
         let new_log_name = format!(
             "{}{}{}.log",
             self.dir_path,
             &self.temp_name,
-            format!("{:29}", fastdate::DateTime::now())
+            format!(
+                "{:29}",
+                fastdate::DateTime::from(crate::now()).set_offset(crate::offset_sec())
+            )
         );
-        std::fs::copy(&first_file_path, &new_log_name);
-        self.sender.send(LogPack {
+        if !self.rename_pack(&first_file_path, &new_log_name) && !self.stopped {
+            //for example windows can't rename the opened file,copy to the staging file then rename
+            let staging = staging_path(&new_log_name);
+            if let Err(e) = std::fs::copy(&first_file_path, &staging)
+                .and_then(|_| std::fs::rename(&staging, &new_log_name))
+            {
+                diagnostic::error(LogError::from(format!(
+                    "[fast_log] copy log file {} fail:{}",
+                    new_log_name, e
+                )));
+            }
+            self.truncate();
+        }
+        diagnostic::report(
+            Level::Info,
+            &format!("[fast_log] rotate log file: {}", new_log_name),
+        );
+        self.saver.push(LogPack {
             dir: self.dir_path.clone(),
             rolling: self.rolling_type.clone(),
             new_log_name: new_log_name,
+            stats: std::mem::take(&mut self.stats),
+            manifest: self.manifest,
         });
-        self.truncate();
+    }
+
+    /// rename the temp file to the pack and continue with a new temp file,return false on failure.
+    /// if the new temp file can't be opened and the pack can't be renamed back,the appender is stopped,
+    /// so the records are not written into the pack being packed or removed
+    fn rename_pack(&mut self, first_file_path: &str, new_log_name: &str) -> bool {
+        if std::fs::rename(first_file_path, new_log_name).is_err() {
+            return false;
+        }
+        match open_temp(first_file_path, self.lock.is_some(), self.direct) {
+            Ok(file) => {
+                self.file = file;
+                self.temp_bytes = 0;
+                true
+            }
+            Err(e) => {
+                diagnostic::error(LogError::from(format!(
+                    "[fast_log] open log file {} fail:{}",
+                    first_file_path, e
+                )));
+                if let Err(e) = std::fs::rename(new_log_name, first_file_path) {
+                    diagnostic::error(LogError::from(format!(
+                        "[fast_log] stop writing,rename {} back to {} fail:{}",
+                        new_log_name, first_file_path, e
+                    )));
+                    self.stopped = true;
+                }
+                false
+            }
+        }
+    }
+
+    /// continue with the temp file of the path if another process rotated it,
+    /// and count the bytes written by all processes
+    fn follow_shared(&mut self) {
+        let path = format!("{}{}.log", self.dir_path, self.temp_name);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let rotated = match (self.file.get_ref().metadata(), std::fs::metadata(&path)) {
+                (Ok(a), Ok(b)) => a.ino() != b.ino() || a.dev() != b.dev(),
+                _ => true,
+            };
+            if rotated {
+                match open_temp(&path, true, None) {
+                    Ok(file) => {
+                        self.file = file;
+                        //the records are packed by the process that rotated
+                        self.stats = SplitStats::default();
+                    }
+                    Err(e) => diagnostic::error(LogError::from(format!(
+                        "[fast_log] open log file {} fail:{}",
+                        path, e
+                    ))),
+                }
+            }
+        }
+        if let Ok(m) = self.file.get_ref().metadata() {
+            self.temp_bytes = m.len() as usize;
+        }
     }
 
     pub fn truncate(&mut self) {
         //reset data
-        self.file.set_len(0);
-        self.file.seek(SeekFrom::Start(0));
+        self.file.truncate();
         self.temp_bytes = 0;
     }
 }
 
 impl FileSplitAppender {
-    ///split_log_bytes:  log file data bytes(MB) splite
+    ///split_log_bytes:  log file data bytes(MB) splite,LogSize or SplitBy::Records
     ///file_path:         the log dir or file name
     ///log_pack_cap:     pack(zip,lz4 or more...) or log Waiting cap
     /// packer: default is zip packer
-    pub fn new(
+    pub fn new<S: Into<SplitBy>>(
         file_path: &str,
-        max_temp_size: LogSize,
+        split: S,
         rolling_type: RollingType,
         packer: Box<dyn Packer>,
     ) -> FileSplitAppender {
@@ -192,45 +455,149 @@ impl FileSplitAppender {
         }
         let file_name = temp_file_name.trim_end_matches(".log");
         let first_file_path = format!("{}{}.log", &dir_path, file_name);
-        let file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .open(first_file_path.as_str());
+        let file = open_temp(&first_file_path, false, None);
         if file.is_err() {
             panic!(
                 "[fast_log] open and create file fail:{}",
                 file.err().unwrap()
             );
         }
-        let mut file = file.unwrap();
+        let file = file.unwrap();
         let mut temp_bytes = 0;
-        if let Ok(m) = file.metadata() {
+        if let Ok(m) = file.get_ref().metadata() {
             temp_bytes = m.len() as usize;
         }
-        file.seek(SeekFrom::Start(temp_bytes as u64));
-        let (sender, receiver) = chan(None);
-        spawn_saver(file_name, receiver, packer);
+        let (max_split_bytes, max_split_records) = match split.into() {
+            SplitBy::Size(size) => (size.get_len(), u64::MAX),
+            SplitBy::Records(records) => (usize::MAX, records),
+        };
+        let mut stats = SplitStats::default();
+        if max_split_records != u64::MAX {
+            stats.records = count_lines(&first_file_path);
+        }
+        let pack_name = packer.pack_name();
+        let saver = Arc::new(Saver {
+            temp: file_name.to_string(),
+            packer: Mutex::new(packer),
+            queue: Mutex::new((VecDeque::new(), false)),
+        });
         Self {
             cell: RefCell::new(FileSplitAppenderData {
-                max_split_bytes: max_temp_size.get_len(),
+                max_split_bytes,
+                max_split_records,
                 temp_bytes: temp_bytes,
                 dir_path: dir_path.to_string(),
                 file: file,
-                sender: sender,
+                saver,
                 rolling_type: rolling_type,
                 temp_name: file_name.to_string(),
+                stats,
+                manifest: false,
+                lock: None,
+                pack_name,
+                stopped: false,
+                direct: None,
             }),
         }
     }
+
+    /// share the dir with other processes(for example the workers of a prefork server):
+    /// the temp file is appended with O_APPEND,and every batch and rotation is done under
+    /// the advisory lock(flock,LockFileEx on windows) of `{temp_name}.lock` in the dir,
+    /// so the lines are never interleaved and the temp file is rotated once by the first process
+    /// reaching the limit,the others continue with the new temp file.
+    /// every process must use the same path,split and packer
+    pub fn shared(self) -> Self {
+        {
+            let mut data = self.cell.borrow_mut();
+            let path = format!("{}{}.log", data.dir_path, data.temp_name);
+            let lock = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(format!("{}{}.lock", data.dir_path, data.temp_name));
+            if data.direct.take().is_some() {
+                diagnostic::error(LogError::from(
+                    "[fast_log] direct_io is not supported by the shared log file",
+                ));
+            }
+            match (lock, open_temp(&path, true, None)) {
+                (Ok(lock), Ok(file)) => {
+                    data.lock = Some(lock);
+                    data.file = file;
+                }
+                (Err(e), _) | (_, Err(e)) => diagnostic::error(LogError::from(format!(
+                    "[fast_log] open the shared log file {} fail:{}",
+                    path, e
+                ))),
+            }
+        }
+        self
+    }
+
+    /// open the temp file with O_DIRECT to bypass the page cache,and with O_DSYNC if `dsync` is true,
+    /// see [`FileAppender::direct_io`](crate::plugin::file::FileAppender::direct_io).
+    /// the zero padding of the partial last block is truncated before the temp file is rolled.
+    /// not supported by [`FileSplitAppender::shared`],if the file system doesn't support O_DIRECT
+    /// the error is reported and the file is kept in the buffered mode
+    #[cfg(target_os = "linux")]
+    pub fn direct_io(self, dsync: bool) -> Self {
+        {
+            let mut data = self.cell.borrow_mut();
+            let path = format!("{}{}.log", data.dir_path, data.temp_name);
+            if data.lock.is_some() {
+                diagnostic::error(LogError::from(
+                    "[fast_log] direct_io is not supported by the shared log file",
+                ));
+            } else {
+                match open_temp(&path, false, Some(dsync)) {
+                    Ok(file) => {
+                        data.file = file;
+                        data.direct = Some(dsync);
+                    }
+                    Err(e) => diagnostic::error(LogError::from(format!(
+                        "[fast_log] open log file {} with O_DIRECT fail:{}",
+                        path, e
+                    ))),
+                }
+            }
+        }
+        self
+    }
+
+    /// you need enable fast_log = { ... ,features=["manifest"]}
+    /// write the SHA-256,size,record count and first/last time of each archive to `manifest.json` of the dir,
+    /// for the integrity verification and the time range lookup.
+    /// the records of the temp file written before the process starts have no first time
+    #[cfg(feature = "manifest")]
+    pub fn manifest(self) -> Self {
+        {
+            let mut data = self.cell.borrow_mut();
+            data.manifest = true;
+            let path = format!("{}{}.log", data.dir_path, data.temp_name);
+            data.stats.records = count_lines(&path);
+        }
+        self
+    }
 }
 
 impl LogAppender for FileSplitAppender {
     fn do_logs(&self, records: &[FastLogRecord]) {
         let mut data = self.cell.borrow_mut();
-        if data.temp_bytes >= data.max_split_bytes {
+        if data.stopped {
+            return;
+        }
+        if let Some(lock) = &data.lock {
+            if let Err(e) = lock.lock() {
+                diagnostic::error(LogError::from(e));
+            }
+            data.follow_shared();
+        }
+        if data.temp_bytes >= data.max_split_bytes || data.stats.records >= data.max_split_records {
             data.send_pack();
         }
+        //the stats of the records in temp_log
+        let mut pending = SplitStats::default();
         //if temp_bytes is full,must send pack
         let temp_log = {
             let mut limit = data.max_split_bytes - data.temp_bytes;
@@ -238,16 +605,23 @@ impl LogAppender for FileSplitAppender {
             for x in records {
                 match x.command {
                     Command::CommandRecord => {
-                        if (temp.as_bytes().len() + x.formated.as_bytes().len()) < limit {
+                        if (temp.len() + x.formated.len()) < limit
+                            && data.stats.records + pending.records < data.max_split_records
+                        {
                             temp.push_str(&x.formated);
                         } else {
                             //do pack
-                            data.file.write(temp.as_bytes());
+                            if let Err(e) = data.file.write_all(&encoding::encode(&temp)) {
+                                diagnostic::error(LogError::from(e));
+                            }
+                            let written = std::mem::take(&mut pending);
+                            data.stats.merge(written);
                             data.send_pack();
                             limit = data.max_split_bytes;
                             temp.clear();
                             temp.push_str(&x.formated);
                         }
+                        pending.add(x.now);
                     }
                     Command::CommandExit => {}
                     Command::CommandFlush(_) => {}
@@ -260,45 +634,235 @@ impl LogAppender for FileSplitAppender {
                 data.send_pack();
             }
             data.temp_bytes += {
-                let bytes = temp_log.as_bytes();
-                let w = data.file.write(bytes);
-                if let Ok(w) = w {
-                    w
-                } else {
-                    0
+                let bytes = encoding::encode(&temp_log);
+                match data.file.write_all(&bytes) {
+                    Ok(_) => bytes.len(),
+                    Err(e) => {
+                        diagnostic::error(LogError::from(e));
+                        0
+                    }
                 }
             };
-            if data.temp_bytes > data.max_split_bytes {
+            data.stats.merge(pending);
+            if data.temp_bytes > data.max_split_bytes
+                || data.stats.records >= data.max_split_records
+            {
                 data.send_pack();
             }
         }
+        //write the partial last block of O_DIRECT at once
+        if let Err(e) = data.file.flush() {
+            diagnostic::error(LogError::from(e));
+        }
+        if let Some(lock) = &data.lock {
+            if let Err(e) = lock.unlock() {
+                diagnostic::error(LogError::from(e));
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        let data = self.cell.borrow();
+        let split = if data.max_split_records != u64::MAX {
+            format!("{} records", data.max_split_records)
+        } else {
+            LogSize::B(data.max_split_bytes).to_string()
+        };
+        format!(
+            "FileSplitAppender({}{}.log,split={},rolling={:?},packer={}{})",
+            data.dir_path,
+            data.temp_name,
+            split,
+            data.rolling_type,
+            data.pack_name,
+            if data.lock.is_some() { ",shared" } else { "" }
+        )
     }
 
     fn flush(&self) {
         let mut data = self.cell.borrow_mut();
-        data.file.flush();
+        if let Err(e) = data.file.flush() {
+            diagnostic::error(LogError::from(e));
+        }
+    }
+
+    fn sync(&self) {
+        if let Err(e) = self.try_sync() {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_sync(&self) -> Result<(), LogError> {
+        let mut data = self.cell.borrow_mut();
+        data.file.sync_data().map_err(LogError::from)
+    }
+
+    fn close(&self) {
+        self.flush();
     }
 }
 
-///spawn an saver thread to save log file or zip file
-fn spawn_saver(temp_name: &str, r: Receiver<LogPack>, packer: Box<dyn Packer>) {
-    let temp = temp_name.to_string();
-    std::thread::spawn(move || {
+/// suffix the temp file name of the split path with the process id and the local start time,
+/// for example "logs/" => "logs/temp-4242-20231001120000.log","logs/app.log" => "logs/app-4242-20231001120000.log".
+/// the rolled files start with the suffixed name,the rolling matches the packs of all the runs by the name without the suffix,
+/// and `RollingType::KeepTime` removes the temp files of the earlier runs not written for the duration
+pub fn per_process_path(file_path: &str) -> String {
+    let file_path = file_path.replace('\\', "/");
+    let (dir, name) = match file_path.rfind('/') {
+        Some(right) => file_path.split_at(right + 1),
+        None => ("", file_path.as_str()),
+    };
+    let name = match name.trim_end_matches(".log") {
+        "" => "temp",
+        v => v,
+    };
+    let (secs, nanos) = crate::date::local_secs(crate::now());
+    let mut start = String::new();
+    crate::embedded::write_date(&mut start, secs, nanos, 0);
+    start.retain(|c| c.is_ascii_digit());
+    format!("{}{}-{}-{}.log", dir, name, std::process::id(), start)
+}
+
+/// open the temp file to write,with O_APPEND if it is shared by processes,
+/// with O_DIRECT if `direct` is Some(dsync)
+fn open_temp(path: &str, append: bool, direct: Option<bool>) -> std::io::Result<LogFile> {
+    let mut options = OpenOptions::new();
+    options.create(true).read(true);
+    if append {
+        options.append(true);
+    } else {
+        options.write(true);
+    }
+    let file = LogFile::open(path, &options, direct, 0)?;
+    //continue the existing temp file
+    file.get_ref().seek(SeekFrom::End(0))?;
+    Ok(file)
+}
+
+/// the lines of the file,0 if it doesn't exist
+fn count_lines(path: &str) -> u64 {
+    let mut file = match File::open(path) {
+        Ok(v) => v,
+        Err(_) => return 0,
+    };
+    //read by blocks,the temp file may be large
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut lines = 0;
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) | Err(_) => return lines,
+            Ok(n) => lines += buf[..n].iter().filter(|v| **v == b'\n').count() as u64,
+        }
+    }
+}
+
+/// the threads packing the rolled files of all split appenders,0 is the available parallelism up to 4
+static PACK_WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+/// the queue of the pack workers,started by the first rotation
+static PACK_POOL: Lazy<Sender<Arc<Saver>>> = Lazy::new(|| {
+    let workers = match PACK_WORKERS.load(Ordering::Relaxed) {
+        0 => std::thread::available_parallelism()
+            .map(|v| v.get().min(4))
+            .unwrap_or(1),
+        v => v,
+    };
+    let (sender, receiver) = chan::<Arc<Saver>>(None);
+    for _ in 0..workers {
+        let receiver = receiver.clone();
+        std::thread::spawn(move || {
+            crate::fast_log::set_logger_thread();
+            while let Ok(saver) = receiver.recv() {
+                saver.run();
+            }
+        });
+    }
+    sender
+});
+
+/// set the number of the threads packing the rolled files,shared by all the split appenders,
+/// so the appender threads keep writing while large files are compressed.
+/// default(0) is the available parallelism up to 4,it takes effect before the first rotation
+pub fn set_pack_workers(workers: usize) {
+    PACK_WORKERS.store(workers, Ordering::Relaxed);
+}
+
+/// the packs of an appender,packed in order by one pack worker at a time
+struct Saver {
+    temp: String,
+    packer: Mutex<Box<dyn Packer>>,
+    /// the packs to save,and is the saver queued on the pack workers
+    queue: Mutex<(VecDeque<LogPack>, bool)>,
+}
+
+impl Saver {
+    fn push(self: &Arc<Self>, pack: LogPack) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        queue.0.push_back(pack);
+        if !queue.1 {
+            queue.1 = true;
+            PACK_POOL.send(self.clone());
+        }
+    }
+
+    /// save the queued packs until the queue is empty
+    fn run(&self) {
         loop {
-            if let Ok(pack) = r.recv() {
-                //do rolling
-                pack.rolling.do_rolling(&temp, &pack.dir);
-                let log_file_path = pack.new_log_name.clone();
-                //do save pack
-                let remove = do_pack(&packer, pack);
-                if let Ok(remove) = remove {
-                    if remove {
-                        std::fs::remove_file(log_file_path);
+            let pack = {
+                let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+                match queue.0.pop_front() {
+                    Some(pack) => pack,
+                    None => {
+                        queue.1 = false;
+                        return;
                     }
                 }
+            };
+            self.save(pack);
+        }
+    }
+
+    fn save(&self, pack: LogPack) {
+        let packer = self.packer.lock().unwrap_or_else(|e| e.into_inner());
+        //do rolling
+        pack.rolling.do_rolling(&self.temp, &pack.dir);
+        let log_file_path = pack.new_log_name.clone();
+        let old_path = format!("{}{}.log", pack.dir, self.temp);
+        let dir = pack.dir.clone();
+        let stats = pack.stats.clone();
+        let manifest = pack.manifest;
+        //do save pack
+        let remove = do_pack(&packer, pack);
+        if let Ok(remove) = remove {
+            if remove {
+                std::fs::remove_file(&log_file_path);
             }
         }
-    });
+        #[cfg(feature = "manifest")]
+        if manifest {
+            let archive = packer.archive_path(&log_file_path);
+            if let Err(e) = crate::plugin::manifest::update(&dir, &archive, &stats) {
+                diagnostic::error(e);
+            }
+        }
+        on_rotate(packer.as_ref(), old_path, log_file_path);
+    }
+}
+
+fn on_rotate(packer: &dyn Packer, old_path: String, new_path: String) {
+    let f = *ON_ROTATE.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(f) = f {
+        let archive_path = packer.archive_path(&new_path);
+        //the archive is missing if the pack failed
+        if let Ok(m) = std::fs::metadata(&archive_path) {
+            f(&RotateEvent {
+                old_path: PathBuf::from(old_path),
+                new_path: PathBuf::from(new_path),
+                archive_path: PathBuf::from(archive_path),
+                archive_size: m.len(),
+            });
+        }
+    }
 }
 
 /// write an Pack to zip file
@@ -314,6 +878,9 @@ pub fn do_pack(packer: &Box<dyn Packer>, mut pack: LogPack) -> Result<bool, LogP
     let log_file = log_file.unwrap();
     //make
     let r = packer.do_pack(log_file, log_file_path);
+    if let Err(e) = &r {
+        diagnostic::error(e.clone());
+    }
     if r.is_err() && packer.retry() > 0 {
         let mut retry = 1;
         while let Err(packs) = do_pack(packer, pack) {
@@ -0,0 +1,102 @@
+use crate::appender::{Command, FastLogRecord, LogAppender, RecordFormat};
+use crate::error::LogError;
+use std::time::Duration;
+
+/// format the records again with its own format before writing them to the appender,
+/// the other appenders keep `Config::format`.
+/// for example the colored human format on the console and JSON in the file:
+/// ```rust
+/// use fast_log::plugin::console::ConsoleAppender;
+/// use fast_log::plugin::file::FileAppender;
+/// use fast_log::{Config, FastLogFormatJson, PrettyFormat};
+/// let config = Config::new()
+///     .custom_format(ConsoleAppender::new(), PrettyFormat::new())
+///     .custom_format(
+///         FileAppender::new("target/logs/app.json"),
+///         FastLogFormatJson::new(),
+///     );
+/// ```
+pub struct FormattedAppender {
+    appender: Box<dyn LogAppender>,
+    format: Box<dyn RecordFormat>,
+}
+
+impl FormattedAppender {
+    pub fn new<A: LogAppender + 'static, F: RecordFormat + 'static>(
+        appender: A,
+        format: F,
+    ) -> Self {
+        Self {
+            appender: Box::new(appender),
+            format: Box::new(format),
+        }
+    }
+
+    fn format_all(&self, records: &[FastLogRecord]) -> Vec<FastLogRecord> {
+        records
+            .iter()
+            .map(|x| {
+                let mut x = x.clone();
+                reformat(self.format.as_ref(), &mut x);
+                x
+            })
+            .collect()
+    }
+}
+
+/// format the record again like the worker,with the scrubbers and `Config::line_end`.
+/// the commands(flush,exit),the records of `print`(formatted by the caller)
+/// and the raw records(redacted and scrubbed by the worker) are unchanged
+pub(crate) fn reformat(format: &dyn RecordFormat, x: &mut FastLogRecord) {
+    let printed = x.target.is_empty() && x.args.is_empty();
+    let raw = x.target.starts_with(crate::formats::RAW_TARGET);
+    if x.command != Command::CommandRecord || printed || raw {
+        return;
+    }
+    x.formated.clear();
+    format.do_format(x);
+    crate::scrub::scrub_record(x);
+    crate::encoding::line_end().apply(&mut x.formated);
+}
+
+impl LogAppender for FormattedAppender {
+    fn open(&self) {
+        self.appender.open();
+    }
+
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        self.appender.do_logs(&self.format_all(records));
+    }
+
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
+        self.appender.try_do_logs(&self.format_all(records))
+    }
+
+    fn flush(&self) {
+        self.appender.flush();
+    }
+
+    fn sync(&self) {
+        self.appender.sync();
+    }
+
+    fn try_sync(&self) -> Result<(), LogError> {
+        self.appender.try_sync()
+    }
+
+    fn idle_timeout(&self) -> Option<Duration> {
+        self.appender.idle_timeout()
+    }
+
+    fn on_idle(&self) {
+        self.appender.on_idle();
+    }
+
+    fn close(&self) {
+        self.appender.close();
+    }
+
+    fn describe(&self) -> String {
+        self.appender.describe()
+    }
+}
@@ -0,0 +1,252 @@
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use crate::diagnostic;
+use crate::error::LogError;
+use crate::formats::escape_json;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use log::Level;
+use std::cell::{Cell, RefCell};
+use std::fmt::Write as _;
+use std::io::Write;
+use std::net::{TcpStream, UdpSocket};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// the chunk magic bytes of GELF
+const CHUNK_MAGIC: [u8; 2] = [0x1e, 0x0f];
+/// the max chunks of one GELF message
+const MAX_CHUNKS: usize = 128;
+/// magic(2) + message id(8) + sequence number(1) + sequence count(1)
+const CHUNK_HEADER_LEN: usize = 12;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GelfTransport {
+    /// chunked and optionally zlib compressed datagrams
+    Udp,
+    /// null byte delimited messages,not compressed
+    Tcp,
+}
+
+/// send records to Graylog in the GELF 1.1 format.
+/// the structured fields `_target`,`_module_path`,`_file`,`_line`,`_thread`,`_pid` are populated from the record
+/// for example:
+/// ```rust,no_run
+/// use fast_log::plugin::gelf::GelfAppender;
+/// use fast_log::Config;
+/// fast_log::init(Config::new().custom(GelfAppender::udp("127.0.0.1:12201").compress(true)));
+/// ```
+pub struct GelfAppender {
+    pub addr: String,
+    pub transport: GelfTransport,
+    pub compress: bool,
+    /// the max datagram size of UDP,bigger messages are chunked. default is 1420
+    pub chunk_size: usize,
+    /// the `host` field,default is the host name
+    pub host: String,
+    udp: RefCell<Option<UdpSocket>>,
+    tcp: RefCell<Option<TcpStream>>,
+    message_id: Cell<u64>,
+}
+
+impl GelfAppender {
+    pub fn new(addr: &str, transport: GelfTransport) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        Self {
+            addr: addr.to_string(),
+            transport,
+            compress: false,
+            chunk_size: 1420,
+            host: crate::fast_log::hostname(),
+            udp: RefCell::new(None),
+            tcp: RefCell::new(None),
+            message_id: Cell::new(seed ^ std::process::id() as u64),
+        }
+    }
+
+    pub fn udp(addr: &str) -> Self {
+        Self::new(addr, GelfTransport::Udp)
+    }
+
+    pub fn tcp(addr: &str) -> Self {
+        Self::new(addr, GelfTransport::Tcp)
+    }
+
+    /// zlib compress the UDP messages,default is false
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(CHUNK_HEADER_LEN + 1);
+        self
+    }
+
+    pub fn host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self
+    }
+
+    /// the GELF json of the record
+    pub fn to_gelf(&self, record: &FastLogRecord) -> String {
+        let timestamp = record
+            .now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let host = record.hostname.as_deref().unwrap_or(&self.host);
+        let mut json = String::with_capacity(record.args.len() + 256);
+        json.push_str("{\"version\":\"1.1\",\"host\":\"");
+        escape_json(&mut json, host);
+        let short_message = record.args.lines().next().unwrap_or_default();
+        json.push_str("\",\"short_message\":\"");
+        escape_json(&mut json, short_message);
+        json.push('"');
+        if short_message.len() != record.args.len() {
+            json.push_str(",\"full_message\":\"");
+            escape_json(&mut json, &record.args);
+            json.push('"');
+        }
+        write!(
+            json,
+            ",\"timestamp\":{:.3},\"level\":{}",
+            timestamp,
+            syslog_level(record.level)
+        );
+        json.push_str(",\"_target\":\"");
+        escape_json(&mut json, &record.target);
+        json.push_str("\",\"_module_path\":\"");
+        escape_json(&mut json, &record.module_path);
+        json.push('"');
+        if !record.file.is_empty() {
+            json.push_str(",\"_file\":\"");
+            escape_json(&mut json, &record.file);
+            json.push('"');
+        }
+        if let Some(line) = record.line {
+            write!(json, ",\"_line\":{}", line);
+        }
+        if !record.thread_name.is_empty() {
+            json.push_str(",\"_thread\":\"");
+            escape_json(&mut json, &record.thread_name);
+            json.push('"');
+        } else if let Some(id) = record.thread_id {
+            write!(json, ",\"_thread\":\"{:?}\"", id);
+        }
+        if let Some(pid) = record.pid {
+            write!(json, ",\"_pid\":{}", pid);
+        }
+        if let Some(seq) = record.seq {
+            write!(json, ",\"_seq\":{}", seq);
+        }
+        json.push('}');
+        json
+    }
+
+    fn send_udp(&self, message: &[u8]) -> std::io::Result<()> {
+        let mut udp = self.udp.borrow_mut();
+        if udp.is_none() {
+            let socket = UdpSocket::bind("0.0.0.0:0")?;
+            socket.connect(&self.addr)?;
+            *udp = Some(socket);
+        }
+        let socket = udp.as_ref().unwrap();
+        let compressed;
+        let message = if self.compress {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(message)?;
+            compressed = encoder.finish()?;
+            &compressed[..]
+        } else {
+            message
+        };
+        if message.len() <= self.chunk_size {
+            socket.send(message)?;
+            return Ok(());
+        }
+        let body_size = self.chunk_size - CHUNK_HEADER_LEN;
+        let count = message.len().div_ceil(body_size);
+        if count > MAX_CHUNKS {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("message needs {} chunks,max is {}", count, MAX_CHUNKS),
+            ));
+        }
+        let id = self.message_id.get().wrapping_add(1);
+        self.message_id.set(id);
+        let mut chunk = Vec::with_capacity(self.chunk_size);
+        for (i, body) in message.chunks(body_size).enumerate() {
+            chunk.clear();
+            chunk.extend_from_slice(&CHUNK_MAGIC);
+            chunk.extend_from_slice(&id.to_be_bytes());
+            chunk.push(i as u8);
+            chunk.push(count as u8);
+            chunk.extend_from_slice(body);
+            socket.send(&chunk)?;
+        }
+        Ok(())
+    }
+
+    fn send_tcp(&self, message: &[u8]) -> std::io::Result<()> {
+        let mut tcp = self.tcp.borrow_mut();
+        if tcp.is_none() {
+            *tcp = Some(TcpStream::connect(&self.addr)?);
+        }
+        let stream = tcp.as_mut().unwrap();
+        let r = stream
+            .write_all(message)
+            .and_then(|_| stream.write_all(&[0]));
+        if r.is_err() {
+            //reconnect on the next write
+            *tcp = None;
+        }
+        r
+    }
+}
+
+impl LogAppender for GelfAppender {
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        if let Err(e) = self.try_do_logs(records) {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
+        for x in records {
+            if x.command != Command::CommandRecord {
+                continue;
+            }
+            let message = self.to_gelf(x);
+            let r = match self.transport {
+                GelfTransport::Udp => self.send_udp(message.as_bytes()),
+                GelfTransport::Tcp => self.send_tcp(message.as_bytes()),
+            };
+            r.map_err(|e| LogError::from(format!("[fast_log] gelf send fail:{}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn flush(&self) {
+        if let Some(stream) = self.tcp.borrow_mut().as_mut() {
+            let _ = stream.flush();
+        }
+    }
+
+    fn close(&self) {
+        self.udp.borrow_mut().take();
+        self.tcp.borrow_mut().take();
+    }
+}
+
+/// the syslog severity of the level
+fn syslog_level(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug => 7,
+        Level::Trace => 7,
+    }
+}
@@ -0,0 +1,167 @@
+//! the gzip compressed file appender,
+//! the records are compressed on the fly,no plaintext file is written before the compression.
+//!
+//! * every flush(`fast_log::flush`,`Config::flush_level`,`Config::fsync`) is a Z_SYNC_FLUSH,
+//!   the records before it can be decompressed even if the process crashes later
+//! * the records after the last flush are kept in the compressor and lost on a crash
+//! * every open appends a new gzip member,`zcat` and `flate2::read::MultiGzDecoder` read all of them
+//! * the unfinished member of a crashed process is recompressed on open,so the file stays readable.
+//!   the start of the unfinished member is kept in `{path}.member` until the member is finished,
+//!   so only that member is read again
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use crate::diagnostic;
+use crate::encoding;
+use crate::error::LogError;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::cell::RefCell;
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+
+pub struct GzipFileAppender {
+    encoder: RefCell<Option<GzEncoder<File>>>,
+    path: String,
+    level: Compression,
+}
+
+impl GzipFileAppender {
+    /// append the compressed records into `path`(for example "target/logs/app.log.gz")
+    pub fn new(path: &str) -> Result<Self, LogError> {
+        let path = path.replace('\\', "/");
+        if let Some(right) = path.rfind('/') {
+            std::fs::create_dir_all(&path[0..right])?;
+        }
+        let s = Self {
+            encoder: RefCell::new(None),
+            path,
+            level: Compression::default(),
+        };
+        //check the file,the member is started by the first records
+        s.open_file()?;
+        s.recover()?;
+        Ok(s)
+    }
+
+    /// the compression level 0-9,default is 6
+    pub fn level(mut self, level: u32) -> Self {
+        self.level = Compression::new(level.min(9));
+        self
+    }
+
+    fn open_file(&self) -> Result<File, LogError> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| LogError::from(format!("open log file {} fail:{}", self.path, e)))
+    }
+
+    /// the file keeping the start of the unfinished member
+    fn member_path(&self) -> String {
+        format!("{}.member", self.path)
+    }
+
+    /// recompress the last member if it is unfinished(the process crashed),
+    /// the records after the last Z_SYNC_FLUSH of the member are lost
+    fn recover(&self) -> Result<(), LogError> {
+        let start = match std::fs::read_to_string(self.member_path()) {
+            Ok(v) => v.trim().parse::<u64>().unwrap_or_default(),
+            Err(_) => return Ok(()),
+        };
+        let mut file = OpenOptions::new().read(true).write(true).open(&self.path)?;
+        if file.metadata()?.len() > start {
+            file.seek(SeekFrom::Start(start))?;
+            let mut decoder = GzDecoder::new(&file);
+            let mut encoder = GzEncoder::new(vec![], self.level);
+            //the copy stops at the truncated tail
+            let _ = std::io::copy(&mut decoder, &mut encoder);
+            let member = encoder.finish()?;
+            file.set_len(start)?;
+            file.seek(SeekFrom::Start(start))?;
+            file.write_all(&member)?;
+            file.sync_data()?;
+        }
+        std::fs::remove_file(self.member_path())?;
+        diagnostic::report(
+            log::Level::Warn,
+            &format!(
+                "[fast_log] recover the unfinished gzip member of {}",
+                self.path
+            ),
+        );
+        Ok(())
+    }
+
+    fn with_encoder<R>(
+        &self,
+        f: impl FnOnce(&mut GzEncoder<File>) -> std::io::Result<R>,
+    ) -> Result<R, LogError> {
+        let mut encoder = self.encoder.borrow_mut();
+        if encoder.is_none() {
+            let file = self.open_file()?;
+            //the member starts at the end of the file,kept until the member is finished
+            std::fs::write(self.member_path(), file.metadata()?.len().to_string())?;
+            *encoder = Some(GzEncoder::new(file, self.level));
+        }
+        f(encoder.as_mut().unwrap()).map_err(LogError::from)
+    }
+}
+
+impl LogAppender for GzipFileAppender {
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        if let Err(e) = self.try_do_logs(records) {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
+        let mut buf = String::new();
+        for x in records {
+            if x.command == Command::CommandRecord {
+                buf.push_str(&x.formated);
+            }
+        }
+        if buf.is_empty() {
+            return Ok(());
+        }
+        self.with_encoder(|e| e.write_all(&encoding::encode(&buf)))
+    }
+
+    /// Z_SYNC_FLUSH the compressor into the file
+    fn flush(&self) {
+        if let Some(encoder) = self.encoder.borrow_mut().as_mut() {
+            if let Err(e) = encoder.flush() {
+                diagnostic::error(LogError::from(e));
+            }
+        }
+    }
+
+    fn sync(&self) {
+        if let Err(e) = self.try_sync() {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_sync(&self) -> Result<(), LogError> {
+        match self.encoder.borrow_mut().as_mut() {
+            Some(encoder) => {
+                encoder.flush()?;
+                encoder.get_ref().sync_data().map_err(LogError::from)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// finish the gzip member with its trailer,the next records start a new member
+    fn close(&self) {
+        if let Some(encoder) = self.encoder.borrow_mut().take() {
+            match encoder.finish() {
+                Ok(_) => {
+                    let _ = std::fs::remove_file(self.member_path());
+                }
+                Err(e) => diagnostic::error(LogError::from(e)),
+            }
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use crate::diagnostic;
+use crate::error::LogError;
+use crate::formats::escape_json;
+use log::Level;
+use std::cell::RefCell;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// the request body of [`HttpAppender`]
+#[derive(Clone, Debug)]
+pub enum BodyTemplate {
+    /// the formatted records,one per line
+    Lines,
+    /// the elasticsearch `_bulk` api,one index action per record
+    EsBulk { index: String },
+    /// the loki `/loki/api/v1/push` api,one stream with the static labels
+    Loki { labels: Vec<(String, String)> },
+    /// the splunk HEC `/services/collector/event` api
+    SplunkHec { sourcetype: String },
+    /// the OpenTelemetry OTLP/HTTP `/v1/logs` api in JSON,see [`HttpAppender::otlp`]
+    #[cfg(feature = "otel")]
+    Otlp { resource: Vec<(String, String)> },
+}
+
+impl BodyTemplate {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            BodyTemplate::Lines => "text/plain",
+            BodyTemplate::EsBulk { .. } => "application/x-ndjson",
+            BodyTemplate::Loki { .. } => "application/json",
+            BodyTemplate::SplunkHec { .. } => "application/json",
+            #[cfg(feature = "otel")]
+            BodyTemplate::Otlp { .. } => "application/json",
+        }
+    }
+
+    /// build the request body of the records
+    pub fn body(&self, records: &[FastLogRecord]) -> String {
+        let mut body = String::new();
+        match self {
+            BodyTemplate::Lines => {
+                for x in records {
+                    body.push_str(&x.formated);
+                }
+            }
+            BodyTemplate::EsBulk { index } => {
+                for x in records {
+                    body.push_str("{\"index\":{\"_index\":\"");
+                    escape_json(&mut body, index);
+                    write!(
+                        body,
+                        "\"}}}}\n{{\"@timestamp\":{},\"level\":\"{}\",\"target\":\"",
+                        unix_nanos(x.now) / 1_000_000,
+                        x.level
+                    );
+                    escape_json(&mut body, &x.target);
+                    body.push_str("\",\"message\":\"");
+                    escape_json(&mut body, &x.args);
+                    body.push_str("\"}\n");
+                }
+            }
+            BodyTemplate::Loki { labels } => {
+                body.push_str("{\"streams\":[{\"stream\":{");
+                for (i, (k, v)) in labels.iter().enumerate() {
+                    if i > 0 {
+                        body.push(',');
+                    }
+                    body.push('"');
+                    escape_json(&mut body, k);
+                    body.push_str("\":\"");
+                    escape_json(&mut body, v);
+                    body.push('"');
+                }
+                body.push_str("},\"values\":[");
+                for (i, x) in records.iter().enumerate() {
+                    if i > 0 {
+                        body.push(',');
+                    }
+                    write!(body, "[\"{}\",\"", unix_nanos(x.now));
+                    escape_json(&mut body, x.formated.trim_end());
+                    body.push_str("\"]");
+                }
+                body.push_str("]}]}");
+            }
+            BodyTemplate::SplunkHec { sourcetype } => {
+                for x in records {
+                    write!(
+                        body,
+                        "{{\"time\":{:.3},\"sourcetype\":\"",
+                        unix_nanos(x.now) as f64 / 1e9
+                    );
+                    escape_json(&mut body, sourcetype);
+                    body.push_str("\",\"event\":\"");
+                    escape_json(&mut body, x.formated.trim_end());
+                    body.push_str("\"}");
+                }
+            }
+            #[cfg(feature = "otel")]
+            BodyTemplate::Otlp { resource } => {
+                body = crate::plugin::otel::otlp_json(records, resource);
+            }
+        }
+        body
+    }
+}
+
+fn is_client_log(record: &FastLogRecord) -> bool {
+    record.target.starts_with("ureq") || record.target.starts_with("rustls")
+}
+
+fn unix_nanos(now: SystemTime) -> u128 {
+    now.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+/// buffer records and POST them in bulk to an http(s) endpoint,for example elasticsearch,loki or splunk HEC.
+/// failed requests are retried with an exponential backoff,
+/// if the endpoint is still down the body is spilled to `spill_dir` and sent again once the endpoint recovers
+/// for example:
+/// ```rust,no_run
+/// use fast_log::plugin::http::{BodyTemplate, HttpAppender};
+/// use fast_log::Config;
+/// fast_log::init(Config::new().custom(
+///     HttpAppender::new("http://localhost:9200/_bulk", BodyTemplate::EsBulk { index: "logs".to_string() })
+///         .spill_dir("target/logs/spill/"),
+/// ));
+/// ```
+pub struct HttpAppender {
+    pub endpoint: String,
+    pub headers: Vec<(String, String)>,
+    pub template: BodyTemplate,
+    /// POST when the buffer has `bulk_size` records,default is 1000
+    pub bulk_size: usize,
+    /// POST the buffered records after this inactivity,default is 1s
+    pub flush_interval: Duration,
+    /// retries of a failed request,default is 3
+    pub max_retries: u32,
+    /// the first retry backoff,doubled on each retry. default is 100ms
+    pub backoff: Duration,
+    pub spill_dir: Option<PathBuf>,
+    agent: ureq::Agent,
+    buffer: RefCell<Vec<FastLogRecord>>,
+}
+
+impl HttpAppender {
+    pub fn new(endpoint: &str, template: BodyTemplate) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            headers: vec![],
+            template,
+            bulk_size: 1000,
+            flush_interval: Duration::from_secs(1),
+            max_retries: 3,
+            backoff: Duration::from_millis(100),
+            spill_dir: None,
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+            buffer: RefCell::new(vec![]),
+        }
+    }
+
+    /// add a request header,for example `header("Authorization", "Splunk <token>")`
+    pub fn header(mut self, key: &str, value: &str) -> Self {
+        self.headers.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn bulk_size(mut self, bulk_size: usize) -> Self {
+        self.bulk_size = bulk_size.max(1);
+        self
+    }
+
+    pub fn flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    pub fn retry(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// spill the bodies that failed to be sent to files in `dir`
+    pub fn spill_dir(mut self, dir: &str) -> Self {
+        std::fs::create_dir_all(dir);
+        self.spill_dir = Some(PathBuf::from(dir));
+        self
+    }
+
+    fn post_once(&self, body: &str) -> Result<(), Box<ureq::Error>> {
+        let mut request = self
+            .agent
+            .post(&self.endpoint)
+            .set("Content-Type", self.template.content_type());
+        for (k, v) in &self.headers {
+            request = request.set(k, v);
+        }
+        request.send_string(body).map(|_| ()).map_err(Box::new)
+    }
+
+    /// POST the body,retry the transport errors,429 and 5xx
+    fn post(&self, body: &str) -> Result<(), LogError> {
+        let mut backoff = self.backoff;
+        let mut retries = 0;
+        loop {
+            let e = match self.post_once(body) {
+                Ok(_) => return Ok(()),
+                Err(e) => e,
+            };
+            let retryable = match e.as_ref() {
+                ureq::Error::Status(code, _) => *code == 429 || *code >= 500,
+                ureq::Error::Transport(_) => true,
+            };
+            if !retryable || retries >= self.max_retries {
+                return Err(LogError::from(format!(
+                    "[fast_log] http post {} fail:{}",
+                    self.endpoint, e
+                )));
+            }
+            retries += 1;
+            std::thread::sleep(backoff);
+            backoff *= 2;
+        }
+    }
+
+    fn spill(&self, body: &str) -> Result<(), LogError> {
+        let dir = match &self.spill_dir {
+            None => return Ok(()),
+            Some(v) => v,
+        };
+        let path = dir.join(format!("{}.spill", unix_nanos(SystemTime::now())));
+        std::fs::write(&path, body)
+            .map_err(|e| LogError::from(format!("[fast_log] http spill fail:{}", e)))
+    }
+
+    /// send the spilled bodies again,the oldest first
+    fn replay_spilled(&self) -> Result<(), LogError> {
+        let dir = match &self.spill_dir {
+            None => return Ok(()),
+            Some(v) => v,
+        };
+        let mut files: Vec<PathBuf> = match std::fs::read_dir(dir) {
+            Ok(v) => v
+                .flatten()
+                .map(|v| v.path())
+                .filter(|v| v.extension().map(|e| e == "spill").unwrap_or(false))
+                .collect(),
+            Err(_) => return Ok(()),
+        };
+        files.sort();
+        for path in files {
+            if let Ok(body) = std::fs::read_to_string(&path) {
+                self.post(&body)?;
+            }
+            let _ = std::fs::remove_file(&path);
+        }
+        Ok(())
+    }
+
+    fn send_buffer(&self) -> Result<(), LogError> {
+        let records = std::mem::take(&mut *self.buffer.borrow_mut());
+        if records.is_empty() {
+            return Ok(());
+        }
+        let body = self.template.body(&records);
+        match self.post(&body) {
+            Ok(_) => self.replay_spilled(),
+            Err(e) => {
+                if self.spill_dir.is_some() {
+                    diagnostic::report(Level::Warn, &e.to_string());
+                    self.spill(&body)
+                } else {
+                    Err(e)
+                }
+            }
+        }
+    }
+}
+
+impl LogAppender for HttpAppender {
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        if let Err(e) = self.try_do_logs(records) {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
+        let full = {
+            let mut buffer = self.buffer.borrow_mut();
+            //skip the commands and the logs of the http client itself,which would feed back into this appender
+            buffer.extend(
+                records
+                    .iter()
+                    .filter(|x| x.command == Command::CommandRecord && !is_client_log(x))
+                    .cloned(),
+            );
+            buffer.len() >= self.bulk_size
+        };
+        if full {
+            self.send_buffer()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&self) {
+        if let Err(e) = self.send_buffer() {
+            diagnostic::error(e);
+        }
+    }
+
+    fn idle_timeout(&self) -> Option<Duration> {
+        Some(self.flush_interval)
+    }
+
+    fn on_idle(&self) {
+        self.flush();
+    }
+
+    fn close(&self) {
+        self.flush();
+    }
+}
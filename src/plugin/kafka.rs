@@ -0,0 +1,198 @@
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use crate::diagnostic;
+use crate::error::LogError;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{BaseProducer, BaseRecord, DeliveryResult, Producer, ProducerContext};
+use rdkafka::ClientContext;
+use std::cell::RefCell;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// the message key of [`KafkaAppender`],records with the same key go to the same partition
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KafkaKey {
+    None,
+    Target,
+    ModulePath,
+    Level,
+}
+
+/// collect the records that kafka failed to deliver
+#[derive(Default)]
+struct DeliveryContext {
+    failed: Mutex<Vec<FastLogRecord>>,
+}
+
+impl ClientContext for DeliveryContext {}
+
+impl ProducerContext for DeliveryContext {
+    type DeliveryOpaque = Box<FastLogRecord>;
+
+    fn delivery(&self, delivery_result: &DeliveryResult<'_>, record: Self::DeliveryOpaque) {
+        if delivery_result.is_err() {
+            self.failed
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(*record);
+        }
+    }
+}
+
+/// publish the formatted records to a kafka topic.
+/// records are batched asynchronously by librdkafka(`linger.ms` defaults to the linger of `Config::linger`),
+/// records that fail to be delivered are written to the fallback appender(for example a local file)
+/// for example:
+/// ```rust,no_run
+/// use fast_log::plugin::file::FileAppender;
+/// use fast_log::plugin::kafka::{KafkaAppender, KafkaKey};
+/// use fast_log::Config;
+/// fast_log::init(Config::new().custom(
+///     KafkaAppender::new("localhost:9092", "logs")
+///         .key(KafkaKey::Target)
+///         .fallback(FileAppender::new("target/logs/kafka_fallback.log")),
+/// ));
+/// ```
+pub struct KafkaAppender {
+    pub topic: String,
+    pub key: KafkaKey,
+    config: ClientConfig,
+    fallback: Option<Box<dyn LogAppender>>,
+    producer: RefCell<Option<BaseProducer<DeliveryContext>>>,
+}
+
+impl KafkaAppender {
+    pub fn new(brokers: &str, topic: &str) -> Self {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", brokers);
+        Self {
+            topic: topic.to_string(),
+            key: KafkaKey::None,
+            config,
+            fallback: None,
+            producer: RefCell::new(None),
+        }
+    }
+
+    pub fn key(mut self, key: KafkaKey) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// set a librdkafka producer property,for example `set("compression.type", "lz4")`
+    pub fn set(mut self, key: &str, value: &str) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    /// write the records that fail to be delivered to `appender`
+    pub fn fallback<A: LogAppender + 'static>(mut self, appender: A) -> Self {
+        self.fallback = Some(Box::new(appender));
+        self
+    }
+
+    fn record_key<'a>(&self, record: &'a FastLogRecord) -> Option<&'a str> {
+        match self.key {
+            KafkaKey::None => None,
+            KafkaKey::Target => Some(&record.target),
+            KafkaKey::ModulePath => Some(&record.module_path),
+            KafkaKey::Level => Some(record.level.as_str()),
+        }
+    }
+
+    /// write the failed records to the fallback appender
+    fn write_failed(&self, failed: Vec<FastLogRecord>) {
+        if failed.is_empty() {
+            return;
+        }
+        diagnostic::error(LogError::from(format!(
+            "[fast_log] kafka deliver fail:{} records",
+            failed.len()
+        )));
+        if let Some(fallback) = &self.fallback {
+            fallback.do_logs(&failed);
+        }
+    }
+
+    fn drain_failed(&self, producer: &BaseProducer<DeliveryContext>) {
+        let failed = std::mem::take(
+            &mut *producer
+                .context()
+                .failed
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        );
+        self.write_failed(failed);
+    }
+}
+
+impl LogAppender for KafkaAppender {
+    fn open(&self) {
+        let mut config = self.config.clone();
+        if config.get("linger.ms").is_none() {
+            config.set("linger.ms", crate::batch().linger.as_millis().to_string());
+        }
+        match config.create_with_context(DeliveryContext::default()) {
+            Ok(producer) => {
+                *self.producer.borrow_mut() = Some(producer);
+            }
+            Err(e) => {
+                diagnostic::error(LogError::from(format!(
+                    "[fast_log] kafka create producer fail:{}",
+                    e
+                )));
+            }
+        }
+        if let Some(fallback) = &self.fallback {
+            fallback.open();
+        }
+    }
+
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        let producer = self.producer.borrow();
+        let producer = match producer.as_ref() {
+            None => {
+                self.write_failed(records.to_vec());
+                return;
+            }
+            Some(v) => v,
+        };
+        let mut failed = vec![];
+        for x in records {
+            if x.command != Command::CommandRecord {
+                continue;
+            }
+            let mut record = BaseRecord::with_opaque_to(&self.topic, Box::new(x.clone()))
+                .payload(x.formated.as_str());
+            if let Some(key) = self.record_key(x) {
+                record = record.key(key);
+            }
+            if let Err((_, record)) = producer.send(record) {
+                //the local queue is full
+                failed.push(*record.delivery_opaque);
+            }
+        }
+        producer.poll(Duration::ZERO);
+        self.write_failed(failed);
+        self.drain_failed(producer);
+    }
+
+    fn flush(&self) {
+        if let Some(producer) = self.producer.borrow().as_ref() {
+            if let Err(e) = producer.flush(Duration::from_secs(10)) {
+                diagnostic::error(LogError::from(format!("[fast_log] kafka flush fail:{}", e)));
+            }
+            self.drain_failed(producer);
+        }
+        if let Some(fallback) = &self.fallback {
+            fallback.flush();
+        }
+    }
+
+    fn close(&self) {
+        self.flush();
+        self.producer.borrow_mut().take();
+        if let Some(fallback) = &self.fallback {
+            fallback.close();
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! the Android logcat appender,writes by `__android_log_write` of liblog
+#![allow(unsafe_code)]
+
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use android_log_sys::{__android_log_write, LogPriority};
+use log::Level;
+use std::ffi::CString;
+
+/// logcat truncates the longer messages
+const MAX_LEN: usize = 4000;
+
+/// write the records into logcat,the tag is the target of the record(or the fixed tag),
+/// Trace => VERBOSE,Debug => DEBUG,Info => INFO,Warn => WARN,Error => ERROR.
+/// logcat shows the time and the level itself,so the message is the args of the record,
+/// the longer messages are split into lines of 4000 bytes
+/// you need enable fast_log = { ... ,features=["logcat"]}
+#[derive(Default)]
+pub struct LogcatAppender {
+    pub tag: Option<String>,
+}
+
+impl LogcatAppender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// use `tag` for all records instead of the target
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.to_string());
+        self
+    }
+}
+
+fn priority(level: Level) -> LogPriority {
+    match level {
+        Level::Error => LogPriority::ERROR,
+        Level::Warn => LogPriority::WARN,
+        Level::Info => LogPriority::INFO,
+        Level::Debug => LogPriority::DEBUG,
+        Level::Trace => LogPriority::VERBOSE,
+    }
+}
+
+/// the C string without the interior nul
+fn c_string(v: &str) -> CString {
+    CString::new(v.replace('\0', "")).unwrap_or_default()
+}
+
+impl LogAppender for LogcatAppender {
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        for x in records {
+            if x.command != Command::CommandRecord {
+                continue;
+            }
+            let tag = c_string(self.tag.as_deref().unwrap_or(&x.target));
+            let mut msg = x.args.as_str();
+            while !msg.is_empty() {
+                let mut end = msg.len().min(MAX_LEN);
+                while !msg.is_char_boundary(end) {
+                    end -= 1;
+                }
+                let text = c_string(&msg[..end]);
+                //the pointers are valid nul terminated strings during the call
+                unsafe {
+                    __android_log_write(priority(x.level) as _, tag.as_ptr(), text.as_ptr());
+                }
+                msg = &msg[end..];
+            }
+        }
+    }
+}
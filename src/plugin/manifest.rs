@@ -0,0 +1,89 @@
+use crate::date;
+use crate::error::LogError;
+use crate::plugin::file_split::SplitStats;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// the manifest file of a file_split dir
+pub const MANIFEST: &str = "manifest.json";
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    archives: Vec<Archive>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Archive {
+    file: String,
+    sha256: String,
+    size: u64,
+    records: u64,
+    first: Option<String>,
+    last: Option<String>,
+}
+
+/// add the archive to `{dir}manifest.json` and remove the archives deleted by the rolling,
+/// one archive per line:
+/// ```json
+/// {"archives":[
+/// {"file":"temp2023-01-01 00:00:00.000000000.zip","sha256":"...","size":1024,"records":10,"first":"2023-01-01 00:00:00","last":"2023-01-01 00:00:01"}
+/// ]}
+/// ```
+pub fn update(dir: &str, archive: &str, stats: &SplitStats) -> Result<(), LogError> {
+    let path = format!("{}{}", dir, MANIFEST);
+    let mut archives = match std::fs::read_to_string(&path) {
+        Ok(v) => serde_json::from_str::<Manifest>(&v)
+            .map(|v| v.archives)
+            .unwrap_or_default(),
+        Err(_) => vec![],
+    };
+    archives.retain(|v| Path::new(dir).join(&v.file).exists());
+    if Path::new(archive).exists() {
+        archives.push(entry(archive, stats)?);
+    }
+    let mut data = String::from("{\"archives\":[\n");
+    for (i, v) in archives.iter().enumerate() {
+        if i > 0 {
+            data.push_str(",\n");
+        }
+        data.push_str(&serde_json::to_string(v).map_err(|e| LogError::Parse(e.to_string()))?);
+    }
+    data.push_str("\n]}\n");
+    //replace the manifest at once,readers never see a partial file
+    let temp = format!("{}.tmp", path);
+    std::fs::write(&temp, data)?;
+    std::fs::rename(&temp, &path)?;
+    Ok(())
+}
+
+fn entry(archive: &str, stats: &SplitStats) -> Result<Archive, LogError> {
+    let mut hasher = Sha256::new();
+    let mut file = File::open(archive)?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+    let name = archive.replace('\\', "/");
+    let mut sha256 = String::new();
+    for b in hasher.finalize() {
+        let _ = write!(sha256, "{:02x}", b);
+    }
+    Ok(Archive {
+        file: name.rsplit('/').next().unwrap_or_default().to_string(),
+        sha256,
+        size,
+        records: stats.records,
+        first: stats.first.map(|v| date::time_precision().format(v)),
+        last: stats.last.map(|v| date::time_precision().format(v)),
+    })
+}
@@ -0,0 +1,169 @@
+//! the memory-mapped file appender,
+//! the records are copied into a pre-sized mmap region instead of write(2),the region grows by `chunk` when full.
+//!
+//! crash consistency:
+//! * a process crash loses nothing,the mapped pages belong to the kernel and are written back later
+//! * a power failure loses the records written after the last sync,call `fast_log::flush` with
+//!   `Config::fsync`,or `LogAppender::try_sync` for an explicit msync
+//! * after a crash the file keeps the zero filled tail of the region,it is skipped when the file is reopened,
+//!   a clean exit truncates the file to the written length
+#![allow(unsafe_code)]
+
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use crate::consts::LogSize;
+use crate::diagnostic;
+use crate::encoding;
+use crate::error::LogError;
+use memmap2::MmapMut;
+use std::cell::RefCell;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom};
+
+pub struct MmapAppender {
+    inner: RefCell<MmapFile>,
+    chunk: u64,
+}
+
+struct MmapFile {
+    file: File,
+    map: Option<MmapMut>,
+    /// the written length
+    len: u64,
+    /// the mapped length
+    cap: u64,
+}
+
+impl MmapAppender {
+    /// append into `path`,the region grows by `chunk`(for example LogSize::MB(64))
+    pub fn new(path: &str, chunk: LogSize) -> Result<Self, LogError> {
+        let path = path.replace('\\', "/");
+        if let Some(right) = path.rfind('/') {
+            std::fs::create_dir_all(&path[0..right])?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        let len = data_len(&mut file)?;
+        let chunk = (chunk.get_len() as u64).max(4096);
+        let mut inner = MmapFile {
+            file,
+            map: None,
+            len,
+            cap: 0,
+        };
+        inner.remap(len + chunk)?;
+        Ok(Self {
+            inner: RefCell::new(inner),
+            chunk,
+        })
+    }
+}
+
+impl MmapFile {
+    fn remap(&mut self, cap: u64) -> Result<(), LogError> {
+        //unmap before resizing the file,windows can't resize a mapped file
+        if let Some(map) = self.map.take() {
+            map.flush_async()?;
+        }
+        self.file.set_len(cap)?;
+        //the file is opened read/write and only this appender resizes it
+        self.map = Some(unsafe { MmapMut::map_mut(&self.file)? });
+        self.cap = cap;
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8], chunk: u64) -> Result<(), LogError> {
+        let end = self.len + data.len() as u64;
+        if end > self.cap {
+            self.remap(end + chunk)?;
+        }
+        if let Some(map) = self.map.as_mut() {
+            map[self.len as usize..end as usize].copy_from_slice(data);
+            self.len = end;
+        }
+        Ok(())
+    }
+}
+
+/// the length without the zero filled tail of a crashed region
+fn data_len(file: &mut File) -> Result<u64, LogError> {
+    let mut end = file.metadata()?.len();
+    let mut buf = vec![0u8; 64 * 1024];
+    while end > 0 {
+        let start = end.saturating_sub(buf.len() as u64);
+        let n = (end - start) as usize;
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut buf[..n])?;
+        match buf[..n].iter().rposition(|v| *v != 0) {
+            Some(i) => return Ok(start + i as u64 + 1),
+            None => end = start,
+        }
+    }
+    Ok(0)
+}
+
+impl LogAppender for MmapAppender {
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        if let Err(e) = self.try_do_logs(records) {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
+        let mut buf = String::new();
+        for x in records {
+            if x.command == Command::CommandRecord {
+                buf.push_str(&x.formated);
+            }
+        }
+        if buf.is_empty() {
+            return Ok(());
+        }
+        self.inner
+            .borrow_mut()
+            .write(&encoding::encode(&buf), self.chunk)
+    }
+
+    fn flush(&self) {
+        if let Some(map) = self.inner.borrow().map.as_ref() {
+            if let Err(e) = map.flush_async() {
+                diagnostic::error(LogError::from(e));
+            }
+        }
+    }
+
+    fn sync(&self) {
+        if let Err(e) = self.try_sync() {
+            diagnostic::error(e);
+        }
+    }
+
+    /// msync the written records
+    fn try_sync(&self) -> Result<(), LogError> {
+        let inner = self.inner.borrow();
+        match inner.map.as_ref() {
+            Some(map) => map
+                .flush_range(0, inner.len as usize)
+                .map_err(LogError::from),
+            None => Ok(()),
+        }
+    }
+
+    fn close(&self) {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(map) = inner.map.take() {
+            if let Err(e) = map.flush() {
+                diagnostic::error(LogError::from(e));
+            }
+        }
+        //a clean exit leaves no zero filled tail
+        let len = inner.len;
+        if let Err(e) = inner.file.set_len(len) {
+            diagnostic::error(LogError::from(e));
+        }
+        inner.cap = len;
+    }
+}
@@ -1,5 +1,37 @@
 pub mod console;
+#[cfg(target_os = "linux")]
+pub mod direct_io;
+#[cfg(feature = "encrypt")]
+pub mod encrypt;
+pub mod fallback;
 pub mod file;
 pub mod file_loop;
 pub mod file_split;
+pub mod formatted;
+#[cfg(feature = "gelf")]
+pub mod gelf;
+#[cfg(feature = "gzip")]
+pub mod gzip_file;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(all(feature = "logcat", target_os = "android"))]
+pub mod logcat;
+#[cfg(feature = "manifest")]
+pub mod manifest;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+#[cfg(all(feature = "os_log", target_vendor = "apple"))]
+pub mod os_log;
+#[cfg(feature = "otel")]
+pub mod otel;
 pub mod packer;
+pub mod ring_buffer;
+pub mod router;
+#[cfg(feature = "s3")]
+pub mod s3;
+#[cfg(unix)]
+pub mod unix_socket;
+#[cfg(all(feature = "web-console", target_arch = "wasm32", target_os = "unknown"))]
+pub mod web_console;
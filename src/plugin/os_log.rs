@@ -0,0 +1,49 @@
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use log::Level;
+use oslog::OsLog;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// write the records into the Apple unified logging(Console.app,`log stream`),
+/// the subsystem is fixed(for example "com.example.app"),the category is the target of the record,
+/// Trace/Debug => debug,Info => info,Warn => default,Error => error.
+/// the unified logging shows the time and the level itself,so the message is the args of the record
+/// you need enable fast_log = { ... ,features=["os_log"]}
+pub struct OsLogAppender {
+    subsystem: String,
+    /// the log of each category
+    logs: RefCell<HashMap<String, OsLog>>,
+}
+
+impl OsLogAppender {
+    pub fn new(subsystem: &str) -> Self {
+        Self {
+            subsystem: subsystem.to_string(),
+            logs: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+fn level(level: Level) -> oslog::Level {
+    match level {
+        Level::Error => oslog::Level::Error,
+        Level::Warn => oslog::Level::Default,
+        Level::Info => oslog::Level::Info,
+        Level::Debug | Level::Trace => oslog::Level::Debug,
+    }
+}
+
+impl LogAppender for OsLogAppender {
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        let mut logs = self.logs.borrow_mut();
+        for x in records {
+            if x.command != Command::CommandRecord {
+                continue;
+            }
+            if !logs.contains_key(&x.target) {
+                logs.insert(x.target.clone(), OsLog::new(&self.subsystem, &x.target));
+            }
+            logs[&x.target].with_level(level(x.level), &x.args);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! the OpenTelemetry logs of the records,exported by [`HttpAppender::otlp`] in the OTLP/HTTP JSON encoding.
+//! * severity => severityNumber/severityText(TRACE 1,DEBUG 5,INFO 9,WARN 13,ERROR 17)
+//! * the trace id and the span id of the record => traceId/spanId
+//! * args => body
+//! * the key-values of the record,target,module path,file,line and thread => attributes
+//! * the static resource attributes(service.name...),and host.name/process.pid if captured
+use crate::appender::{FastLogRecord, KvValue};
+use crate::formats::escape_json;
+use crate::plugin::http::{BodyTemplate, HttpAppender};
+use log::Level;
+use std::fmt::Write as _;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+impl HttpAppender {
+    /// export the records to an OTLP/HTTP collector,for example "http://localhost:4318/v1/logs",
+    /// batched by `bulk_size` and `flush_interval`
+    /// you need enable fast_log = { ... ,features=["otel"]}
+    /// ```rust,no_run
+    /// use fast_log::plugin::http::HttpAppender;
+    /// use fast_log::Config;
+    /// fast_log::init(Config::new().custom(
+    ///     HttpAppender::otlp("http://localhost:4318/v1/logs", "checkout")
+    ///         .resource("deployment.environment", "prod"),
+    /// ));
+    /// ```
+    pub fn otlp(endpoint: &str, service_name: &str) -> Self {
+        Self::new(
+            endpoint,
+            BodyTemplate::Otlp {
+                resource: vec![("service.name".to_string(), service_name.to_string())],
+            },
+        )
+    }
+
+    /// add a resource attribute of the OTLP template
+    pub fn resource(mut self, key: &str, value: &str) -> Self {
+        if let BodyTemplate::Otlp { resource } = &mut self.template {
+            resource.push((key.to_string(), value.to_string()));
+        }
+        self
+    }
+}
+
+fn severity(level: Level) -> u8 {
+    match level {
+        Level::Trace => 1,
+        Level::Debug => 5,
+        Level::Info => 9,
+        Level::Warn => 13,
+        Level::Error => 17,
+    }
+}
+
+fn unix_nanos(now: SystemTime) -> u128 {
+    now.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+/// append `"key":{"stringValue":"value"}` as an OTLP KeyValue
+fn push_attribute(body: &mut String, key: &str, value: &str) {
+    if !body.ends_with('[') {
+        body.push(',');
+    }
+    body.push_str("{\"key\":\"");
+    escape_json(body, key);
+    body.push_str("\",\"value\":{\"stringValue\":\"");
+    escape_json(body, value);
+    body.push_str("\"}}");
+}
+
+/// the attribute of a typed value,the int64 is a JSON string in OTLP,
+/// the u64 over i64::MAX and the non-finite f64 are strings
+fn push_value(body: &mut String, key: &str, value: &KvValue) {
+    match value {
+        KvValue::Str(v) => return push_attribute(body, key, v),
+        KvValue::U64(v) if *v > i64::MAX as u64 => {
+            return push_attribute(body, key, &v.to_string())
+        }
+        KvValue::F64(v) if !v.is_finite() => return push_attribute(body, key, &v.to_string()),
+        _ => {}
+    }
+    if !body.ends_with('[') {
+        body.push(',');
+    }
+    body.push_str("{\"key\":\"");
+    escape_json(body, key);
+    body.push_str("\",\"value\":{");
+    match value {
+        KvValue::Bool(v) => write!(body, "\"boolValue\":{}", v),
+        KvValue::F64(v) => write!(body, "\"doubleValue\":{}", v),
+        v => write!(body, "\"intValue\":\"{}\"", v),
+    };
+    body.push_str("}}");
+}
+
+/// the ExportLogsServiceRequest of the records
+pub fn otlp_json(records: &[FastLogRecord], resource: &[(String, String)]) -> String {
+    let observed = unix_nanos(crate::now());
+    let mut body = String::from("{\"resourceLogs\":[{\"resource\":{\"attributes\":[");
+    for (k, v) in resource {
+        push_attribute(&mut body, k, v);
+    }
+    if let Some(x) = records.first() {
+        if let Some(hostname) = &x.hostname {
+            push_attribute(&mut body, "host.name", hostname);
+        }
+        if let Some(pid) = x.pid {
+            push_attribute(&mut body, "process.pid", &pid.to_string());
+        }
+    }
+    body.push_str("]},\"scopeLogs\":[{\"scope\":{\"name\":\"fast_log\"},\"logRecords\":[");
+    for (i, x) in records.iter().enumerate() {
+        if i > 0 {
+            body.push(',');
+        }
+        write!(
+            body,
+            "{{\"timeUnixNano\":\"{}\",\"observedTimeUnixNano\":\"{}\",\"severityNumber\":{},\"severityText\":\"{}\",\"body\":{{\"stringValue\":\"",
+            unix_nanos(x.now),
+            observed,
+            severity(x.level),
+            x.level
+        );
+        escape_json(&mut body, &x.args);
+        body.push_str("\"},");
+        //the span id of a tracing span alone doesn't belong to a trace
+        if let (Some(trace_id), Some(span_id)) = (x.trace_id, x.span_id) {
+            write!(
+                body,
+                "\"traceId\":\"{:032x}\",\"spanId\":\"{:016x}\",",
+                trace_id, span_id
+            );
+        }
+        body.push_str("\"attributes\":[");
+        for (k, v) in &x.kv {
+            push_value(&mut body, k, v);
+        }
+        push_attribute(&mut body, "log.target", &x.target);
+        if !x.module_path.is_empty() {
+            push_attribute(&mut body, "code.namespace", &x.module_path);
+        }
+        if !x.file.is_empty() {
+            push_attribute(&mut body, "code.filepath", &x.file);
+        }
+        if let Some(line) = x.line {
+            body.push_str(",{\"key\":\"code.lineno\",\"value\":{\"intValue\":\"");
+            write!(body, "{}\"}}}}", line);
+        }
+        if !x.thread_name.is_empty() {
+            push_attribute(&mut body, "thread.name", &x.thread_name);
+        }
+        body.push_str("]}");
+    }
+    body.push_str("]}]}]}");
+    body
+}
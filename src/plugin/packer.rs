@@ -1,6 +1,13 @@
 use crate::error::LogError;
 use crate::plugin::file_split::Packer;
 use std::fs::File;
+#[cfg(any(
+    feature = "zip",
+    feature = "lz4",
+    feature = "gzip",
+    feature = "encrypt"
+))]
+use std::sync::Arc;
 
 /// keep temp{date}.log
 pub struct LogPacker {}
@@ -15,6 +22,39 @@ impl Packer for LogPacker {
     }
 }
 
+/// the staging path of an archive,the packer writes `{archive}.tmp` and renames it when finish,
+/// so tailing tools and uploaders never see a half-written archive
+pub fn staging_path(archive_path: &str) -> String {
+    format!("{}.tmp", archive_path)
+}
+
+/// create the staging file of the archive
+#[cfg(any(feature = "zip", feature = "lz4", feature = "gzip"))]
+pub(crate) fn create_staging(pack_name: &str, archive_path: &str) -> Result<File, LogError> {
+    let path = staging_path(archive_path);
+    File::create(&path).map_err(|e| {
+        LogError::Packer(
+            pack_name.to_string(),
+            Arc::new(std::io::Error::new(
+                e.kind(),
+                format!("create {} fail:{}", path, e),
+            )),
+        )
+    })
+}
+
+/// atomic rename the finished staging file to the archive
+#[cfg(any(
+    feature = "zip",
+    feature = "lz4",
+    feature = "gzip",
+    feature = "encrypt"
+))]
+pub(crate) fn finish_staging(pack_name: &str, archive_path: &str) -> Result<(), LogError> {
+    std::fs::rename(staging_path(archive_path), archive_path)
+        .map_err(|e| LogError::Packer(pack_name.to_string(), Arc::new(e)))
+}
+
 #[cfg(feature = "zip")]
 use zip::result::ZipResult;
 #[cfg(feature = "zip")]
@@ -38,15 +78,7 @@ impl Packer for ZipPacker {
             log_name = log_name[(v + 1)..log_name.len()].to_string();
         }
         let zip_path = log_file_path.replace(".log", ".zip");
-        let zip_file = std::fs::File::create(&zip_path);
-        if zip_file.is_err() {
-            return Err(LogError::from(format!(
-                "[fast_log] create(&{}) fail:{}",
-                zip_path,
-                zip_file.err().unwrap()
-            )));
-        }
-        let zip_file = zip_file.unwrap();
+        let zip_file = create_staging(self.pack_name(), &zip_path)?;
         //write zip bytes data
         let mut zip = zip::ZipWriter::new(zip_file);
         zip.start_file(log_name, FileOptions::default());
@@ -56,11 +88,12 @@ impl Packer for ZipPacker {
         let finish: ZipResult<File> = zip.finish();
         if finish.is_err() {
             //println!("[fast_log] try zip fail{:?}", finish.err());
-            return Err(LogError::from(format!(
-                "[fast_log] try zip fail{:?}",
-                finish.err()
-            )));
+            return Err(LogError::Packer(
+                self.pack_name().to_string(),
+                Arc::new(finish.err().unwrap()),
+            ));
         }
+        finish_staging(self.pack_name(), &zip_path)?;
         return Ok(true);
     }
 }
@@ -69,13 +102,6 @@ impl Packer for ZipPacker {
 #[cfg(feature = "lz4")]
 use lz4::EncoderBuilder;
 
-#[cfg(feature = "lz4")]
-impl From<std::io::Error> for LogError {
-    fn from(arg: std::io::Error) -> Self {
-        LogError::E(arg.to_string())
-    }
-}
-
 /// the zip compress
 #[cfg(feature = "lz4")]
 pub struct LZ4Packer {}
@@ -92,28 +118,24 @@ impl Packer for LZ4Packer {
             log_name = log_name[(v + 1)..log_name.len()].to_string();
         }
         let lz4_path = log_file_path.replace(".log", ".lz4");
-        let lz4_file = std::fs::File::create(&lz4_path);
-        if lz4_file.is_err() {
-            return Err(LogError::from(format!(
-                "[fast_log] create(&{}) fail:{}",
-                lz4_path,
-                lz4_file.err().unwrap()
-            )));
-        }
-        let lz4_file = lz4_file.unwrap();
+        let lz4_file = create_staging(self.pack_name(), &lz4_path)?;
         //write lz4 bytes data
 
-        let mut encoder = EncoderBuilder::new().level(0).build(lz4_file)?;
+        let mut encoder = EncoderBuilder::new()
+            .level(0)
+            .build(lz4_file)
+            .map_err(|e| LogError::Packer(self.pack_name().to_string(), Arc::new(e)))?;
         // io::copy(&mut lz4_file, &mut encoder)?;
         //buf reader
         std::io::copy(&mut log_file, &mut encoder);
         let (_output, result) = encoder.finish();
         if result.is_err() {
-            return Err(LogError::from(format!(
-                "[fast_log] try zip fail{:?}",
-                result.err()
-            )));
+            return Err(LogError::Packer(
+                self.pack_name().to_string(),
+                Arc::new(result.err().unwrap()),
+            ));
         }
+        finish_staging(self.pack_name(), &lz4_path)?;
         return Ok(true);
     }
 }
@@ -139,26 +161,19 @@ impl Packer for GZipPacker {
             log_name = log_name[(v + 1)..log_name.len()].to_string();
         }
         let zip_path = log_file_path.replace(".log", ".gz");
-        let zip_file = std::fs::File::create(&zip_path);
-        if zip_file.is_err() {
-            return Err(LogError::from(format!(
-                "[fast_log] create(&{}) fail:{}",
-                zip_path,
-                zip_file.err().unwrap()
-            )));
-        }
-        let zip_file = zip_file.unwrap();
+        let zip_file = create_staging(self.pack_name(), &zip_path)?;
         //write zip bytes data
         let mut zip = GzEncoder::new(zip_file, Compression::default());
         std::io::copy(&mut log_file, &mut zip);
         zip.flush();
         let finish = zip.finish();
         if finish.is_err() {
-            return Err(LogError::from(format!(
-                "[fast_log] try zip fail{:?}",
-                finish.err()
-            )));
+            return Err(LogError::Packer(
+                self.pack_name().to_string(),
+                Arc::new(finish.err().unwrap()),
+            ));
         }
+        finish_staging(self.pack_name(), &zip_path)?;
         return Ok(true);
     }
 }
@@ -0,0 +1,80 @@
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+struct Recent {
+    capacity: usize,
+    records: VecDeque<String>,
+}
+
+/// the records kept by a [`RingBufferAppender`],a handle shared with the appender,see [`dump_recent`]
+#[derive(Clone)]
+pub struct RecentRecords {
+    inner: Arc<Mutex<Recent>>,
+}
+
+impl RecentRecords {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Recent {
+                capacity,
+                records: VecDeque::with_capacity(capacity),
+            })),
+        }
+    }
+}
+
+/// keep the last `capacity` formatted records in memory,see [`dump_recent`]
+pub struct RingBufferAppender {
+    recent: RecentRecords,
+}
+
+impl RingBufferAppender {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_recent(RecentRecords::new(capacity))
+    }
+
+    /// keep the records in `recent`,for example a handle created before the appender
+    pub fn with_recent(recent: RecentRecords) -> Self {
+        Self { recent }
+    }
+
+    /// the handle of the kept records,see [`dump_recent`]
+    pub fn recent(&self) -> RecentRecords {
+        self.recent.clone()
+    }
+}
+
+impl LogAppender for RingBufferAppender {
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        let mut recent = self.recent.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if recent.capacity == 0 {
+            return;
+        }
+        for x in records {
+            if x.command != Command::CommandRecord {
+                continue;
+            }
+            if recent.records.len() >= recent.capacity {
+                recent.records.pop_front();
+            }
+            recent.records.push_back(x.formated.clone());
+        }
+    }
+}
+
+/// the recent formatted records kept by the [`RingBufferAppender`] of `recent`,the oldest first.
+/// for example attach them to a crash report or serve them on a `/debug/logs` endpoint
+/// ```rust
+/// use fast_log::plugin::ring_buffer::RingBufferAppender;
+/// let ring = RingBufferAppender::new(100);
+/// let recent = ring.recent();
+/// fast_log::init(fast_log::Config::new().custom(ring)).unwrap();
+/// log::info!("kept by the ring buffer");
+/// log::logger().flush();
+/// assert_eq!(fast_log::dump_recent(&recent).len(), 1);
+/// ```
+pub fn dump_recent(recent: &RecentRecords) -> Vec<String> {
+    let recent = recent.inner.lock().unwrap_or_else(|e| e.into_inner());
+    recent.records.iter().cloned().collect()
+}
@@ -0,0 +1,122 @@
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use crate::diagnostic;
+use crate::error::LogError;
+
+/// route the records by the target prefix to different appenders,each with its own rotation settings,
+/// the longest matching prefix wins,the records matching no prefix go to the default appender(if any)
+/// ```rust
+/// use fast_log::consts::LogSize;
+/// use fast_log::plugin::file::FileAppender;
+/// use fast_log::plugin::file_split::{FileSplitAppender, RollingType};
+/// use fast_log::plugin::packer::LogPacker;
+/// use fast_log::plugin::router::RouterAppender;
+/// let router = RouterAppender::new()
+///     .route("access::", FileAppender::new("target/logs/access.log"))
+///     .route(
+///         "audit::",
+///         FileSplitAppender::new(
+///             "target/logs/audit/",
+///             LogSize::MB(10),
+///             RollingType::All,
+///             Box::new(LogPacker {}),
+///         ),
+///     )
+///     .default_route(FileAppender::new("target/logs/app.log"));
+/// let config = fast_log::Config::new().router(router);
+/// ```
+#[derive(Default)]
+pub struct RouterAppender {
+    /// sorted by the prefix length,longest first
+    routes: Vec<(String, Box<dyn LogAppender>)>,
+    default: Option<Box<dyn LogAppender>>,
+}
+
+impl RouterAppender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// write the records whose target starts with `prefix` to `appender`
+    pub fn route<A: LogAppender + 'static>(mut self, prefix: &str, appender: A) -> Self {
+        self.routes.push((prefix.to_string(), Box::new(appender)));
+        self.routes
+            .sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        self
+    }
+
+    /// write the records matching no prefix to `appender`,else they are dropped
+    pub fn default_route<A: LogAppender + 'static>(mut self, appender: A) -> Self {
+        self.default = Some(Box::new(appender));
+        self
+    }
+
+    fn appenders(&self) -> impl Iterator<Item = &Box<dyn LogAppender>> {
+        self.routes
+            .iter()
+            .map(|(_, a)| a)
+            .chain(self.default.iter())
+    }
+
+    /// the index of the appender of `target`,routes.len() is the default
+    fn index_of(&self, target: &str) -> usize {
+        self.routes
+            .iter()
+            .position(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .unwrap_or(self.routes.len())
+    }
+}
+
+impl LogAppender for RouterAppender {
+    fn open(&self) {
+        for x in self.appenders() {
+            x.open();
+        }
+    }
+
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        if let Err(e) = self.try_do_logs(records) {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
+        let mut batches: Vec<Vec<FastLogRecord>> = vec![vec![]; self.routes.len() + 1];
+        for x in records {
+            if x.command == Command::CommandRecord {
+                batches[self.index_of(&x.target)].push(x.clone());
+            }
+        }
+        let mut result = Ok(());
+        for (batch, appender) in batches.iter().zip(self.appenders()) {
+            if batch.is_empty() {
+                continue;
+            }
+            if let Err(e) = appender.try_do_logs(batch) {
+                if result.is_ok() {
+                    result = Err(e);
+                } else {
+                    diagnostic::error(e);
+                }
+            }
+        }
+        result
+    }
+
+    fn flush(&self) {
+        for x in self.appenders() {
+            x.flush();
+        }
+    }
+
+    fn sync(&self) {
+        for x in self.appenders() {
+            x.sync();
+        }
+    }
+
+    fn close(&self) {
+        for x in self.appenders() {
+            x.close();
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::error::LogError;
+use crate::plugin::file_split::Packer;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// you need enable fast_log = { ... ,features=["s3"]}
+/// pack the rolled file with the inner packer,then upload the archive to `bucket/prefix` of S3
+/// or an S3 compatible object storage(minio,ceph...),
+/// so file_split becomes a complete archival pipeline.
+/// the credentials default to the env vars AWS_ACCESS_KEY_ID,AWS_SECRET_ACCESS_KEY and AWS_SESSION_TOKEN
+/// ```rust,no_run
+/// use fast_log::consts::LogSize;
+/// use fast_log::plugin::file_split::RollingType;
+/// use fast_log::plugin::packer::LogPacker;
+/// use fast_log::plugin::s3::S3Packer;
+/// let packer = S3Packer::new(LogPacker {}, "my-bucket", "us-east-1")
+///     .prefix("logs/app/")
+///     .delete_local(true);
+/// fast_log::init(fast_log::Config::new().file_split(
+///     "target/logs/",
+///     LogSize::MB(100),
+///     RollingType::KeepNum(5),
+///     packer,
+/// ))
+/// .unwrap();
+/// ```
+pub struct S3Packer {
+    inner: Box<dyn Packer>,
+    pub bucket: String,
+    pub region: String,
+    pub prefix: String,
+    /// None is `https://{bucket}.s3.{region}.amazonaws.com`,Some is the path style `{endpoint}/{bucket}`
+    pub endpoint: Option<String>,
+    pub access_key: String,
+    pub secret_key: String,
+    pub session_token: Option<String>,
+    /// delete the local archive after the upload
+    pub delete_local: bool,
+    agent: ureq::Agent,
+}
+
+impl S3Packer {
+    pub fn new<P: Packer + 'static>(inner: P, bucket: &str, region: &str) -> Self {
+        Self {
+            inner: Box::new(inner),
+            bucket: bucket.to_string(),
+            region: region.to_string(),
+            prefix: String::new(),
+            endpoint: None,
+            access_key: std::env::var("AWS_ACCESS_KEY_ID").unwrap_or_default(),
+            secret_key: std::env::var("AWS_SECRET_ACCESS_KEY").unwrap_or_default(),
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+            delete_local: false,
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(300))
+                .build(),
+        }
+    }
+
+    /// the key prefix of the archives,for example `logs/app/`
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    /// the endpoint of an S3 compatible storage,for example `http://127.0.0.1:9000`
+    pub fn endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = Some(endpoint.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn credentials(mut self, access_key: &str, secret_key: &str) -> Self {
+        self.access_key = access_key.to_string();
+        self.secret_key = secret_key.to_string();
+        self
+    }
+
+    pub fn session_token(mut self, token: &str) -> Self {
+        self.session_token = Some(token.to_string());
+        self
+    }
+
+    /// delete the local archive after the upload,default is false
+    pub fn delete_local(mut self, delete_local: bool) -> Self {
+        self.delete_local = delete_local;
+        self
+    }
+
+    fn error<E: std::error::Error + Send + Sync + 'static>(&self, e: E) -> LogError {
+        LogError::Packer(self.pack_name().to_string(), Arc::new(e))
+    }
+
+    /// put the file to `key`,signed by the AWS signature version 4
+    pub fn upload(&self, path: &str, key: &str) -> Result<(), LogError> {
+        let mut hasher = Sha256::new();
+        let mut file = File::open(path)?;
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut len = 0u64;
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            len += n as u64;
+        }
+        let payload_hash = hex(&hasher.finalize());
+        let (url, host, uri) = match &self.endpoint {
+            None => {
+                let host = format!("{}.s3.{}.amazonaws.com", self.bucket, self.region);
+                let uri = format!("/{}", uri_encode(key));
+                (format!("https://{}{}", host, uri), host, uri)
+            }
+            Some(endpoint) => {
+                let host = endpoint
+                    .split_once("://")
+                    .map(|(_, v)| v)
+                    .unwrap_or(endpoint)
+                    .to_string();
+                let uri = format!("/{}/{}", uri_encode(&self.bucket), uri_encode(key));
+                (format!("{}{}", endpoint, uri), host, uri)
+            }
+        };
+        let now = amz_date(SystemTime::now());
+        let mut headers = vec![
+            ("host", host),
+            ("x-amz-content-sha256", payload_hash.clone()),
+            ("x-amz-date", now.clone()),
+        ];
+        if let Some(token) = &self.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        let authorization = sign_v4(
+            "PUT",
+            &uri,
+            &headers,
+            &payload_hash,
+            &now,
+            &self.region,
+            &self.access_key,
+            &self.secret_key,
+        );
+        let mut req = self
+            .agent
+            .put(&url)
+            .set("Authorization", &authorization)
+            .set("Content-Length", &len.to_string());
+        for (k, v) in &headers {
+            if *k != "host" {
+                req = req.set(k, v);
+            }
+        }
+        req.send(File::open(path)?)
+            .map_err(|e| self.error(Box::new(e)))?;
+        Ok(())
+    }
+}
+
+impl Packer for S3Packer {
+    fn pack_name(&self) -> &'static str {
+        self.inner.pack_name()
+    }
+
+    fn do_pack(&self, log_file: File, log_file_path: &str) -> Result<bool, LogError> {
+        let remove = self.inner.do_pack(log_file, log_file_path)?;
+        let archive = self.inner.archive_path(log_file_path);
+        let name = archive.replace('\\', "/");
+        let name = name.rsplit('/').next().unwrap_or_default();
+        self.upload(&archive, &format!("{}{}", self.prefix, name))?;
+        if self.delete_local {
+            if archive == log_file_path {
+                //the log file is the archive,removed by the caller
+                return Ok(true);
+            }
+            std::fs::remove_file(&archive)?;
+        }
+        Ok(remove)
+    }
+
+    fn retry(&self) -> i32 {
+        self.inner.retry()
+    }
+
+    fn archive_path(&self, log_file_path: &str) -> String {
+        self.inner.archive_path(log_file_path)
+    }
+}
+
+/// the authorization header of the AWS signature version 4,
+/// `headers` are the lowercase signed headers
+#[allow(clippy::too_many_arguments)]
+fn sign_v4(
+    method: &str,
+    uri: &str,
+    headers: &[(&str, String)],
+    payload_hash: &str,
+    amz_date: &str,
+    region: &str,
+    access_key: &str,
+    secret_key: &str,
+) -> String {
+    let mut headers = headers.to_vec();
+    headers.sort_by(|a, b| a.0.cmp(b.0));
+    let mut canonical = format!("{}\n{}\n\n", method, uri);
+    for (k, v) in &headers {
+        let _ = writeln!(canonical, "{}:{}", k, v.trim());
+    }
+    let signed_headers = headers
+        .iter()
+        .map(|(k, _)| *k)
+        .collect::<Vec<_>>()
+        .join(";");
+    let _ = write!(canonical, "\n{}\n{}", signed_headers, payload_hash);
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical.as_bytes()))
+    );
+    let mut key = hmac(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    for v in [region, "s3", "aws4_request"] {
+        key = hmac(&key, v.as_bytes());
+    }
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key,
+        scope,
+        signed_headers,
+        hex(&hmac(&key, string_to_sign.as_bytes()))
+    )
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(data: &[u8]) -> String {
+    let mut s = String::with_capacity(data.len() * 2);
+    for b in data {
+        let _ = write!(s, "{:02x}", b);
+    }
+    s
+}
+
+/// encode all but the unreserved characters and '/'
+fn uri_encode(v: &str) -> String {
+    let mut s = String::with_capacity(v.len());
+    for b in v.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                s.push(b as char)
+            }
+            _ => {
+                let _ = write!(s, "%{:02X}", b);
+            }
+        }
+    }
+    s
+}
+
+/// the UTC time as yyyyMMddTHHmmssZ
+fn amz_date(now: SystemTime) -> String {
+    let secs = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (year, month, day) = crate::embedded::civil_from_days((secs / 86400) as i64);
+    let rem = secs % 86400;
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
@@ -0,0 +1,119 @@
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use crate::diagnostic;
+use crate::encoding;
+use crate::error::LogError;
+use std::cell::RefCell;
+use std::io::Write;
+use std::os::unix::net::{UnixDatagram, UnixStream};
+use std::path::PathBuf;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SocketType {
+    /// SOCK_STREAM,the records are written in order,reconnect after a failure
+    Stream,
+    /// SOCK_DGRAM,one datagram per record
+    Datagram,
+}
+
+enum Socket {
+    Stream(UnixStream),
+    Datagram(UnixDatagram),
+}
+
+/// write the formatted records to a unix domain socket,
+/// for local collectors like vector or fluent-bit
+/// for example:
+/// ```rust,no_run
+/// use fast_log::plugin::unix_socket::UnixSocketAppender;
+/// use fast_log::Config;
+/// fast_log::init(Config::new().custom(UnixSocketAppender::stream("/var/run/vector.sock")));
+/// ```
+pub struct UnixSocketAppender {
+    pub path: PathBuf,
+    pub socket_type: SocketType,
+    socket: RefCell<Option<Socket>>,
+}
+
+impl UnixSocketAppender {
+    pub fn new(path: &str, socket_type: SocketType) -> Self {
+        Self {
+            path: PathBuf::from(path),
+            socket_type,
+            socket: RefCell::new(None),
+        }
+    }
+
+    pub fn stream(path: &str) -> Self {
+        Self::new(path, SocketType::Stream)
+    }
+
+    pub fn datagram(path: &str) -> Self {
+        Self::new(path, SocketType::Datagram)
+    }
+
+    fn connect(&self) -> std::io::Result<Socket> {
+        match self.socket_type {
+            SocketType::Stream => Ok(Socket::Stream(UnixStream::connect(&self.path)?)),
+            SocketType::Datagram => {
+                let socket = UnixDatagram::unbound()?;
+                socket.connect(&self.path)?;
+                Ok(Socket::Datagram(socket))
+            }
+        }
+    }
+
+    fn write(&self, records: &[FastLogRecord]) -> std::io::Result<()> {
+        let mut socket = self.socket.borrow_mut();
+        if socket.is_none() {
+            *socket = Some(self.connect()?);
+        }
+        let r = match socket.as_mut().unwrap() {
+            Socket::Stream(stream) => {
+                let mut buf = Vec::new();
+                for x in records {
+                    if x.command == Command::CommandRecord {
+                        buf.extend_from_slice(&encoding::encode(&x.formated));
+                    }
+                }
+                stream.write_all(&buf)
+            }
+            Socket::Datagram(datagram) => records
+                .iter()
+                .filter(|x| x.command == Command::CommandRecord)
+                .try_for_each(|x| datagram.send(&encoding::encode(&x.formated)).map(|_| ())),
+        };
+        if r.is_err() {
+            //reconnect on the next write
+            *socket = None;
+        }
+        r
+    }
+}
+
+impl LogAppender for UnixSocketAppender {
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        if let Err(e) = self.try_do_logs(records) {
+            diagnostic::error(e);
+        }
+    }
+
+    fn try_do_logs(&self, records: &[FastLogRecord]) -> Result<(), LogError> {
+        self.write(records).map_err(|e| {
+            LogError::from(format!(
+                "[fast_log] write unix socket {} fail:{}",
+                self.path.display(),
+                e
+            ))
+        })
+    }
+
+    fn flush(&self) {
+        if let Some(Socket::Stream(stream)) = self.socket.borrow_mut().as_mut() {
+            let _ = stream.flush();
+        }
+    }
+
+    fn close(&self) {
+        self.socket.borrow_mut().take();
+    }
+}
@@ -0,0 +1,31 @@
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use log::Level;
+use wasm_bindgen::JsValue;
+
+/// write into the browser console,error => console.error,warn => console.warn,others => console.log
+/// you need enable fast_log = { ... ,features=["web-console"]}
+#[derive(Default)]
+pub struct WebConsoleAppender {}
+
+impl WebConsoleAppender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl LogAppender for WebConsoleAppender {
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        for x in records {
+            if x.command != Command::CommandRecord {
+                continue;
+            }
+            //the console breaks the lines itself
+            let msg = JsValue::from_str(x.formated.trim_end_matches(['\r', '\n']));
+            match x.level {
+                Level::Error => web_sys::console::error_1(&msg),
+                Level::Warn => web_sys::console::warn_1(&msg),
+                _ => web_sys::console::log_1(&msg),
+            }
+        }
+    }
+}
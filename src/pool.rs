@@ -0,0 +1,42 @@
+use crossbeam::queue::ArrayQueue;
+use once_cell::sync::OnceCell;
+
+/// the strings bigger than this are not recycled
+const MAX_POOLED_CAPACITY: usize = 4096;
+
+static POOL: OnceCell<ArrayQueue<String>> = OnceCell::new();
+
+/// init the recycled string pool of `FastLogRecord` with `size` strings,
+/// so each log doesn't allocate fresh strings for the message and the formatted line.
+/// the pool is global,the strings are taken on the logging threads and recycled on the appender threads.
+/// 0 is disable the pool,the pool keeps the size of the first init
+pub fn init(size: usize) {
+    if size > 0 {
+        POOL.get_or_init(|| ArrayQueue::new(size));
+    }
+}
+
+/// take an empty string from the pool,or a new string if the pool is empty
+pub fn take() -> String {
+    match POOL.get() {
+        None => String::new(),
+        Some(pool) => pool.pop().unwrap_or_default(),
+    }
+}
+
+/// take a string from the pool filled with `v`
+pub fn copy(v: &str) -> String {
+    let mut s = take();
+    s.push_str(v);
+    s
+}
+
+/// give back a string to the pool
+pub fn recycle(mut s: String) {
+    if let Some(pool) = POOL.get() {
+        if s.capacity() > 0 && s.capacity() <= MAX_POOLED_CAPACITY {
+            s.clear();
+            let _ = pool.push(s);
+        }
+    }
+}
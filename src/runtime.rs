@@ -7,6 +7,8 @@ pub type SendError<T> = crossbeam_channel::SendError<T>;
 #[cfg(feature = "runtime_thread")]
 pub type RecvError = crossbeam_channel::RecvError;
 #[cfg(feature = "runtime_thread")]
+pub type RecvTimeoutError = crossbeam_channel::RecvTimeoutError;
+#[cfg(feature = "runtime_thread")]
 pub type JoinHandle<T> = std::thread::JoinHandle<T>;
 #[cfg(feature = "runtime_thread")]
 pub type WaitGroup = crossbeam_utils::sync::WaitGroup;
@@ -36,18 +38,54 @@ pub fn spawn_stack_size<F>(f: F, stack_size: usize) -> JoinHandle<()>
 }
 
 
-pub fn try_send_num<T>(sender: &Sender<T>, num: usize, mut target: T) {
+pub fn try_send_num<T>(sender: &Sender<T>, num: usize, mut target: T) -> Result<(), SendError<T>> {
     let mut trys = 0;
     loop {
         if trys > num {
-            sender.send(target);
-            break;
+            return sender.send(target);
         }
         if let Err(e) = sender.try_send(target) {
             trys += 1;
             target = e.into_inner();
         } else {
-            break;
+            return Ok(());
         }
     }
-}
\ No newline at end of file
+}
+
+/// the monotonic clock,`std::time::Instant::now()` panics on wasm32-unknown-unknown
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) use web_time::Instant;
+
+/// the current time of the clock,see [`Config::clock`](crate::Config::clock)
+pub fn now() -> std::time::SystemTime {
+    match crate::clock::source_now() {
+        Some(v) => v,
+        None => crate::date::coarse_now().unwrap_or_else(system_now),
+    }
+}
+
+/// the wall clock,the browser clock on wasm32-unknown-unknown where `SystemTime::now()` panics
+pub(crate) fn system_now() -> std::time::SystemTime {
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    {
+        let since = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .unwrap_or_default();
+        std::time::UNIX_EPOCH + since
+    }
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    std::time::SystemTime::now()
+}
+
+/// the offset of the local time in seconds
+pub fn offset_sec() -> i32 {
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    {
+        -(js_sys::Date::new_0().get_timezone_offset() as i32) * 60
+    }
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fastdate::offset_sec()
+}
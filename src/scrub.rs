@@ -0,0 +1,263 @@
+//! the scrubbers of the records,run after the format and before the appenders,
+//! on the formatted line,the message and the key-values,
+//! so the data-protection rules of a service live in one place of the logger,
+//! also for the appenders sending the fields(gelf,otel,http,logcat,os_log).
+//! see [`Config::scrubber`](crate::Config::scrubber)
+//! ```rust
+//! use fast_log::scrub::{EmailScrubber, IpScrubber, JwtScrubber, Scrubber};
+//! let mut line = "login alice@example.com from 10.0.0.1 token eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.c2ln".to_string();
+//! EmailScrubber::new().scrub(&mut line);
+//! IpScrubber::new().scrub(&mut line);
+//! JwtScrubber::new().scrub(&mut line);
+//! assert_eq!(line, "login *** from *** token ***");
+//! ```
+use crate::appender::{FastLogRecord, KvValue};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+/// rewrite the formatted record in place
+pub trait Scrubber: Send + Sync {
+    fn scrub(&self, line: &mut String);
+
+    /// the lower order runs first,the scrubbers of the same order run in the order they are added.
+    /// default is 0
+    fn order(&self) -> i32 {
+        0
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static SCRUBBERS: RwLock<Vec<Box<dyn Scrubber>>> = RwLock::new(Vec::new());
+
+/// set the global scrubbers,sorted by `Scrubber::order`
+pub fn set_scrubbers(mut scrubbers: Vec<Box<dyn Scrubber>>) {
+    scrubbers.sort_by_key(|v| v.order());
+    ENABLED.store(!scrubbers.is_empty(), Ordering::SeqCst);
+    *SCRUBBERS.write().unwrap_or_else(|e| e.into_inner()) = scrubbers;
+}
+
+/// take back the global scrubbers
+pub fn take_scrubbers() -> Vec<Box<dyn Scrubber>> {
+    ENABLED.store(false, Ordering::SeqCst);
+    std::mem::take(&mut *SCRUBBERS.write().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// run the global scrubbers on the formatted record
+pub fn scrub(line: &mut String) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    for s in SCRUBBERS.read().unwrap_or_else(|e| e.into_inner()).iter() {
+        s.scrub(line);
+    }
+}
+
+/// run the global scrubbers on the formatted line,the message and the key-values of the record
+pub fn scrub_record(x: &mut FastLogRecord) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    for s in SCRUBBERS.read().unwrap_or_else(|e| e.into_inner()).iter() {
+        s.scrub(&mut x.formated);
+        s.scrub(&mut x.args);
+        for (_, v) in x.kv.iter_mut() {
+            if let KvValue::Str(v) = v {
+                s.scrub(v);
+            }
+        }
+    }
+}
+
+/// replace the byte ranges of `line` found by `find(line, from)` with `replacement`
+fn replace_all(
+    line: &mut String,
+    replacement: &str,
+    find: impl Fn(&str, usize) -> Option<(usize, usize)>,
+) {
+    let mut from = 0;
+    let mut out: Option<String> = None;
+    let mut last = 0;
+    while let Some((start, end)) = find(line, from) {
+        let out = out.get_or_insert_with(|| String::with_capacity(line.len()));
+        out.push_str(&line[last..start]);
+        out.push_str(replacement);
+        last = end;
+        from = end;
+    }
+    if let Some(mut out) = out {
+        out.push_str(&line[last..]);
+        *line = out;
+    }
+}
+
+/// the start of the run of `allowed` chars ending at `end`
+fn run_start(line: &str, end: usize, allowed: impl Fn(char) -> bool) -> usize {
+    line[..end]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| allowed(*c))
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(end)
+}
+
+/// the end of the run of `allowed` chars starting at `start`
+fn run_end(line: &str, start: usize, allowed: impl Fn(char) -> bool) -> usize {
+    line[start..]
+        .char_indices()
+        .find(|(_, c)| !allowed(*c))
+        .map(|(i, _)| start + i)
+        .unwrap_or(line.len())
+}
+
+/// the email addresses,`name@domain.tld`
+pub struct EmailScrubber {
+    pub replacement: String,
+    pub order: i32,
+}
+
+impl EmailScrubber {
+    pub fn new() -> Self {
+        Self {
+            replacement: "***".to_string(),
+            order: 0,
+        }
+    }
+}
+
+impl Default for EmailScrubber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scrubber for EmailScrubber {
+    fn scrub(&self, line: &mut String) {
+        let local = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+        let domain = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '-';
+        replace_all(line, &self.replacement, |line, mut from| {
+            while let Some(at) = line[from..].find('@').map(|i| from + i) {
+                let start = run_start(line, at, local);
+                let end = run_end(line, at + 1, domain);
+                let host = line[at + 1..end].trim_end_matches('.');
+                if start < at && host.contains('.') && !host.starts_with('.') {
+                    return Some((start, at + 1 + host.len()));
+                }
+                from = at + 1;
+            }
+            None
+        });
+    }
+
+    fn order(&self) -> i32 {
+        self.order
+    }
+}
+
+/// the IPv4 and IPv6 addresses
+pub struct IpScrubber {
+    pub replacement: String,
+    pub order: i32,
+}
+
+impl IpScrubber {
+    pub fn new() -> Self {
+        Self {
+            replacement: "***".to_string(),
+            order: 0,
+        }
+    }
+}
+
+impl Default for IpScrubber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scrubber for IpScrubber {
+    fn scrub(&self, line: &mut String) {
+        let token = |c: char| c.is_ascii_hexdigit() || c == '.' || c == ':';
+        replace_all(line, &self.replacement, |line, mut from| {
+            while from < line.len() {
+                let start = from + line[from..].find(token)?;
+                let end = run_end(line, start, token);
+                //a part of a word,for example "v1.2.3.4"
+                let word = |c: char| c.is_alphanumeric() || c == '_';
+                let bounded = !line[..start].chars().next_back().is_some_and(word)
+                    && !line[end..].chars().next().is_some_and(word);
+                let candidate = line[start..end].trim_end_matches(['.', ':']);
+                let len = match candidate.parse::<IpAddr>() {
+                    Ok(IpAddr::V4(_)) => Some(candidate.len()),
+                    //two groups and a digit at least,so "cafe::db" of a path is not an address
+                    Ok(IpAddr::V6(_)) => (candidate.split(':').filter(|v| !v.is_empty()).count()
+                        >= 2
+                        && candidate.contains(|c: char| c.is_ascii_digit()))
+                    .then_some(candidate.len()),
+                    //with the port
+                    Err(_) => candidate
+                        .split_once(':')
+                        .filter(|(v4, port)| {
+                            v4.parse::<Ipv4Addr>().is_ok()
+                                && port.chars().all(|c| c.is_ascii_digit())
+                        })
+                        .map(|(v4, _)| v4.len()),
+                };
+                if let (true, Some(len)) = (bounded, len) {
+                    return Some((start, start + len));
+                }
+                from = end;
+            }
+            None
+        });
+    }
+
+    fn order(&self) -> i32 {
+        self.order
+    }
+}
+
+/// the JSON web tokens,three base64url segments starting with `eyJ`
+pub struct JwtScrubber {
+    pub replacement: String,
+    pub order: i32,
+}
+
+impl JwtScrubber {
+    pub fn new() -> Self {
+        Self {
+            replacement: "***".to_string(),
+            order: 0,
+        }
+    }
+}
+
+impl Default for JwtScrubber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scrubber for JwtScrubber {
+    fn scrub(&self, line: &mut String) {
+        let base64url = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+        replace_all(line, &self.replacement, |line, mut from| {
+            while let Some(start) = line[from..].find("eyJ").map(|i| from + i) {
+                let end = run_end(line, start, |c| base64url(c) || c == '.');
+                let token = line[start..end].trim_end_matches('.');
+                let segments: Vec<&str> = token.split('.').collect();
+                if segments.len() == 3 && segments.iter().all(|v| !v.is_empty()) {
+                    return Some((start, start + token.len()));
+                }
+                from = start + 3;
+            }
+            None
+        });
+    }
+
+    fn order(&self) -> i32 {
+        self.order
+    }
+}
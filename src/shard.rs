@@ -0,0 +1,193 @@
+//! the per-thread queues of `ProducerMode::Sharded`.
+//! each producer thread pushes into its own bounded queue instead of the shared channel,
+//! the worker drains the queues before the channel,and drains every queue to empty before a command
+//! of the channel(flush,exit),so the commands sent after a record are processed after it
+use crate::appender::FastLogRecord;
+use crate::runtime::{chan, Instant, Receiver, Sender};
+use crossbeam::channel::Select;
+use crossbeam::queue::ArrayQueue;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::time::Duration;
+
+/// the records of a producer thread the worker hasn't taken yet
+const SHARD_CAPACITY: usize = 1024;
+
+/// try the full queue so many times before waiting for the worker
+const PUSH_TRIES: usize = 3;
+
+/// the producers waiting for the space of their full queues
+static FULL_WAITERS: AtomicUsize = AtomicUsize::new(0);
+
+/// notified by the worker after taking records while producers wait
+static SPACE: (Mutex<()>, Condvar) = (Mutex::new(()), Condvar::new());
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static SHARDS: RwLock<Vec<Arc<ArrayQueue<FastLogRecord>>>> = RwLock::new(Vec::new());
+
+/// the worker is waiting,the first producer pushing a record wakes it
+static SLEEPING: AtomicBool = AtomicBool::new(false);
+
+static WAKE: Lazy<(Sender<()>, Receiver<()>)> = Lazy::new(|| chan(Some(1)));
+
+thread_local! {
+    static LOCAL: Arc<ArrayQueue<FastLogRecord>> = {
+        let shard = Arc::new(ArrayQueue::new(SHARD_CAPACITY));
+        SHARDS
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(shard.clone());
+        shard
+    };
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// push the record into the queue of the current thread,
+/// blocks until the worker takes records while the queue is full like the bounded channel.
+/// gives back the record if the thread is exiting(its queue is destroyed)
+pub fn push(record: FastLogRecord) -> Result<(), Box<FastLogRecord>> {
+    let mut record = Some(record);
+    let pushed = LOCAL.try_with(|shard| {
+        let mut tries = 0;
+        loop {
+            match shard.push(record.take().unwrap()) {
+                Ok(()) => break,
+                Err(v) => {
+                    record = Some(v);
+                    wake();
+                    tries += 1;
+                    if tries > PUSH_TRIES {
+                        wait_space(shard);
+                    }
+                }
+            }
+        }
+    });
+    fence(Ordering::SeqCst);
+    wake();
+    match pushed {
+        Ok(()) => Ok(()),
+        Err(_) => Err(Box::new(record.unwrap())),
+    }
+}
+
+/// push the record into the queue of the current thread without waiting,
+/// gives back the record if the queue is full or the thread is exiting
+pub fn try_push(record: FastLogRecord) -> Result<(), Box<FastLogRecord>> {
+    let mut record = Some(record);
+    let pushed = LOCAL.try_with(|shard| match shard.push(record.take().unwrap()) {
+        Ok(()) => true,
+        Err(v) => {
+            record = Some(v);
+            false
+        }
+    });
+    fence(Ordering::SeqCst);
+    wake();
+    match pushed {
+        Ok(true) => Ok(()),
+        _ => Err(Box::new(record.unwrap())),
+    }
+}
+
+/// block until the worker takes records of the full queue
+fn wait_space(shard: &ArrayQueue<FastLogRecord>) {
+    FULL_WAITERS.fetch_add(1, Ordering::SeqCst);
+    let guard = SPACE.0.lock().unwrap_or_else(|e| e.into_inner());
+    if shard.is_full() {
+        wake_now();
+        //the timeout is a guard,the worker notifies after draining
+        let _ = SPACE
+            .1
+            .wait_timeout(guard, Duration::from_millis(100))
+            .unwrap_or_else(|e| e.into_inner());
+    }
+    FULL_WAITERS.fetch_sub(1, Ordering::SeqCst);
+}
+
+/// wake the worker even if it is not sleeping yet
+fn wake_now() {
+    SLEEPING.store(false, Ordering::SeqCst);
+    let _ = WAKE.0.try_send(());
+}
+
+fn wake() {
+    if SLEEPING.swap(false, Ordering::SeqCst) {
+        let _ = WAKE.0.try_send(());
+    }
+}
+
+fn is_empty() -> bool {
+    SHARDS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .all(|v| v.is_empty())
+}
+
+/// wait until a queue or the channel has a record,or the `timeout`
+pub fn wait(recv: &Receiver<FastLogRecord>, timeout: Option<Duration>) {
+    let deadline = timeout.map(|v| Instant::now() + v);
+    loop {
+        if !recv.is_empty() || !is_empty() {
+            return;
+        }
+        SLEEPING.store(true, Ordering::SeqCst);
+        fence(Ordering::SeqCst);
+        if !recv.is_empty() || !is_empty() {
+            SLEEPING.store(false, Ordering::SeqCst);
+            return;
+        }
+        let mut select = Select::new();
+        select.recv(recv);
+        select.recv(&WAKE.1);
+        //the timeout is a guard,the producers wake the worker
+        let mut guard = Duration::from_secs(1);
+        if let Some(deadline) = deadline {
+            guard = guard.min(deadline.saturating_duration_since(Instant::now()));
+        }
+        let ready = select.ready_timeout(guard);
+        SLEEPING.store(false, Ordering::SeqCst);
+        if ready == Ok(1) {
+            let _ = WAKE.1.try_recv();
+        }
+        if deadline.is_some_and(|v| Instant::now() >= v) {
+            return;
+        }
+    }
+}
+
+/// move at most `max` records of the queues into `data`,
+/// and forget the queues of the exited threads once they are empty
+pub fn drain(data: &mut Vec<FastLogRecord>, max: usize) {
+    let mut exited = false;
+    let len = data.len();
+    for shard in SHARDS.read().unwrap_or_else(|e| e.into_inner()).iter() {
+        while data.len() < max {
+            match shard.pop() {
+                Some(v) => data.push(v),
+                None => break,
+            }
+        }
+        exited |= Arc::strong_count(shard) == 1 && shard.is_empty();
+    }
+    if data.len() > len && FULL_WAITERS.load(Ordering::SeqCst) > 0 {
+        let _guard = SPACE.0.lock().unwrap_or_else(|e| e.into_inner());
+        SPACE.1.notify_all();
+    }
+    if exited {
+        SHARDS
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|v| Arc::strong_count(v) > 1 || !v.is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::fast_log::level_filter;
+use crate::runtime::Instant;
+use log::{LevelFilter, Record};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// a named sub-logger(per tenant/plugin) sharing the pipeline of the logger.
+/// records whose target is the name or starts with "name::" belong to it,
+/// and are checked by its own level(within the global level),rate quota and byte budget.
+/// for example:
+/// ```rust
+/// use std::time::Duration;
+/// use fast_log::sub_logger::SubLogger;
+/// let plugin = fast_log::sub_logger::register(
+///     SubLogger::new("plugin_a")
+///         .level(log::LevelFilter::Info)
+///         .quota(1000, 1024 * 1024, Duration::from_secs(1)),
+/// );
+/// log::info!(target: "plugin_a", "hello");
+/// println!("{:?}", plugin.metrics());
+/// ```
+pub struct SubLogger {
+    pub name: String,
+    level: AtomicI32,
+    /// max records of each interval,0 is unlimited
+    pub max_records: u64,
+    /// max bytes of each interval,0 is unlimited
+    pub max_bytes: u64,
+    pub interval: Duration,
+    window: Mutex<QuotaWindow>,
+    accepted: AtomicU64,
+    dropped: AtomicU64,
+    bytes: AtomicU64,
+}
+
+struct QuotaWindow {
+    start: Instant,
+    records: u64,
+    bytes: u64,
+}
+
+/// the counters of a sub-logger
+#[derive(Clone, Debug, Default)]
+pub struct SubLoggerMetrics {
+    pub accepted: u64,
+    /// records dropped by the quota or the byte budget
+    pub dropped: u64,
+    /// bytes of the accepted messages
+    pub bytes: u64,
+}
+
+impl SubLogger {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            level: AtomicI32::new(LevelFilter::Trace as i32),
+            max_records: 0,
+            max_bytes: 0,
+            interval: Duration::from_secs(1),
+            window: Mutex::new(QuotaWindow {
+                start: Instant::now(),
+                records: 0,
+                bytes: 0,
+            }),
+            accepted: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+        }
+    }
+
+    pub fn level(self, level: LevelFilter) -> Self {
+        self.set_level(level);
+        self
+    }
+
+    /// at most `max_records` records and `max_bytes` bytes of each `interval`,0 is unlimited
+    pub fn quota(mut self, max_records: u64, max_bytes: u64, interval: Duration) -> Self {
+        self.max_records = max_records;
+        self.max_bytes = max_bytes;
+        self.interval = interval;
+        self
+    }
+
+    pub fn set_level(&self, level: LevelFilter) {
+        self.level.store(level as i32, Ordering::Relaxed);
+    }
+
+    pub fn get_level(&self) -> LevelFilter {
+        level_filter(self.level.load(Ordering::Relaxed))
+    }
+
+    pub fn metrics(&self) -> SubLoggerMetrics {
+        SubLoggerMetrics {
+            accepted: self.accepted.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// is the target belong to this sub-logger
+    pub fn contains(&self, target: &str) -> bool {
+        target == self.name
+            || (target.starts_with(&self.name) && target[self.name.len()..].starts_with("::"))
+    }
+
+    /// return true if the record should be dropped
+    pub fn filter(&self, record: &Record) -> bool {
+        if record.level() > self.get_level() {
+            return true;
+        }
+        let len = match record.args().as_str() {
+            Some(s) => s.len(),
+            None => record.args().to_string().len(),
+        } as u64;
+        if self.max_records > 0 || self.max_bytes > 0 {
+            let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            if now.duration_since(window.start) >= self.interval {
+                window.start = now;
+                window.records = 0;
+                window.bytes = 0;
+            }
+            if (self.max_records > 0 && window.records >= self.max_records)
+                || (self.max_bytes > 0 && window.bytes + len > self.max_bytes)
+            {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                return true;
+            }
+            window.records += 1;
+            window.bytes += len;
+        }
+        self.accepted.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(len, Ordering::Relaxed);
+        false
+    }
+}
+
+static HAS_SUB_LOGGERS: AtomicBool = AtomicBool::new(false);
+static SUB_LOGGERS: RwLock<Vec<Arc<SubLogger>>> = RwLock::new(vec![]);
+
+/// register a sub-logger,a registered sub-logger with the same name is replaced
+pub fn register(sub_logger: SubLogger) -> Arc<SubLogger> {
+    let sub_logger = Arc::new(sub_logger);
+    let mut subs = SUB_LOGGERS.write().unwrap_or_else(|e| e.into_inner());
+    subs.retain(|v| v.name != sub_logger.name);
+    subs.push(sub_logger.clone());
+    //the longer name is matched first
+    subs.sort_by_key(|v| std::cmp::Reverse(v.name.len()));
+    HAS_SUB_LOGGERS.store(true, Ordering::Relaxed);
+    sub_logger
+}
+
+/// get a registered sub-logger by name
+pub fn get(name: &str) -> Option<Arc<SubLogger>> {
+    let subs = SUB_LOGGERS.read().unwrap_or_else(|e| e.into_inner());
+    subs.iter().find(|v| v.name == name).cloned()
+}
+
+/// return true if the record is dropped by its sub-logger
+pub(crate) fn filter(record: &Record) -> bool {
+    if !HAS_SUB_LOGGERS.load(Ordering::Relaxed) {
+        return false;
+    }
+    let subs = SUB_LOGGERS.read().unwrap_or_else(|e| e.into_inner());
+    for x in subs.iter() {
+        if x.contains(record.target()) {
+            return x.filter(record);
+        }
+    }
+    false
+}
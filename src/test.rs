@@ -0,0 +1,218 @@
+use crate::appender::{Command, FastLogRecord, LogAppender};
+use crate::clock::TimeSource;
+use crate::error::LogError;
+use crate::Config;
+use log::Level;
+use once_cell::sync::{Lazy, OnceCell};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread::ThreadId;
+use std::time::{Duration, SystemTime};
+
+/// a record captured by [`CaptureLogger`]
+#[derive(Clone, Debug)]
+pub struct CapturedRecord {
+    pub level: Level,
+    pub target: String,
+    pub args: String,
+    pub formated: String,
+}
+
+type Captured = Arc<Mutex<Vec<CapturedRecord>>>;
+
+/// the capture of each test thread
+static CAPTURES: Lazy<Mutex<HashMap<ThreadId, Captured>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static INSTALLED: OnceCell<Result<(), LogError>> = OnceCell::new();
+
+struct CaptureAppender {}
+
+impl LogAppender for CaptureAppender {
+    fn do_logs(&self, records: &[FastLogRecord]) {
+        let captures = CAPTURES.lock().unwrap_or_else(|e| e.into_inner());
+        for x in records {
+            if x.command != Command::CommandRecord {
+                continue;
+            }
+            let captured = match x.thread_id.and_then(|id| captures.get(&id)) {
+                None => continue,
+                Some(v) => v,
+            };
+            captured
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(CapturedRecord {
+                    level: x.level,
+                    target: x.target.clone(),
+                    args: x.args.clone(),
+                    formated: x.formated.clone(),
+                });
+        }
+    }
+}
+
+/// capture the records logged by the current thread,for asserting log behavior in tests.
+/// fast_log is initialized once per process,so every `#[test]` can call `CaptureLogger::install()`,
+/// the records of the other tests running in parallel are not captured.
+/// the records of the threads the test spawns are missed unless they call [`CaptureLogger::attach`]
+/// for example:
+/// ```rust
+/// use fast_log::test::{contains, CaptureLogger};
+/// use log::Level;
+/// let logger = CaptureLogger::install().unwrap();
+/// log::warn!("connect timeout");
+/// logger.assert_logged(Level::Warn, contains("timeout"));
+/// ```
+pub struct CaptureLogger {
+    thread: ThreadId,
+    captured: Captured,
+}
+
+impl CaptureLogger {
+    /// init fast_log with the capture appender if not initialized,and capture the current thread.
+    /// fail if fast_log is already initialized with another config
+    pub fn install() -> Result<Self, LogError> {
+        INSTALLED
+            .get_or_init(|| {
+                crate::init(Config::new().with_source(true).custom(CaptureAppender {})).map(|_| ())
+            })
+            .clone()?;
+        let thread = std::thread::current().id();
+        let captured = Captured::default();
+        CAPTURES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(thread, captured.clone());
+        Ok(Self { thread, captured })
+    }
+
+    /// capture the records of the current thread into this capture too,until the returned capture drops.
+    /// for the threads spawned by the test:
+    /// ```rust
+    /// use fast_log::test::{eq, CaptureLogger};
+    /// use log::Level;
+    /// let logger = CaptureLogger::install().unwrap();
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| {
+    ///         let _attached = logger.attach();
+    ///         log::info!("from the worker");
+    ///     });
+    /// });
+    /// logger.assert_logged(Level::Info, eq("from the worker"));
+    /// ```
+    pub fn attach(&self) -> Self {
+        let thread = std::thread::current().id();
+        CAPTURES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(thread, self.captured.clone());
+        Self {
+            thread,
+            captured: self.captured.clone(),
+        }
+    }
+
+    /// the captured records,flushes the logger first
+    pub fn records(&self) -> Vec<CapturedRecord> {
+        log::logger().flush();
+        self.captured
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn clear(&self) {
+        log::logger().flush();
+        self.captured
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// is any record at `level` matching `matcher` captured
+    pub fn logged(&self, level: Level, matcher: impl Fn(&str) -> bool) -> bool {
+        self.records()
+            .iter()
+            .any(|x| x.level == level && matcher(&x.args))
+    }
+
+    /// panic if no record at `level` matches `matcher`
+    pub fn assert_logged(&self, level: Level, matcher: impl Fn(&str) -> bool) {
+        if !self.logged(level, matcher) {
+            panic!(
+                "no matching {} record is logged,the captured records:\n{:#?}",
+                level,
+                self.records()
+            );
+        }
+    }
+
+    /// panic if any record at `level` matches `matcher`
+    pub fn assert_not_logged(&self, level: Level, matcher: impl Fn(&str) -> bool) {
+        if self.logged(level, matcher) {
+            panic!("a matching {} record is logged", level);
+        }
+    }
+}
+
+impl Drop for CaptureLogger {
+    fn drop(&mut self) {
+        //the records of the thread are captured before it is removed
+        log::logger().flush();
+        CAPTURES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.thread);
+    }
+}
+
+/// match the messages containing `v`
+pub fn contains(v: &str) -> impl Fn(&str) -> bool + '_ {
+    move |args: &str| args.contains(v)
+}
+
+/// match the messages equal to `v`
+pub fn eq(v: &str) -> impl Fn(&str) -> bool + '_ {
+    move |args: &str| args == v
+}
+
+/// a manual clock for the deterministic tests of the timestamps,the formats and the time rolling,
+/// the clones share the time.
+/// for example:
+/// ```rust
+/// use fast_log::clock::{set_clock, Clock};
+/// use fast_log::test::MockClock;
+/// use std::time::{Duration, UNIX_EPOCH};
+/// let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(1_660_902_827));
+/// set_clock(Clock::custom(clock.clone()));
+/// clock.advance(Duration::from_millis(798));
+/// assert_eq!(fast_log::now(), UNIX_EPOCH + Duration::from_millis(1_660_902_827_798));
+/// set_clock(Clock::System);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MockClock {
+    now: Arc<Mutex<SystemTime>>,
+}
+
+impl MockClock {
+    pub fn new(now: SystemTime) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    pub fn set(&self, now: SystemTime) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) += duration;
+    }
+}
+
+impl TimeSource for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
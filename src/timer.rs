@@ -0,0 +1,64 @@
+use crate::runtime::Instant;
+use log::{Level, Record};
+use std::fmt;
+use std::time::Duration;
+
+/// log the start of a scope,and the finish with the elapsed time(the key-value `elapsed_us`) on drop,
+/// see [`timed!`](crate::timed)
+/// for example:
+/// ```rust
+/// use fast_log::timer::ScopeTimer;
+/// fn load_config() {
+///     let _timer = ScopeTimer::new(module_path!(), log::Level::Debug, "load_config");
+///     //...
+/// }
+/// ```
+pub struct ScopeTimer {
+    target: &'static str,
+    level: Level,
+    name: String,
+    start: Instant,
+}
+
+impl ScopeTimer {
+    pub fn new(target: &'static str, level: Level, name: &str) -> Self {
+        let timer = Self {
+            target,
+            level,
+            name: name.to_string(),
+            start: Instant::now(),
+        };
+        timer.log(format_args!("{} started", name), &[]);
+        timer
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// the module path of the record is the target,not this module
+    fn log(&self, args: fmt::Arguments, kv: &[(&str, u64)]) {
+        if self.level > log::max_level() {
+            return;
+        }
+        log::logger().log(
+            &Record::builder()
+                .level(self.level)
+                .target(self.target)
+                .module_path_static(Some(self.target))
+                .args(args)
+                .key_values(&kv)
+                .build(),
+        );
+    }
+}
+
+impl Drop for ScopeTimer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        self.log(
+            format_args!("{} finished in {:?}", self.name, elapsed),
+            &[("elapsed_us", elapsed.as_micros() as u64)],
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use crate::diagnostic;
+use crossbeam_channel::{bounded, RecvTimeoutError, Sender};
+use log::Level;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+/// polls the config file and stops on drop,see [`watch`]
+pub struct Watcher {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        //the watcher thread exits when the channel disconnects
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// poll the config file every `interval`,reload the running logger when the file changes,see [`crate::reload`].
+/// the queued records are written before the old logger exits,an invalid file is reported and the running logger is kept.
+/// you need enable fast_log = { ... ,features=["watch"]}
+/// ```rust,no_run
+/// use fast_log::Config;
+/// use std::time::Duration;
+/// fast_log::init(Config::from_file("log.toml").unwrap()).unwrap();
+/// let _watcher = fast_log::watch("log.toml", Duration::from_secs(5));
+/// ```
+pub fn watch(path: &str, interval: Duration) -> Watcher {
+    let path = path.to_string();
+    let (stop, stopped) = bounded::<()>(0);
+    let handle = std::thread::spawn(move || {
+        let mut last = stamp(&path);
+        while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+            let now = stamp(&path);
+            if now.is_none() || now == last {
+                continue;
+            }
+            last = now;
+            match crate::reload(&path) {
+                Ok(_) => diagnostic::report(
+                    Level::Info,
+                    &format!("[fast_log] reloaded the config {}", path),
+                ),
+                Err(e) => diagnostic::error(e),
+            }
+        }
+    });
+    Watcher {
+        stop: Some(stop),
+        handle: Some(handle),
+    }
+}
+
+fn stamp(path: &str) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}